@@ -0,0 +1,89 @@
+//! AI 分析结果的磁盘缓存，按提示词哈希索引，避免重复请求
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// 缓存有效期
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 最多保留的缓存条目数
+const MAX_CACHE_ENTRIES: usize = 50;
+
+pub struct AnalysisCache {
+    dir: PathBuf,
+}
+
+impl AnalysisCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// 由若干部分（模型、系统提示词、提示词等）计算缓存键
+    pub fn key(parts: &[&str]) -> String {
+        // FNV-1a 64 位，结果跨版本稳定
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for part in parts {
+            for byte in part.bytes().chain(std::iter::once(0)) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.txt", key))
+    }
+
+    /// 读取未过期的缓存
+    pub fn get(&self, key: &str) -> Option<String> {
+        let path = self.entry_path(key);
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        if is_expired(modified) {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        fs::read_to_string(&path).ok()
+    }
+
+    /// 写入缓存并清理过期/超量条目，失败只记录日志
+    pub fn put(&self, key: &str, content: &str) {
+        if let Err(e) = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.entry_path(key), content))
+        {
+            log::warn!("写入 AI 缓存失败: {}", e);
+            return;
+        }
+        self.prune();
+    }
+
+    /// 删除过期条目，并只保留最新的 MAX_CACHE_ENTRIES 条
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut files: Vec<(PathBuf, SystemTime)> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "txt"))
+            .filter_map(|e| {
+                let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+                Some((e.path(), modified))
+            })
+            .collect();
+
+        files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        for (i, (path, modified)) in files.iter().enumerate() {
+            if i >= MAX_CACHE_ENTRIES || is_expired(*modified) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+fn is_expired(modified: SystemTime) -> bool {
+    SystemTime::now()
+        .duration_since(modified)
+        .map(|age| age > CACHE_TTL)
+        .unwrap_or(false)
+}
@@ -0,0 +1,77 @@
+//! 命令行参数
+
+use crate::package_manager::RemoveVariant;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "lian", version, about = "基于 AI 的 TUI 包管理器")]
+pub struct Cli {
+    /// 使用指定的配置文件（默认 $XDG_CONFIG_HOME/lian/config.toml）
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// 使用配置文件中 [profiles.<name>] 的配置档案
+    #[arg(long, value_name = "NAME", global = true)]
+    pub profile: Option<String>,
+
+    /// 不启动 TUI，以 JSON 输出操作结果（包管理器输出转到 stderr）
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// 不带子命令时启动 TUI
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 系统更新 (-Syu)，完成后输出显式安装包变化与 AI 分析
+    Update,
+    /// 安装软件包 (-S)
+    Install {
+        #[arg(required = true, value_name = "PKG")]
+        packages: Vec<String>,
+        /// 标记为显式安装 (--asexplicit)
+        #[arg(long)]
+        asexplicit: bool,
+    },
+    /// 卸载软件包
+    Remove {
+        #[arg(required = true, value_name = "PKG")]
+        packages: Vec<String>,
+        /// 卸载方式: rns (删除配置和孤立依赖) | rs (保留配置) | r (保留依赖和配置)
+        #[arg(long, value_name = "MODE", default_value = "rns", value_parser = parse_remove_variant)]
+        mode: RemoveVariant,
+    },
+    /// 搜索软件包
+    Query {
+        keyword: String,
+        /// 只搜索已安装的包
+        #[arg(long)]
+        local: bool,
+    },
+    /// 查看最新的 AI 分析报告
+    Report {
+        /// 列出最近的报告而不是显示内容
+        #[arg(long)]
+        list: bool,
+        /// 列出的报告数量
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 20)]
+        limit: usize,
+        /// 比较两份报告：--list 中的序号（1 为最新）或文件路径
+        #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "list")]
+        diff: Option<Vec<String>>,
+        /// 按操作类型和月份汇总所有报告
+        #[arg(long, conflicts_with_all = ["list", "diff"])]
+        stats: bool,
+    },
+}
+
+/// 按报告操作标识 (rns / rs / r) 解析卸载方式，不区分大小写
+fn parse_remove_variant(s: &str) -> Result<RemoveVariant, String> {
+    RemoveVariant::ALL
+        .into_iter()
+        .find(|v| v.operation().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("未知的卸载方式 \"{}\"，可选 rns / rs / r", s))
+}
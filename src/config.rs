@@ -1,9 +1,147 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
+use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
+use std::sync::RwLock;
 
-const DEFAULT_API_URL: &str = "https://api.deepseek.com/chat/completions";
+/// OpenAI 兼容接口的对话路径
+const CHAT_COMPLETIONS_PATH: &str = "/chat/completions";
+
+/// Ollama 的对话路径
+const OLLAMA_CHAT_PATH: &str = "/api/chat";
+
+/// 系统密钥环中保存 API Key 的服务名和账户名
+const KEYRING_SERVICE: &str = "lian-pacman";
+const KEYRING_USER: &str = "api_key";
+
+/// 可选的分析输出语言（配置值, 显示名称）
+pub const ANALYSIS_LANGUAGES: [(&str, &str); 2] = [("zh", "中文"), ("en", "English")];
+
+/// 可选的界面语言（配置值, 显示名称）
+pub const UI_LANGUAGES: [(&str, &str); 2] = [("zh", "中文"), ("en", "English")];
+
+/// AI 服务商预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    DeepSeek,
+    OpenAI,
+    Moonshot,
+    /// 本地 Ollama（/api/chat，不需要 API Key）
+    Ollama,
+    /// 自定义：完全使用 api_url / model 配置
+    Custom,
+}
+
+impl Provider {
+    pub const ALL: [Provider; 5] = [
+        Provider::DeepSeek,
+        Provider::OpenAI,
+        Provider::Moonshot,
+        Provider::Ollama,
+        Provider::Custom,
+    ];
+
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::DeepSeek => "DeepSeek",
+            Provider::OpenAI => "OpenAI",
+            Provider::Moonshot => "Moonshot",
+            Provider::Ollama => "Ollama (本地)",
+            Provider::Custom => "自定义",
+        }
+    }
+
+    /// 预设 API 基础地址（不含 /chat/completions 或 /api/chat）
+    pub fn base_url(&self) -> &'static str {
+        match self {
+            Provider::DeepSeek | Provider::Custom => "https://api.deepseek.com",
+            Provider::OpenAI => "https://api.openai.com/v1",
+            Provider::Moonshot => "https://api.moonshot.cn/v1",
+            Provider::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// 对话接口路径
+    fn chat_path(&self) -> &'static str {
+        match self {
+            Provider::Ollama => OLLAMA_CHAT_PATH,
+            _ => CHAT_COMPLETIONS_PATH,
+        }
+    }
+
+    /// 是否需要 API Key
+    pub fn requires_api_key(&self) -> bool {
+        *self != Provider::Ollama
+    }
+
+    /// 预设默认模型
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Provider::DeepSeek | Provider::Custom => "deepseek-reasoner",
+            Provider::OpenAI => "gpt-4o-mini",
+            Provider::Moonshot => "moonshot-v1-8k",
+            Provider::Ollama => "qwen2.5:7b",
+        }
+    }
+}
+
+/// 报告文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    /// 结构化 JSON，便于脚本处理
+    Json,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::Markdown, ReportFormat::Json];
+
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "Markdown",
+            ReportFormat::Json => "JSON",
+        }
+    }
+
+    /// 报告文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Json => "json",
+        }
+    }
+}
+
+/// 报告保存方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportMode {
+    /// 每次操作单独保存为 YYYY/MM/DD/ 下的文件
+    #[default]
+    PerFile,
+    /// 追加到报告目录下的 history.md（JSON 格式为 history.jsonl）
+    Append,
+}
+
+impl ReportMode {
+    pub const ALL: [ReportMode; 2] = [ReportMode::PerFile, ReportMode::Append];
+
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportMode::PerFile => "每次单独文件",
+            ReportMode::Append => "追加到 history",
+        }
+    }
+}
 
 /// AI 分析开关配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +155,9 @@ pub struct AiConfig {
     /// 卸载软件包后 AI 分析 (默认关闭)
     #[serde(default)]
     pub remove: bool,
+    /// 缓存相同内容的分析结果 (默认开启)
+    #[serde(default = "default_true")]
+    pub cache: bool,
 }
 
 fn default_true() -> bool {
@@ -29,15 +170,114 @@ impl Default for AiConfig {
             update: true,
             install: false,
             remove: false,
+            cache: true,
         }
     }
 }
 
+/// 界面配色预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    /// 粉蓝配色 (MTF flag)
+    #[default]
+    Mtf,
+    Nord,
+    Gruvbox,
+    /// 使用终端自身的基础色，适合浅色终端
+    Terminal,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 4] = [
+        ThemePreset::Mtf,
+        ThemePreset::Nord,
+        ThemePreset::Gruvbox,
+        ThemePreset::Terminal,
+    ];
+
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemePreset::Mtf => "MTF (粉蓝)",
+            ThemePreset::Nord => "Nord",
+            ThemePreset::Gruvbox => "Gruvbox",
+            ThemePreset::Terminal => "终端配色",
+        }
+    }
+}
+
+/// 自定义颜色（"#rrggbb"），未设置的项使用预设
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeColors {
+    /// 主色：仓库名、未选中的标记、提示符
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+    /// 辅色：包名、版本、设置项标签
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<String>,
+    /// 选中行背景
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<String>,
+    /// 正文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// 次要信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dim: Option<String>,
+    /// 选中行内的描述文字
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ThemeColors {
+    pub fn is_empty(&self) -> bool {
+        self.entries().iter().all(|(_, v)| v.is_none())
+    }
+
+    /// (配置键, 值) 列表，用于校验
+    pub fn entries(&self) -> [(&'static str, Option<&str>); 6] {
+        [
+            ("primary", self.primary.as_deref()),
+            ("secondary", self.secondary.as_deref()),
+            ("selection", self.selection.as_deref()),
+            ("text", self.text.as_deref()),
+            ("dim", self.dim.as_deref()),
+            ("description", self.description.as_deref()),
+        ]
+    }
+}
+
+/// 解析 "#rrggbb" 颜色
+pub fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// AI 服务商预设，api_url / model 可覆盖预设值
+    #[serde(default)]
+    pub provider: Provider,
     pub model: String,
     pub temperature: f32,
     pub report_dir: PathBuf,
+    /// 报告格式："markdown" (默认) / "json"
+    #[serde(default)]
+    pub report_format: ReportFormat,
+    /// 报告保存方式："perfile" (默认) / "append"
+    #[serde(default)]
+    pub report_mode: ReportMode,
+    /// 报告文件名模板（不含扩展名），支持 {date} {time} {distro} {operation}
+    #[serde(default = "default_report_filename")]
+    pub report_filename: String,
+    /// 最多保留的报告文件数，超出时删除最旧的 (0 = 不限制)
+    #[serde(default)]
+    pub max_reports: usize,
     #[serde(default)]
     pub api_url: Option<String>,
     #[serde(default)]
@@ -46,27 +286,172 @@ pub struct Config {
     pub proxy: Option<String>,
     #[serde(default)]
     pub ai: AiConfig,
+    /// 包列表导出路径（支持 ~）
+    #[serde(default = "default_export_path")]
+    pub export_path: String,
+    /// 系统更新前执行的命令（如快照），失败时中止更新
+    #[serde(default)]
+    pub pre_update_command: Option<String>,
+    /// 提权命令："sudo" (默认) / "doas" / "pkexec" 等
+    #[serde(default = "default_privilege_command")]
+    pub privilege_command: String,
+    /// 更新 / 安装 / 卸载进行中刷新 sudo 凭证的间隔（秒），0 = 不刷新
+    #[serde(default = "default_sudo_keepalive_secs")]
+    pub sudo_keepalive_secs: u64,
+    /// 安装后根分区至少保留的空间 (MB)，不足时安装预览给出警告
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    /// 作为 system 角色发送的系统提示词，为空时不发送
+    #[serde(default = "default_system_prompt")]
+    pub system_prompt: String,
+    /// AI 分析输出语言："zh" (默认) / "en"
+    #[serde(default = "default_analysis_language")]
+    pub analysis_language: String,
+    /// 界面语言："zh" (默认) / "en"
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// 发送给 AI 前隐藏用户名和家目录 (默认开启)
+    #[serde(default = "default_true")]
+    pub redact_prompts: bool,
+    /// 更新 / 安装 / 卸载结束时发送桌面通知 (默认关闭)
+    #[serde(default)]
+    pub notifications: bool,
+    /// 每 1K token 的价格，用于估算分析费用（未设置时不显示）
+    #[serde(default)]
+    pub token_price: Option<f64>,
+    /// 配色预设："mtf" (默认) / "nord" / "gruvbox" / "terminal"
+    #[serde(default)]
+    pub theme: ThemePreset,
+    /// 覆盖预设中的单项颜色
+    #[serde(default, skip_serializing_if = "ThemeColors::is_empty")]
+    pub theme_colors: ThemeColors,
+    /// 命名配置档案 `[profiles.<name>]`，启用时覆盖顶层配置
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, toml::Table>,
+    /// 当前启用的配置档案（--profile）
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+}
+
+fn default_analysis_language() -> String {
+    "zh".to_string()
+}
+
+fn default_language() -> String {
+    "zh".to_string()
+}
+
+fn default_system_prompt() -> String {
+    "你是一个专业的 Linux 系统管理员和软件包分析专家。".to_string()
+}
+
+/// 默认报告目录 $XDG_DATA_HOME/lian/reports
+///
+/// 旧版本默认存到 ~/.lian/pacman；旧目录还在而新目录尚未创建时继续沿用，历史报告不会因升级而"消失"
+fn default_report_dir() -> PathBuf {
+    let dir = data_dir().join("reports");
+    let legacy = expand_tilde("~/.lian/pacman");
+    if !dir.exists() && legacy.is_dir() {
+        return legacy;
+    }
+    dir
+}
+
+pub fn default_report_filename() -> String {
+    "{operation}-{time}".to_string()
+}
+
+fn default_privilege_command() -> String {
+    "sudo".to_string()
 }
 
+fn default_sudo_keepalive_secs() -> u64 {
+    60
+}
+
+fn default_min_free_space_mb() -> u64 {
+    1024
+}
+
+fn default_export_path() -> String {
+    "~/lian-packages.txt".to_string()
+}
+
+/// 展开路径开头的 ~ 为 $HOME
+pub fn expand_tilde(path: &str) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    if path == "~" {
+        PathBuf::from(home)
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        PathBuf::from(home).join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// 有效的采样温度范围
+pub const TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+
 impl Default for Config {
     fn default() -> Self {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
         Self {
-            model: "deepseek-reasoner".to_string(),
+            provider: Provider::default(),
+            model: Provider::default().default_model().to_string(),
             temperature: 0.8,
-            report_dir: PathBuf::from(home).join(".lian/pacman"),
+            report_dir: default_report_dir(),
+            report_format: ReportFormat::default(),
+            report_mode: ReportMode::default(),
+            report_filename: default_report_filename(),
+            max_reports: 0,
             api_url: None,
             api_key: None,
             proxy: None,
             ai: AiConfig::default(),
+            export_path: default_export_path(),
+            pre_update_command: None,
+            privilege_command: default_privilege_command(),
+            sudo_keepalive_secs: default_sudo_keepalive_secs(),
+            min_free_space_mb: default_min_free_space_mb(),
+            system_prompt: default_system_prompt(),
+            analysis_language: default_analysis_language(),
+            language: default_language(),
+            token_price: None,
+            theme: ThemePreset::default(),
+            theme_colors: ThemeColors::default(),
+            redact_prompts: true,
+            notifications: false,
+            profiles: BTreeMap::new(),
+            active_profile: None,
         }
     }
 }
 
 impl Config {
-    /// 获取 API URL，优先配置文件，否则使用默认值
-    pub fn get_api_url(&self) -> &str {
-        self.api_url.as_deref().unwrap_or(DEFAULT_API_URL)
+    /// 获取完整的 API URL：优先配置的 api_url，否则使用服务商预设，统一补全对话路径
+    pub fn get_api_url(&self) -> String {
+        let base = self
+            .api_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(self.provider.base_url())
+            .trim_end_matches('/');
+        let path = self.provider.chat_path();
+        if base.ends_with(path) {
+            base.to_string()
+        } else {
+            format!("{}{}", base, path)
+        }
+    }
+
+    /// 实际使用的模型：未配置时使用服务商预设
+    pub fn get_model(&self) -> &str {
+        let model = self.model.trim();
+        if model.is_empty() {
+            self.provider.default_model()
+        } else {
+            model
+        }
     }
 
     /// 检查指定操作是否启用 AI 分析
@@ -79,29 +464,301 @@ impl Config {
         }
     }
 
-    pub fn load_or_default() -> Result<Self> {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let config_path = PathBuf::from(&home).join(".config/lian/config.toml");
+    /// AI 分析缓存目录（位于报告目录下）
+    pub fn cache_dir(&self) -> PathBuf {
+        self.report_dir.join(".cache")
+    }
+
+    /// 显式安装包列表的导出路径（已展开 ~）
+    pub fn explicit_export_path(&self) -> PathBuf {
+        expand_tilde(&self.export_path)
+    }
+
+    /// 外部 (AUR/本地) 包列表的导出路径：在文件名后追加 -foreign
+    pub fn foreign_export_path(&self) -> PathBuf {
+        let path = self.explicit_export_path();
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "lian-packages".to_string());
+        let filename = match path.extension() {
+            Some(ext) => format!("{}-foreign.{}", stem, ext.to_string_lossy()),
+            None => format!("{}-foreign", stem),
+        };
+        path.with_file_name(filename)
+    }
+
+    /// 将当前 API Key 存入系统密钥环，并从配置文件中移除
+    pub fn store_api_key_in_keyring(&mut self) -> Result<()> {
+        let key = self
+            .api_key
+            .clone()
+            .filter(|k| !k.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("当前没有可存入的 API Key"))?;
+        with_keyring(move |entry| entry.set_password(&key))?;
+        self.api_key = None;
+        self.write(true)
+    }
+
+    /// 恢复默认配置，保留 API Key 与配置档案（尚未写入文件）
+    pub fn reset_preserving_key(&mut self) {
+        let api_key = self.api_key.take();
+        let profiles = std::mem::take(&mut self.profiles);
+        let active_profile = self.active_profile.take();
+        *self = Self {
+            api_key,
+            profiles,
+            active_profile,
+            ..Self::default()
+        };
+    }
+
+    /// 配置文件对组/其他用户可读时返回其权限位（如 0o644）
+    pub fn insecure_permissions() -> Option<u32> {
+        let mode = fs::metadata(config_path()).ok()?.permissions().mode() & 0o777;
+        (mode & 0o077 != 0).then_some(mode)
+    }
+
+    /// 将配置文件权限改为 600（仅所有者可读写）
+    pub fn secure_permissions() -> Result<()> {
+        fs::set_permissions(config_path(), fs::Permissions::from_mode(0o600))?;
+        Ok(())
+    }
+
+    /// 检查配置值：温度范围、URL 格式、报告目录可写
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if !TEMPERATURE_RANGE.contains(&self.temperature) {
+            errors.push(format!("温度 {} 超出范围 (0.0 - 2.0)", self.temperature));
+        }
+
+        if let Some(url) = self.api_url.as_deref().filter(|u| !u.trim().is_empty()) {
+            match reqwest::Url::parse(url.trim()) {
+                Ok(u) if matches!(u.scheme(), "http" | "https") => {}
+                Ok(u) => errors.push(format!("API 地址协议不受支持: {}", u.scheme())),
+                Err(e) => errors.push(format!("API 地址格式错误 ({}): {}", url, e)),
+            }
+        }
+
+        if let Some(proxy) = self.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+            if let Err(e) = reqwest::Proxy::all(proxy.trim()) {
+                errors.push(format!("代理地址格式错误 ({}): {}", proxy, e));
+            }
+        }
+
+        for (key, value) in self.theme_colors.entries() {
+            if let Some(value) = value.filter(|v| parse_hex_color(v).is_none()) {
+                errors.push(format!("颜色 theme_colors.{} 格式错误 ({})，应为 #rrggbb", key, value));
+            }
+        }
+
+        if let Err(e) = check_dir_writable(&self.report_dir) {
+            errors.push(format!("报告目录不可写 ({}): {}", self.report_dir.display(), e));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 加载配置文件，profile 为 Some 时将 `[profiles.<name>]` 叠加到顶层配置上
+    pub fn load_or_default(profile: Option<&str>) -> Result<Self> {
+        let config_path = config_path();
 
         if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)?;
+            let mut table: toml::Table = toml::from_str(&content)?;
+            if let Some(name) = profile {
+                let overlay = table
+                    .get("profiles")
+                    .and_then(|p| p.get(name))
+                    .and_then(|v| v.as_table())
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("配置档案不存在: {}", name))?;
+                merge_table(&mut table, overlay);
+            }
+            let mut config: Config = toml::Value::Table(table).try_into()?;
+            config.active_profile = profile.map(str::to_string);
+            // 加载时只警告，不阻止启动；问题会在设置页中显示
+            if let Err(errors) = config.validate() {
+                for e in &errors {
+                    log::warn!("配置无效: {}", e);
+                }
+            }
             Ok(config)
         } else {
+            if let Some(name) = profile {
+                anyhow::bail!("配置档案不存在: {}", name);
+            }
             let config = Self::default();
             config.save()?;
             Ok(config)
         }
     }
 
-    /// 保存配置到 ~/.config/lian/config.toml
+    /// 保存配置到 [`config_path`]（配置无效时拒绝保存）
+    ///
+    /// 启用配置档案时，只把与顶层配置不同的项写入 `[profiles.<name>]`。
     pub fn save(&self) -> Result<()> {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let config_dir = PathBuf::from(&home).join(".config/lian");
-        fs::create_dir_all(&config_dir)?;
-        let config_path = config_dir.join("config.toml");
-        let content = toml::to_string_pretty(self)?;
-        fs::write(config_path, content)?;
+        self.write(false)
+    }
+
+    /// 写入配置文件；drop_api_key 时从顶层和当前档案中都删除 api_key
+    ///
+    /// 档案模式只写差异叠加层，api_key 为 None 不会覆盖顶层的明文 Key，需显式删除
+    fn write(&self, drop_api_key: bool) -> Result<()> {
+        if let Err(errors) = self.validate() {
+            anyhow::bail!("配置无效: {}", errors.join("; "));
+        }
+        let config_path = config_path();
+        if let Some(dir) = config_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let content = match &self.active_profile {
+            None => toml::to_string_pretty(self)?,
+            Some(name) => {
+                let mut root: toml::Table = match fs::read_to_string(&config_path) {
+                    Ok(content) => toml::from_str(&content)?,
+                    Err(_) => toml::Table::new(),
+                };
+                if drop_api_key {
+                    root.remove("api_key");
+                }
+                let current = toml::Table::try_from(self)?;
+                let overlay: toml::Table = current
+                    .into_iter()
+                    .filter(|(k, v)| k != "profiles" && root.get(k) != Some(v))
+                    .filter(|(k, _)| !drop_api_key || k != "api_key")
+                    .collect();
+                let profiles = root
+                    .entry("profiles")
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                if let Some(profiles) = profiles.as_table_mut() {
+                    profiles.insert(name.clone(), toml::Value::Table(overlay));
+                }
+                toml::to_string_pretty(&root)?
+            }
+        };
+        write_private(&config_path, &content)?;
         Ok(())
     }
 }
+
+/// 写入文件；新建时权限为 600，已存在的文件保留原权限
+fn write_private(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())
+}
+
+/// 从系统密钥环读取 API Key，密钥环不可用或未保存时返回 None
+pub fn keyring_api_key() -> Option<String> {
+    match with_keyring(|entry| entry.get_password()) {
+        Ok(key) if !key.is_empty() => Some(key),
+        Ok(_) => None,
+        Err(e) => {
+            log::info!("未从密钥环读取到 API Key: {}", e);
+            None
+        }
+    }
+}
+
+/// 在独立线程中访问密钥环（后端自带运行时，不能在 tokio 工作线程中直接阻塞调用）
+fn with_keyring<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(keyring::Entry) -> keyring::Result<T> + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+        f(entry)
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("密钥环访问线程异常退出"))?
+    .map_err(|e| anyhow::anyhow!("密钥环不可用: {}", e))
+}
+
+/// --config 指定的配置文件路径，未指定时为空
+static CONFIG_PATH_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// 改用指定的配置文件（--config），需在加载配置前调用
+pub fn set_config_path(path: PathBuf) {
+    if let Ok(mut current) = CONFIG_PATH_OVERRIDE.write() {
+        *current = Some(path);
+    }
+}
+
+/// 配置文件路径：--config 指定的路径，否则为 $XDG_CONFIG_HOME/lian/config.toml
+pub fn config_path() -> PathBuf {
+    CONFIG_PATH_OVERRIDE
+        .read()
+        .ok()
+        .and_then(|p| p.clone())
+        .unwrap_or_else(|| xdg_dir("XDG_CONFIG_HOME", ".config").join("lian/config.toml"))
+}
+
+/// 数据目录 $XDG_DATA_HOME/lian，默认报告目录位于其下
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", ".local/share").join("lian")
+}
+
+/// XDG 基础目录：环境变量为绝对路径时使用，否则回退到 $HOME 下的默认位置
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .unwrap_or_else(|| expand_tilde("~").join(fallback))
+}
+
+/// 配置目录（config.toml 所在目录），也用于保存历史记录等数据
+pub fn config_dir() -> PathBuf {
+    config_path()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// 递归合并 TOML 表：overlay 中的值覆盖 base，子表逐项合并
+fn merge_table(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_sub)), toml::Value::Table(overlay_sub)) => {
+                merge_table(base_sub, overlay_sub);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// 检查目录是否可写，只读检查：目录不存在时检查最近的已存在上级目录
+///
+/// 目录本身在真正保存报告时才创建，拼错的路径不会因为校验而落到磁盘上
+fn check_dir_writable(dir: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    let existing = dir
+        .ancestors()
+        .find(|p| !p.as_os_str().is_empty() && p.exists())
+        .unwrap_or_else(|| std::path::Path::new("."));
+    if !existing.is_dir() {
+        return Err(std::io::Error::other(format!("{} 不是目录", existing.display())));
+    }
+    let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: c_path 是以 NUL 结尾的有效 C 字符串，access 不会保留该指针
+    if unsafe { libc::access(c_path.as_ptr(), libc::W_OK | libc::X_OK) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
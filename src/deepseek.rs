@@ -1,6 +1,9 @@
+use crate::cache::AnalysisCache;
+use crate::config::{Config, Provider};
 use anyhow::Result;
-use reqwest::Client;
+use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Debug, Serialize)]
 struct ChatRequest {
@@ -8,18 +11,37 @@ struct ChatRequest {
     messages: Vec<Message>,
     temperature: f32,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
+/// 对话消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: String,
+}
+
+impl Message {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct ChatResponse {
     choices: Vec<Choice>,
-    usage: Option<Usage>,
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,85 +49,497 @@ struct Choice {
     message: Message,
 }
 
+/// 流式响应中的单个 SSE 数据块
 #[derive(Debug, Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: Delta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+/// Ollama /api/chat 请求
+#[derive(Debug, Serialize)]
+struct OllamaRequest {
+    model: String,
+    messages: Vec<Message>,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+}
+
+/// Ollama 响应（非流式为单个对象，流式为逐行 JSON）
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    message: Option<Message>,
+    #[serde(default)]
+    done: bool,
+    prompt_eval_count: Option<u32>,
+    eval_count: Option<u32>,
+}
+
+impl OllamaResponse {
+    fn usage(&self) -> Option<TokenUsage> {
+        let prompt_tokens = self.prompt_eval_count?;
+        let completion_tokens = self.eval_count.unwrap_or(0);
+        Some(TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        })
+    }
+}
+
+/// Token 用量
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Footer 显示文本；配置了每千 token 价格时附带费用估算
+    pub fn footer_text(&self, price_per_1k: Option<f64>) -> String {
+        let mut text = format!(
+            "Token: {} (输入 {} / 输出 {})",
+            self.total_tokens, self.prompt_tokens, self.completion_tokens
+        );
+        if let Some(price) = price_per_1k {
+            text.push_str(&format!(" ≈ {:.4}", self.total_tokens as f64 / 1000.0 * price));
+        }
+        text
+    }
+}
+
+/// 流式分析的 StreamOptions（请求在末尾返回 usage）
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
+}
+
+/// 构建 HTTP 客户端，配置了代理时走代理（无效代理回退为直连）
+pub fn build_http_client(proxy: Option<&str>) -> Client {
+    if let Some(proxy_url) = proxy {
+        match reqwest::Proxy::all(proxy_url) {
+            Ok(p) => Client::builder().proxy(p).build().unwrap_or_else(|_| Client::new()),
+            Err(e) => {
+                log::warn!("代理配置无效 ({}): {}", proxy_url, e);
+                Client::new()
+            }
+        }
+    } else {
+        Client::new()
+    }
 }
 
+/// OpenAI 兼容的 AI 客户端（DeepSeek / OpenAI / Ollama 等）
+///
+/// 通常用 [`AiClient::from_config`] 创建；[`AiClient::analyze_update`] 一次性返回分析结果，
+/// [`AiClient::analyze_update_stream`] 边生成边发送片段，[`AiClient::chat`] 用于多轮追问
 pub struct AiClient {
     client: Client,
     api_key: String,
     api_url: String,
+    system_prompt: Option<String>,
+    provider: Provider,
+    cache: Option<AnalysisCache>,
+    redact: bool,
 }
 
 impl AiClient {
     pub fn new(api_key: String, api_url: String, proxy: Option<&str>) -> Self {
-        let client = if let Some(proxy_url) = proxy {
-            match reqwest::Proxy::all(proxy_url) {
-                Ok(p) => Client::builder().proxy(p).build().unwrap_or_else(|_| Client::new()),
-                Err(e) => {
-                    log::warn!("代理配置无效 ({}): {}", proxy_url, e);
-                    Client::new()
-                }
-            }
-        } else {
-            Client::new()
-        };
+        // 本地服务不走代理
+        let proxy = if is_local_url(&api_url) { None } else { proxy };
+        let client = build_http_client(proxy);
         Self {
             client,
             api_key,
             api_url,
+            system_prompt: None,
+            provider: Provider::default(),
+            cache: None,
+            redact: false,
         }
     }
 
-    pub async fn analyze_update(
-        &self,
-        prompt: &str,
-        model: &str,
-        temperature: f32,
-    ) -> Result<String> {
-        let request = ChatRequest {
-            model: model.to_string(),
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt.to_string(),
-            }],
-            temperature,
-            stream: false,
+    /// 按配置创建客户端（地址、代理、服务商、系统提示词、缓存）
+    pub fn from_config(api_key: &str, config: &Config) -> Self {
+        let client = Self::new(api_key.to_string(), config.get_api_url(), config.proxy.as_deref())
+            .with_provider(config.provider)
+            .with_system_prompt(&config.system_prompt)
+            .with_redaction(config.redact_prompts);
+        if config.ai.cache {
+            client.with_cache(AnalysisCache::new(config.cache_dir()))
+        } else {
+            client
+        }
+    }
+
+    /// 发送前脱敏：隐藏用户名和家目录
+    pub fn with_redaction(mut self, enabled: bool) -> Self {
+        self.redact = enabled;
+        self
+    }
+
+    /// 启用分析结果缓存
+    pub fn with_cache(mut self, cache: AnalysisCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// 缓存键：覆盖所有影响输出的请求参数
+    fn cache_key(&self, prompt: &str, model: &str, temperature: f32) -> String {
+        AnalysisCache::key(&[
+            &self.api_url,
+            model,
+            &temperature.to_string(),
+            self.system_prompt.as_deref().unwrap_or(""),
+            // 脱敏前后发送的内容不同，不能共用缓存
+            if self.redact { "redact" } else { "raw" },
+            prompt,
+        ])
+    }
+
+    /// 命中缓存时返回结果（命中时不产生 token 用量）
+    fn cached(&self, key: &str) -> Option<String> {
+        let cached = self.cache.as_ref()?.get(key)?;
+        log::info!("AI 分析命中缓存: {}", key);
+        Some(cached)
+    }
+
+    fn store(&self, key: &str, content: &str) {
+        if let Some(cache) = &self.cache {
+            cache.put(key, content);
+        }
+    }
+
+    /// 设置服务商，决定请求/响应格式（OpenAI 兼容或 Ollama）
+    pub fn with_provider(mut self, provider: Provider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// 设置 system 角色提示词（空白内容视为未设置）
+    pub fn with_system_prompt(mut self, prompt: &str) -> Self {
+        let prompt = prompt.trim();
+        self.system_prompt = if prompt.is_empty() {
+            None
+        } else {
+            Some(prompt.to_string())
         };
+        self
+    }
 
-        let response = self
+    /// 构建消息列表：可选的 system 提示词 + 对话消息
+    fn build_messages(&self, conversation: Vec<Message>) -> Vec<Message> {
+        let mut messages = Vec::with_capacity(conversation.len() + 1);
+        if let Some(system) = &self.system_prompt {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: system.clone(),
+            });
+        }
+        if self.redact {
+            messages.extend(conversation.into_iter().map(|m| Message {
+                content: redact(&m.content),
+                ..m
+            }));
+        } else {
+            messages.extend(conversation);
+        }
+        messages
+    }
+
+    /// 发送 JSON 请求并检查 HTTP 状态
+    async fn send_json<T: Serialize>(&self, body: &T) -> Result<Response> {
+        let mut request = self
             .client
             .post(&self.api_url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request)
-            .send()
-            .await?;
+            .header("Content-Type", "application/json");
+        if !self.api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+        let response = request.json(body).send().await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await?;
             anyhow::bail!("API 请求失败 ({}): {}", status, error_text);
         }
+        Ok(response)
+    }
 
-        let chat_response: ChatResponse = response.json().await?;
+    /// 按服务商格式发送对话请求
+    async fn send_chat(
+        &self,
+        conversation: Vec<Message>,
+        model: &str,
+        temperature: f32,
+        stream: bool,
+    ) -> Result<Response> {
+        let messages = self.build_messages(conversation);
+        let model = model.to_string();
+        if self.provider == Provider::Ollama {
+            self.send_json(&OllamaRequest {
+                model,
+                messages,
+                stream,
+                options: OllamaOptions { temperature },
+            })
+            .await
+        } else {
+            self.send_json(&ChatRequest {
+                model,
+                messages,
+                temperature,
+                stream,
+                stream_options: stream.then_some(StreamOptions { include_usage: true }),
+            })
+            .await
+        }
+    }
 
-        if let Some(choice) = chat_response.choices.first() {
-            if let Some(usage) = chat_response.usage {
-                log::info!(
-                    "Token 使用: 输入={}, 输出={}, 总计={}",
-                    usage.prompt_tokens,
-                    usage.completion_tokens,
-                    usage.total_tokens
-                );
-            }
+    /// 分析并返回文本和 token 用量（服务端未返回用量时为 None）
+    pub async fn analyze_update(
+        &self,
+        prompt: &str,
+        model: &str,
+        temperature: f32,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        let key = self.cache_key(prompt, model, temperature);
+        if let Some(cached) = self.cached(&key) {
+            return Ok((cached, None));
+        }
 
-            Ok(choice.message.content.clone())
+        let (content, usage) = self
+            .complete(vec![Message::user(prompt)], model, temperature)
+            .await?;
+        self.store(&key, &content);
+        Ok((content, usage))
+    }
+
+    /// 多轮对话：发送完整的对话历史，返回回复文本和 token 用量
+    pub async fn chat(
+        &self,
+        messages: Vec<Message>,
+        model: &str,
+        temperature: f32,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        self.complete(messages, model, temperature).await
+    }
+
+    /// 非流式请求，按服务商格式解析回复
+    async fn complete(
+        &self,
+        conversation: Vec<Message>,
+        model: &str,
+        temperature: f32,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        let response = self.send_chat(conversation, model, temperature, false).await?;
+
+        let (content, usage) = if self.provider == Provider::Ollama {
+            let resp: OllamaResponse = response.json().await?;
+            let usage = resp.usage();
+            (resp.message.map(|m| m.content), usage)
         } else {
-            anyhow::bail!("API 返回了空响应")
+            let resp: ChatResponse = response.json().await?;
+            (resp.choices.into_iter().next().map(|c| c.message.content), resp.usage)
+        };
+
+        if let Some(usage) = &usage {
+            log_usage(usage);
         }
+        match content {
+            Some(content) => Ok((content, usage)),
+            None => anyhow::bail!("API 返回了空响应"),
+        }
+    }
+
+    /// 流式分析：每收到一段增量文本就发送到 chunk_tx，结束后返回完整文本和 token 用量
+    pub async fn analyze_update_stream(
+        &self,
+        prompt: &str,
+        model: &str,
+        temperature: f32,
+        chunk_tx: UnboundedSender<String>,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        let key = self.cache_key(prompt, model, temperature);
+        if let Some(cached) = self.cached(&key) {
+            let _ = chunk_tx.send(cached.clone());
+            return Ok((cached, None));
+        }
+
+        let mut response = self
+            .send_chat(vec![Message::user(prompt)], model, temperature, true)
+            .await?;
+        let mut full = String::new();
+        let mut usage = None;
+        // 按字节缓冲，避免 UTF-8 字符被网络分块截断
+        let mut buf: Vec<u8> = Vec::new();
+
+        'outer: while let Some(bytes) = response.chunk().await? {
+            buf.extend_from_slice(&bytes);
+            while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+                let raw: Vec<u8> = buf.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&raw);
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+
+                let (content, done) = if self.provider == Provider::Ollama {
+                    // Ollama: 每行一个 JSON 对象
+                    match serde_json::from_str::<OllamaResponse>(line) {
+                        Ok(chunk) => {
+                            if chunk.done {
+                                usage = chunk.usage();
+                            }
+                            (chunk.message.map(|m| m.content), chunk.done)
+                        }
+                        Err(e) => {
+                            log::warn!("无法解析流式数据块: {} ({})", e, line);
+                            continue;
+                        }
+                    }
+                } else {
+                    // OpenAI 兼容: SSE "data: {...}"，以 [DONE] 结束
+                    let data = match line.strip_prefix("data:") {
+                        Some(d) => d.trim(),
+                        None => continue,
+                    };
+                    if data == "[DONE]" {
+                        break 'outer;
+                    }
+                    match serde_json::from_str::<StreamChunk>(data) {
+                        Ok(chunk) => {
+                            if chunk.usage.is_some() {
+                                usage = chunk.usage;
+                            }
+                            let content: String = chunk
+                                .choices
+                                .into_iter()
+                                .filter_map(|c| c.delta.content)
+                                .collect();
+                            (Some(content), false)
+                        }
+                        Err(e) => {
+                            log::warn!("无法解析流式数据块: {} ({})", e, data);
+                            continue;
+                        }
+                    }
+                };
+
+                if let Some(content) = content.filter(|c| !c.is_empty()) {
+                    full.push_str(&content);
+                    let _ = chunk_tx.send(content);
+                }
+                if done {
+                    break 'outer;
+                }
+            }
+        }
+
+        if let Some(usage) = &usage {
+            log_usage(usage);
+        }
+        if full.is_empty() {
+            anyhow::bail!("API 返回了空响应");
+        }
+        self.store(&key, &full);
+        Ok((full, usage))
+    }
+}
+
+/// 替换文本中的家目录和用户名为占位符，如 /home/alice/secret → /home/<user>/secret
+fn redact(text: &str) -> String {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let user = std::env::var("USER")
+        .ok()
+        .filter(|u| !u.is_empty())
+        .or_else(|| {
+            std::path::Path::new(&home)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        .unwrap_or_default();
+    redact_with(text, &home, &user)
+}
+
+/// [`redact`] 的实现，家目录与用户名由调用方传入
+fn redact_with(text: &str, home: &str, user: &str) -> String {
+    let mut result = text.to_string();
+    let home = home.trim_end_matches('/');
+    if home.len() > 1 {
+        let placeholder = if home.starts_with("/home/") { "/home/<user>" } else { "<home>" };
+        result = result.replace(home, placeholder);
+    }
+    // root 等过于常见的名字不做替换，避免误伤日志内容
+    if user.len() >= 2 && user != "root" {
+        if let Ok(re) = regex::Regex::new(&format!(r"\b{}\b", regex::escape(user))) {
+            result = re.replace_all(&result, "<user>").into_owned();
+        }
+    }
+    result
+}
+
+/// 是否为本机地址（localhost / 127.0.0.1 / ::1）
+fn is_local_url(url: &str) -> bool {
+    let rest = url.split("://").nth(1).unwrap_or(url);
+    let host = rest.split('/').next().unwrap_or("");
+    let host = match host.rsplit_once(':') {
+        Some((h, port)) if port.chars().all(|c| c.is_ascii_digit()) => h,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+fn log_usage(usage: &TokenUsage) {
+    log::info!(
+        "Token 使用: 输入={}, 输出={}, 总计={}",
+        usage.prompt_tokens,
+        usage.completion_tokens,
+        usage.total_tokens
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact_with;
+
+    #[test]
+    fn redact_replaces_home_path() {
+        assert_eq!(
+            redact_with("cat /home/alice/secret", "/home/alice", "alice"),
+            "cat /home/<user>/secret"
+        );
+    }
+
+    #[test]
+    fn redact_replaces_bare_username() {
+        assert_eq!(
+            redact_with("user alice logged in, alicebob untouched", "/home/alice", "alice"),
+            "user <user> logged in, alicebob untouched"
+        );
+    }
+
+    #[test]
+    fn redact_leaves_unrelated_text() {
+        let text = "upgraded linux (6.9.1 -> 6.9.2)";
+        assert_eq!(redact_with(text, "/home/alice", "alice"), text);
+        assert_eq!(redact_with("/root/.bashrc", "/root", "root"), "<home>/.bashrc");
     }
 }
@@ -0,0 +1,497 @@
+//! 无界面模式：不进入 TUI（不开启 raw mode / 备用屏幕），直接执行操作并输出结果
+//!
+//! 加 --json 时 stdout 只输出一个 JSON 对象，包管理器输出转到 stderr，便于脚本解析
+
+use crate::config::Config;
+use crate::deepseek::{AiClient, TokenUsage};
+use crate::package_manager::{self, PackageManager, RemoveVariant, UpdateOutput};
+use crate::prompt;
+use crate::report::{self, ReportSaver};
+use crate::sysinfo::SystemInfo;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// 更新 / 安装 / 卸载的结果（--json 输出）
+#[derive(Debug, Default, Serialize)]
+struct OperationResult {
+    /// 报告中的操作标识，如 "Syu" / "S" / "Rns"
+    operation: String,
+    success: bool,
+    package_manager: String,
+    /// 安装 / 卸载的目标包（更新时为空）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<String>,
+    /// 失败原因（成功时为 null）
+    error: Option<String>,
+    /// 显式安装包变化（仅更新）
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    diff: Option<PackageDiff>,
+    /// AI 分析（未启用或失败时为 null）
+    analysis: Option<String>,
+    usage: Option<TokenUsage>,
+    report_path: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct PackageDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    upgraded: Vec<VersionChange>,
+}
+
+#[derive(Debug, Serialize)]
+struct VersionChange {
+    name: String,
+    from: String,
+    to: String,
+}
+
+/// 执行系统更新 (-Syu)，完成后进行 AI 分析并保存报告
+///
+/// 更新失败时以退出码 1 结束进程
+pub async fn run_update(api_key: &str, config: &Config, json: bool) -> Result<()> {
+    let pm = PackageManager::detect()?;
+    let mut result = OperationResult {
+        operation: "Syu".to_string(),
+        package_manager: pm.name().to_string(),
+        ..Default::default()
+    };
+
+    let pm_clone = pm.clone();
+    let hook = config.pre_update_command.clone();
+    let outcome = run_streaming(json, config.sudo_keepalive_secs, move |output_tx| {
+        if let Some(hook) = hook {
+            package_manager::run_pre_update_hook(&hook, output_tx.clone())?;
+        }
+        let before = pm_clone.get_explicit_packages().ok();
+        let output = pm_clone.update_streaming(&[], output_tx)?;
+        let after = pm_clone.get_explicit_packages().ok();
+        Ok((output, before, after))
+    })
+    .await;
+    let (outcome, before, after) = match outcome {
+        Ok((output, before, after)) => (Ok(output), before, after),
+        Err(e) => (Err(e), None, None),
+    };
+    if outcome.as_ref().is_ok_and(|o| !o.success && o.has_signature_error()) {
+        eprintln!(
+            "提示: PGP 签名校验失败，通常是 {0} 过旧，可先执行 sudo pacman -Sy {0} 后重试",
+            package_manager::KEYRING_PACKAGE
+        );
+    }
+    let Some(output) = settle(&mut result, outcome) else {
+        return finish(&result, json);
+    };
+
+    if let (Some(before), Some(after)) = (&before, &after) {
+        let (added, removed) = package_manager::diff_explicit_packages(before, after);
+        let upgraded = package_manager::diff_explicit_versions(before, after)
+            .into_iter()
+            .map(|(name, from, to)| VersionChange { name, from, to })
+            .collect();
+        result.diff = Some(PackageDiff { added, removed, upgraded });
+    } else {
+        result.diff = Some(PackageDiff::default());
+    }
+
+    if config.ai_enabled_for("update") {
+        let sys_info = SystemInfo::detect();
+        let log = output.combined_output();
+        let prompt_text = prompt::generate_analysis_prompt(
+            pm.name(),
+            &log,
+            before.as_deref(),
+            after.as_deref(),
+            Some(&sys_info),
+            &config.analysis_language,
+        );
+        analyze(&mut result, api_key, config, &prompt_text, &log, &sys_info.distro, json).await;
+    }
+
+    finish(&result, json)
+}
+
+/// 安装软件包 (-S)；extra_flags 如 --asexplicit
+pub async fn run_install(
+    api_key: &str,
+    config: &Config,
+    packages: Vec<String>,
+    extra_flags: Vec<String>,
+    json: bool,
+) -> Result<()> {
+    let pm = PackageManager::detect()?;
+    let mut result = OperationResult {
+        operation: "S".to_string(),
+        package_manager: pm.name().to_string(),
+        packages: packages.clone(),
+        ..Default::default()
+    };
+
+    let pm_clone = pm.clone();
+    let outcome = run_streaming(json, config.sudo_keepalive_secs, move |output_tx| {
+        pm_clone.install_streaming(&packages, &extra_flags, output_tx)
+    })
+    .await;
+    let Some(output) = settle(&mut result, outcome) else {
+        return finish(&result, json);
+    };
+
+    if config.ai_enabled_for("install") {
+        let sys_info = SystemInfo::detect();
+        let log = output.combined_output();
+        let prompt_text = prompt::generate_install_prompt(
+            pm.name(),
+            &result.packages,
+            &log,
+            Some(&sys_info),
+            &config.analysis_language,
+        );
+        analyze(&mut result, api_key, config, &prompt_text, &log, &sys_info.distro, json).await;
+    }
+
+    finish(&result, json)
+}
+
+/// 卸载软件包 (-Rns / -Rs / -R)
+pub async fn run_remove(
+    api_key: &str,
+    config: &Config,
+    packages: Vec<String>,
+    variant: RemoveVariant,
+    json: bool,
+) -> Result<()> {
+    let pm = PackageManager::detect()?;
+    let mut result = OperationResult {
+        operation: variant.operation().to_string(),
+        package_manager: pm.name().to_string(),
+        packages: packages.clone(),
+        ..Default::default()
+    };
+
+    let pm_clone = pm.clone();
+    let outcome = run_streaming(json, config.sudo_keepalive_secs, move |output_tx| {
+        pm_clone.remove_streaming(&packages, variant, output_tx)
+    })
+    .await;
+    let Some(output) = settle(&mut result, outcome) else {
+        return finish(&result, json);
+    };
+
+    if config.ai_enabled_for("remove") {
+        let sys_info = SystemInfo::detect();
+        let log = output.combined_output();
+        let prompt_text = prompt::generate_remove_prompt(
+            pm.name(),
+            &result.packages,
+            variant.flag(),
+            &log,
+            Some(&sys_info),
+            &config.analysis_language,
+        );
+        analyze(&mut result, api_key, config, &prompt_text, &log, &sys_info.distro, json).await;
+    }
+
+    finish(&result, json)
+}
+
+/// 搜索软件包：默认搜索同步库，local 时搜索已安装包
+pub fn run_query(keyword: &str, local: bool, json: bool) -> Result<()> {
+    let pm = PackageManager::detect()?;
+    let results = if local {
+        pm.search_local(keyword)
+    } else {
+        pm.search_remote(keyword, &package_manager::SearchCancel::default())?
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    if results.is_empty() {
+        eprintln!("未找到匹配 \"{}\" 的软件包", keyword);
+        std::process::exit(1);
+    }
+    for pkg in &results {
+        let installed = if pkg.installed && !local { " [已安装]" } else { "" };
+        println!("{}/{} {}{}", pkg.repo, pkg.name, pkg.version, installed);
+        if !pkg.description.is_empty() {
+            println!("    {}", pkg.description);
+        }
+    }
+    Ok(())
+}
+
+/// 报告列表条目（--json 输出）
+#[derive(Debug, Serialize)]
+struct ReportEntry {
+    /// 序号，1 为最新，可用于 --diff
+    index: usize,
+    path: String,
+    time: String,
+}
+
+/// 打印最新一份 AI 分析报告；list 时列出最近 limit 份报告
+pub fn run_report(config: &Config, list: bool, limit: usize, json: bool) -> Result<()> {
+    let saver = ReportSaver::from_config(config);
+
+    if list {
+        let entries: Vec<ReportEntry> = saver
+            .list()?
+            .into_iter()
+            .take(limit)
+            .enumerate()
+            .map(|(i, (path, time))| ReportEntry {
+                index: i + 1,
+                path: path.display().to_string(),
+                time: time.format("%Y-%m-%d %H:%M").to_string(),
+            })
+            .collect();
+        if json {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        } else if entries.is_empty() {
+            eprintln!("{} 下暂无报告", config.report_dir.display());
+        } else {
+            for entry in &entries {
+                println!("{:>3}  {}  {}", entry.index, entry.time, entry.path);
+            }
+        }
+        return Ok(());
+    }
+
+    let Some(path) = saver.latest()? else {
+        eprintln!("{} 下暂无报告", config.report_dir.display());
+        std::process::exit(1);
+    };
+    let content = std::fs::read_to_string(&path)?;
+    if json {
+        #[derive(Serialize)]
+        struct LatestReport<'a> {
+            path: String,
+            content: &'a str,
+        }
+        let report = LatestReport {
+            path: path.display().to_string(),
+            content: &content,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        eprintln!("── {} ──", path.display());
+        println!("{}", content);
+    }
+    Ok(())
+}
+
+/// 比较两份报告，输出统一 diff；old / new 为 --list 中的序号或文件路径
+pub fn run_report_diff(config: &Config, old: &str, new: &str, json: bool) -> Result<()> {
+    let saver = ReportSaver::from_config(config);
+    let reports = saver.list()?;
+    let resolve = |arg: &str| -> Result<PathBuf> {
+        let path = PathBuf::from(arg);
+        if path.is_file() {
+            return Ok(path);
+        }
+        match arg.parse::<usize>() {
+            Ok(index) if index >= 1 => reports
+                .get(index - 1)
+                .map(|(path, _)| path.clone())
+                .ok_or_else(|| anyhow!("只有 {} 份报告，没有第 {} 份", reports.len(), index)),
+            _ => Err(anyhow!("找不到报告 \"{}\"", arg)),
+        }
+    };
+    let (old_path, new_path) = (resolve(old)?, resolve(new)?);
+    let (old_text, new_text) = (ReportSaver::load(&old_path)?, ReportSaver::load(&new_path)?);
+    let (old_name, new_name) = (old_path.display().to_string(), new_path.display().to_string());
+    let diff = similar::TextDiff::from_lines(&old_text, &new_text)
+        .unified_diff()
+        .context_radius(3)
+        .header(&old_name, &new_name)
+        .to_string();
+
+    if json {
+        #[derive(Serialize)]
+        struct ReportDiff<'a> {
+            old: &'a str,
+            new: &'a str,
+            diff: &'a str,
+        }
+        let report = ReportDiff {
+            old: &old_name,
+            new: &new_name,
+            diff: &diff,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if diff.is_empty() {
+        eprintln!("两份报告内容相同");
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
+/// 按操作类型和月份汇总报告目录
+pub fn run_report_stats(config: &Config, json: bool) -> Result<()> {
+    let stats = report::aggregate_reports(&config.report_dir);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.total == 0 {
+        eprintln!("{} 下暂无报告", config.report_dir.display());
+        return Ok(());
+    }
+    println!("共 {} 份报告，JSON 报告的日志中记录了 {} 次包操作", stats.total, stats.packages);
+    println!("\n── 按操作 ──");
+    for (operation, count) in &stats.by_operation {
+        println!("{:>6}  {}", count, operation);
+    }
+    println!("\n── 按月份 ──");
+    for (month, count) in stats.by_month.iter().rev() {
+        println!("{:>6}  {}", count, month);
+    }
+    Ok(())
+}
+
+/// 完成提权鉴权（默认 sudo）后在后台线程执行 run，流式打印包管理器输出
+///
+/// 非 JSON 模式打印到 stdout，JSON 模式打印到 stderr；Ctrl+C 按与 TUI 相同的信号阶梯终止包管理器
+async fn run_streaming<T, F>(json: bool, keepalive_secs: u64, run: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(mpsc::UnboundedSender<String>) -> Result<T> + Send + 'static,
+{
+    // 先在终端完成密码验证，避免子进程等待输入
+    let privilege = package_manager::privilege::name();
+    if !package_manager::privilege::command(false)
+        .args(package_manager::privilege::validate_args())
+        .status()?
+        .success()
+    {
+        anyhow::bail!("{} 验证失败", privilege);
+    }
+    let _keepalive = package_manager::SudoKeepalive::start(keepalive_secs);
+
+    package_manager::reset_cancel();
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = tokio::task::spawn_blocking(package_manager::cancel_update).await;
+        }
+    });
+
+    let (output_tx, mut output_rx) = mpsc::unbounded_channel::<String>();
+    let printer = tokio::spawn(async move {
+        while let Some(line) = output_rx.recv().await {
+            // 进度刷新行在非终端环境下没有意义
+            if line.starts_with("PROGRESS:") {
+                continue;
+            }
+            if json {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    });
+
+    let outcome = tokio::task::spawn_blocking(move || run(output_tx)).await?;
+    let _ = printer.await;
+    outcome
+}
+
+/// 根据执行结果填写 success / error；成功时返回需要继续处理的输出
+fn settle(result: &mut OperationResult, outcome: Result<UpdateOutput>) -> Option<UpdateOutput> {
+    match outcome {
+        Ok(output) => {
+            result.success = output.success;
+            if output.success {
+                return Some(output);
+            }
+            result.error = Some(output.stderr.trim().to_string()).filter(|s| !s.is_empty());
+            None
+        }
+        Err(e) => {
+            result.error = Some(e.to_string());
+            None
+        }
+    }
+}
+
+/// AI 分析并保存报告，结果写入 result；分析失败不影响操作本身的 success
+async fn analyze(
+    result: &mut OperationResult,
+    api_key: &str,
+    config: &Config,
+    prompt_text: &str,
+    log: &str,
+    distro: &str,
+    json: bool,
+) {
+    if !json {
+        println!("\n正在进行 AI 分析...");
+    }
+    let client = AiClient::from_config(api_key, config);
+    match client
+        .analyze_update(prompt_text, config.get_model(), config.temperature)
+        .await
+    {
+        Ok((analysis, usage)) => {
+            let saver = ReportSaver::from_config(config);
+            match saver.save(&analysis, log, distro, &result.operation) {
+                Ok(path) => result.report_path = Some(path.display().to_string()),
+                Err(e) => log::error!("保存报告失败: {}", e),
+            }
+            result.analysis = Some(analysis);
+            result.usage = usage;
+        }
+        Err(e) => result.error = Some(format!("AI 分析失败: {}", e)),
+    }
+}
+
+/// 输出结果；操作失败时退出码为 1
+fn finish(result: &OperationResult, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(result)?);
+    } else {
+        print_summary(result);
+    }
+    if !result.success {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 非 JSON 模式下的文字摘要
+fn print_summary(result: &OperationResult) {
+    println!();
+    if let Some(error) = &result.error {
+        eprintln!("❌ {}", error);
+    }
+    if !result.success {
+        return;
+    }
+    println!("✅ 操作完成");
+    if let Some(diff) = &result.diff {
+        println!(
+            "── 显式安装包变化: +{} -{} ~{} ──",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.upgraded.len()
+        );
+        for pkg in &diff.added {
+            println!("+ {}", pkg);
+        }
+        for pkg in &diff.removed {
+            println!("- {}", pkg);
+        }
+        for change in &diff.upgraded {
+            println!("~ {} {} -> {}", change.name, change.from, change.to);
+        }
+    }
+    if let Some(analysis) = &result.analysis {
+        println!("\n── AI 分析 ──\n{}", analysis);
+    }
+    if let Some(path) = &result.report_path {
+        println!("\n报告已保存: {}", path);
+    }
+}
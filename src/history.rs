@@ -0,0 +1,55 @@
+//! 输入历史的持久化，保存在配置目录下，每行一条
+
+use std::fs;
+
+/// 查询模式搜索历史文件名
+pub const QUERY_HISTORY_FILE: &str = "query_history";
+
+/// Shell 模式命令历史文件名
+pub const SHELL_HISTORY_FILE: &str = "shell_history";
+
+/// 最多保留的历史条数
+pub const MAX_HISTORY: usize = 200;
+
+/// 读取历史记录（旧的在前），文件不存在时返回空
+/// 文件损坏（非 UTF-8）时按有损方式读取，并重新去重、截断
+pub fn load(name: &str) -> Vec<String> {
+    let path = crate::config::config_dir().join(name);
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for line in String::from_utf8_lossy(&bytes).lines() {
+        push(&mut entries, line);
+    }
+    entries
+}
+
+/// 写入历史记录，失败只记录日志
+pub fn save(name: &str, entries: &[String]) {
+    let dir = crate::config::config_dir();
+    let result = fs::create_dir_all(&dir).and_then(|_| {
+        let mut text = entries.join("\n");
+        text.push('\n');
+        fs::write(dir.join(name), text)
+    });
+    if let Err(e) = result {
+        log::warn!("保存历史记录失败 ({}): {}", name, e);
+    }
+}
+
+/// 追加一条记录：忽略空白和与上一条相同的记录，已存在的旧记录移到末尾，
+/// 超出上限时丢弃最旧的。返回是否有变化
+pub fn push(entries: &mut Vec<String>, entry: &str) -> bool {
+    let entry = entry.trim();
+    if entry.is_empty() || entries.last().map(|s| s.as_str()) == Some(entry) {
+        return false;
+    }
+    entries.retain(|e| e != entry);
+    entries.push(entry.to_string());
+    if entries.len() > MAX_HISTORY {
+        let excess = entries.len() - MAX_HISTORY;
+        entries.drain(..excess);
+    }
+    true
+}
@@ -0,0 +1,51 @@
+//! lian 的核心库：包管理器封装、AI 分析客户端、配置与报告，不依赖 TUI
+//!
+//! - [`package_manager::PackageManager`]：检测并调用 pacman / paru / yay，查询、预览、流式执行更新 / 安装 / 卸载
+//! - [`deepseek::AiClient`]：OpenAI 兼容的 AI 分析客户端（DeepSeek / OpenAI / Ollama），支持流式输出与结果缓存
+//! - [`config::Config`]：`$XDG_CONFIG_HOME/lian/config.toml`（默认 `~/.config/lian/config.toml`）的读写
+//! - [`report::ReportSaver`]：保存 AI 分析报告
+//!
+//! 只使用库时可关闭默认的 `tui` feature，不引入 ratatui / crossterm：
+//!
+//! ```toml
+//! lian = { git = "https://github.com/Yueosa/lian", default-features = false }
+//! ```
+//!
+//! 更新系统并分析日志：
+//!
+//! ```no_run
+//! use lian::config::Config;
+//! use lian::deepseek::AiClient;
+//! use lian::package_manager::PackageManager;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let config = Config::load_or_default(None)?;
+//! let pm = PackageManager::detect()?;
+//!
+//! let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+//! std::thread::spawn(move || while let Some(line) = rx.blocking_recv() {
+//!     println!("{line}");
+//! });
+//! let output = pm.update_streaming(&[], tx)?;
+//!
+//! let prompt = lian::prompt::generate_analysis_prompt(
+//!     pm.name(), &output.combined_output(), None, None, None, &config.analysis_language,
+//! );
+//! let client = AiClient::from_config("sk-...", &config);
+//! let (analysis, _usage) = client
+//!     .analyze_update(&prompt, config.get_model(), config.temperature)
+//!     .await?;
+//! println!("{analysis}");
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cache;
+pub mod config;
+pub mod deepseek;
+pub mod news;
+pub mod notify;
+pub mod package_manager;
+pub mod prompt;
+pub mod report;
+pub mod sysinfo;
@@ -1,31 +1,77 @@
-mod config;
-mod deepseek;
-mod package_manager;
-mod prompt;
-mod report;
-mod sysinfo;
+mod cli;
+mod headless;
+mod history;
 mod tui;
 
+use lian::{config, deepseek, news, notify, package_manager, prompt, report, sysinfo};
 use anyhow::Result;
+use clap::Parser;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
+    let cli = cli::Cli::parse();
+    if cli.json && cli.command.is_none() {
+        eprintln!("错误: --json 需要配合子命令使用，例如 lian --json update");
+        std::process::exit(2);
+    }
 
-    // 加载配置
-    let config = config::Config::load_or_default()?;
+    // 加载配置（可选叠加配置档案）
+    if let Some(path) = cli.config.clone() {
+        config::set_config_path(path);
+    }
+    let config = config::Config::load_or_default(cli.profile.as_deref())?;
+    package_manager::privilege::set_command(&config.privilege_command);
 
-    // API Key 优先级：配置文件 > 环境变量
-    let api_key = config.api_key.clone()
+    // 配置文件可能含 API Key，权限过宽时提醒（不阻止启动）；TUI 会切到备用屏幕，改在仪表盘上提示
+    if let Some(mode) = config::Config::insecure_permissions().filter(|_| cli.command.is_some()) {
+        let path = config::config_path();
+        eprintln!(
+            "警告: {} 的权限为 {:o}，其他用户可读取其中的 API Key",
+            path.display(),
+            mode
+        );
+        eprintln!("可执行 chmod 600 {}，或在设置页按 Ctrl+P 修复", path.display());
+    }
+
+    use cli::Command;
+    match cli.command {
+        None => tui::run(resolve_api_key(&config), config).await?,
+        Some(Command::Update) => {
+            headless::run_update(&resolve_api_key(&config), &config, cli.json).await?
+        }
+        Some(Command::Install { packages, asexplicit }) => {
+            let flags = if asexplicit { vec!["--asexplicit".to_string()] } else { Vec::new() };
+            headless::run_install(&resolve_api_key(&config), &config, packages, flags, cli.json)
+                .await?
+        }
+        Some(Command::Remove { packages, mode }) => {
+            headless::run_remove(&resolve_api_key(&config), &config, packages, mode, cli.json)
+                .await?
+        }
+        // 查询与报告不需要 AI
+        Some(Command::Query { keyword, local }) => headless::run_query(&keyword, local, cli.json)?,
+        Some(Command::Report { list, limit, diff, stats }) => match diff {
+            Some(pair) => headless::run_report_diff(&config, &pair[0], &pair[1], cli.json)?,
+            None if stats => headless::run_report_stats(&config, cli.json)?,
+            None => headless::run_report(&config, list, limit, cli.json)?,
+        },
+    }
+
+    Ok(())
+}
+
+/// API Key 优先级：配置文件 > 系统密钥环 > 环境变量（本地 Ollama 不需要），均未设置时退出
+fn resolve_api_key(config: &config::Config) -> String {
+    config.api_key.clone()
+        .or_else(config::keyring_api_key)
         .or_else(|| std::env::var("LIAN_AI_KEY").ok())
+        .or_else(|| (!config.provider.requires_api_key()).then(String::new))
         .unwrap_or_else(|| {
             eprintln!("错误: 未设置 AI API Key");
-            eprintln!("请在配置文件 ~/.config/lian/config.toml 中设置 api_key");
+            eprintln!("请在配置文件 {} 中设置 api_key", config::config_path().display());
+            eprintln!("或将其保存到系统密钥环 (服务名 lian-pacman)");
             eprintln!("或设置环境变量: export LIAN_AI_KEY='your-api-key'");
             std::process::exit(1);
-        });
-
-    tui::run(api_key, config).await?;
-
-    Ok(())
+        })
 }
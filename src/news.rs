@@ -0,0 +1,108 @@
+//! Arch Linux 官方新闻（RSS）获取，用于更新前提示需要手动干预的公告
+
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, Local};
+
+const ARCH_NEWS_URL: &str = "https://archlinux.org/feeds/news/";
+
+/// 最多展示的新闻条数
+const MAX_NEWS_ITEMS: usize = 5;
+
+/// 多少天内发布的新闻视为"新公告"
+const RECENT_DAYS: i64 = 14;
+
+/// 单条新闻
+#[derive(Debug, Clone)]
+pub struct NewsItem {
+    pub title: String,
+    pub link: String,
+    pub date: Option<DateTime<FixedOffset>>,
+}
+
+impl NewsItem {
+    /// 日期显示文本，如 "2025-01-10"
+    pub fn date_str(&self) -> String {
+        self.date
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "----------".to_string())
+    }
+
+    /// 是否为最近发布的新闻
+    pub fn is_recent(&self) -> bool {
+        self.date
+            .map(|d| (Local::now().fixed_offset() - d).num_days() < RECENT_DAYS)
+            .unwrap_or(false)
+    }
+}
+
+/// 获取 Arch Linux 官方新闻（最新若干条）
+///
+/// 使用较短的超时，网络不可用时由调用方静默忽略错误。
+pub async fn fetch_arch_news(proxy: Option<&str>) -> Result<Vec<NewsItem>> {
+    let client = crate::deepseek::build_http_client(proxy);
+    let response = client
+        .get(ARCH_NEWS_URL)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("获取 Arch 新闻失败 ({})", response.status());
+    }
+
+    let body = response.text().await?;
+    Ok(parse_rss(&body))
+}
+
+/// 解析 RSS 文本，提取 <item> 中的 title / link / pubDate
+fn parse_rss(xml: &str) -> Vec<NewsItem> {
+    let mut items = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find("<item>") {
+        let after = &rest[start + "<item>".len()..];
+        let end = match after.find("</item>") {
+            Some(e) => e,
+            None => break,
+        };
+        let block = &after[..end];
+
+        let title = extract_tag(block, "title").unwrap_or_default();
+        let link = extract_tag(block, "link").unwrap_or_default();
+        let date = extract_tag(block, "pubDate")
+            .and_then(|d| DateTime::parse_from_rfc2822(d.trim()).ok());
+
+        if !title.is_empty() {
+            items.push(NewsItem { title, link, date });
+        }
+        if items.len() >= MAX_NEWS_ITEMS {
+            break;
+        }
+        rest = &after[end + "</item>".len()..];
+    }
+
+    items
+}
+
+/// 提取 <tag>...</tag> 之间的文本并解码常见 XML 实体
+fn extract_tag(block: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = block.find(&open)? + open.len();
+    let end = block[start..].find(&close)? + start;
+    let raw = block[start..end].trim();
+    let raw = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|s| s.strip_suffix("]]>"))
+        .unwrap_or(raw);
+    Some(decode_entities(raw))
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,33 @@
+//! 桌面通知：调用 notify-send，命令或通知守护进程不存在时静默忽略
+
+use std::process::{Command, Stdio};
+
+/// 发送一条桌面通知，不等待 notify-send 返回（无守护进程时它可能阻塞到 D-Bus 超时）
+pub fn send(summary: &str, body: &str) {
+    let child = Command::new("notify-send")
+        .args(["--app-name=lian", "--icon=system-software-update", summary, body])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = child {
+        // 后台回收子进程，避免僵尸进程
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+    }
+}
+
+/// 操作结束通知：operation 如 "系统更新"，count 为涉及的包数量，error 为 Some 时按失败通知
+pub fn operation_finished(operation: &str, count: usize, error: Option<&str>) {
+    match error {
+        None => send(
+            &format!("{}完成", operation),
+            &format!("共 {} 个软件包", count),
+        ),
+        Some(error) => send(
+            &format!("{}失败", operation),
+            &format!("共 {} 个软件包\n{}", count, error),
+        ),
+    }
+}
@@ -0,0 +1,70 @@
+//! 软件包名称/描述的模糊匹配与排序
+
+use super::PackageInfo;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// 包名包含完整关键词时的额外得分，保证子串匹配排在模糊匹配之前
+const SUBSTRING_BONUS: i64 = 1_000_000;
+
+/// 描述匹配的权重折扣（相对包名）
+const DESCRIPTION_DIVISOR: i64 = 4;
+
+/// 计算关键词与包的匹配得分，不匹配时返回 None（忽略大小写）
+pub fn match_score(matcher: &SkimMatcherV2, keyword: &str, name: &str, description: &str) -> Option<i64> {
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.is_empty() {
+        return Some(0);
+    }
+    let name_lower = name.to_lowercase();
+
+    if name_lower.contains(&keyword) {
+        // 完全相同 > 前缀 > 其他子串，名称越短越靠前
+        let mut score = SUBSTRING_BONUS * 2 - name_lower.len() as i64;
+        if name_lower == keyword {
+            score += SUBSTRING_BONUS * 2;
+        } else if name_lower.starts_with(&keyword) {
+            score += SUBSTRING_BONUS;
+        }
+        return Some(score);
+    }
+    if description.to_lowercase().contains(&keyword) {
+        return Some(SUBSTRING_BONUS);
+    }
+
+    let name_score = matcher.fuzzy_match(&name_lower, &keyword);
+    let desc_score = matcher
+        .fuzzy_match(&description.to_lowercase(), &keyword)
+        .map(|s| s / DESCRIPTION_DIVISOR);
+    name_score.max(desc_score)
+}
+
+/// 按匹配得分筛选并排序，返回匹配项的原下标，得分相同时保持原顺序
+pub fn rank<'a, I>(keyword: &str, items: I) -> Vec<usize>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(usize, i64)> = items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, (name, desc))| match_score(&matcher, keyword, name, desc).map(|s| (i, s)))
+        .collect();
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+/// 按匹配得分筛选并排序软件包列表
+pub fn rank_packages(keyword: &str, packages: Vec<PackageInfo>) -> Vec<PackageInfo> {
+    let order = rank(keyword, packages.iter().map(|p| (p.name.as_str(), p.description.as_str())));
+    let mut slots: Vec<Option<PackageInfo>> = packages.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// 按匹配得分排序但不丢弃结果（用于已由包管理器筛选过的搜索结果）
+pub fn sort_packages(keyword: &str, packages: &mut [PackageInfo]) {
+    let matcher = SkimMatcherV2::default();
+    packages.sort_by_cached_key(|p| {
+        std::cmp::Reverse(match_score(&matcher, keyword, &p.name, &p.description).unwrap_or(i64::MIN))
+    });
+}
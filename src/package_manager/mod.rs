@@ -1,6 +1,8 @@
 //! 包管理器模块 — 对 pacman / paru / yay 的封装
 
+pub mod fuzzy;
 pub mod parser;
+pub mod privilege;
 pub mod streaming;
 pub mod types;
 
@@ -9,20 +11,37 @@ pub use streaming::cancel_update;
 pub use streaming::cleanup_child_processes;
 pub use streaming::reset_cancel;
 pub use streaming::run_custom_command_streaming;
+pub use streaming::run_pre_update_hook;
+pub use streaming::SudoKeepalive;
 pub use parser::ProgressInfo;
 pub use parser::parse_progress_info;
-pub use types::{InstalledPackage, PackageDetail, PackageInfo, UpdateOutput};
+pub use parser::{diff_explicit_packages, diff_explicit_versions};
+pub use parser::{find_critical_updates, find_kernel_update, has_keyring_update, is_security_sensitive, KEYRING_PACKAGE};
+pub use parser::{format_size, pacnew_target, parse_optional_deps, parse_pacman_conf_text, parse_pacman_log, parse_size, parse_version_spec};
+pub use types::{InstalledPackage, LogAction, OptionalDep, PackageCounts, PackageDetail, PackageInfo, PackageSize, PacmanConf, PacmanLogEntry, RemoveVariant, UpdateOutput};
 
 use anyhow::{anyhow, Result};
-use parser::{parse_installed_packages, parse_package_detail, parse_search_output};
-use std::process::Command;
-
+use parser::{parse_installed_packages, parse_package_detail, parse_package_sizes, parse_search_output};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+
+/// 包管理器句柄：按 paru > yay > pacman 的优先级检测
+///
+/// 查询类方法同步执行并解析输出；`*_streaming` 方法逐行发送输出并阻塞到命令结束，
+/// 应在独立线程中调用，调用前先执行 [`reset_cancel`]，可用 [`cancel_update`] 中止。
+/// pacman 通过提权命令（默认 `sudo`，见 [`privilege`]）执行，调用方需事先完成鉴权。
 #[derive(Debug, Clone)]
 pub struct PackageManager {
+    /// 可执行文件名: "paru" / "yay" / "pacman"
     pub command: String,
 }
 
 impl PackageManager {
+    /// 检测系统中可用的包管理器
     pub fn detect() -> Result<Self> {
         for pm in &["paru", "yay", "pacman"] {
             if Command::new("which")
@@ -52,29 +71,118 @@ impl PackageManager {
             .unwrap_or(0)
     }
 
-    /// 检查可用更新（不实际执行更新）
-    pub fn check_updates(&self) -> Vec<String> {
-        let output = Command::new("checkupdates").output();
-        if let Ok(o) = &output {
-            if o.status.success() {
-                return String::from_utf8_lossy(&o.stdout)
-                    .lines()
-                    .map(|s| s.to_string())
-                    .collect();
+    /// 显式 / 依赖 / 外部包数量
+    pub fn count_breakdown(&self) -> PackageCounts {
+        let count = |flag: &str| {
+            Command::new("pacman")
+                .arg(flag)
+                .output()
+                .map(|o| String::from_utf8_lossy(&o.stdout).lines().count())
+                .unwrap_or(0)
+        };
+        PackageCounts {
+            explicit: count("-Qqe"),
+            dependencies: count("-Qqd"),
+            foreign: count("-Qqm"),
+        }
+    }
+
+    /// 孤立包数量 (pacman -Qtdq)
+    pub fn count_orphans(&self) -> usize {
+        Command::new("pacman")
+            .args(["-Qtdq"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).lines().count())
+            .unwrap_or(0)
+    }
+
+    /// 孤立包列表 (pacman -Qtdi)，字段同 get_installed_packages_with_size
+    pub fn get_orphan_packages(&self) -> Vec<InstalledPackage> {
+        let output = Command::new("pacman")
+            .args(["-Qtdi"])
+            .env_remove("LC_ALL")
+            .env("LC_TIME", "C")
+            .output();
+        match output {
+            Ok(o) if o.status.success() => {
+                parse_installed_packages(&String::from_utf8_lossy(&o.stdout))
             }
+            _ => Vec::new(),
         }
-        let output = if self.command == "pacman" {
-            Command::new("pacman").args(["-Qu"]).output()
-        } else {
-            Command::new(&self.command).args(["-Qu"]).output()
+    }
+
+    /// 占用空间最大的 n 个已安装包 (pacman -Qi)，按 Installed Size 降序
+    pub fn largest_packages(&self, n: usize) -> Vec<InstalledPackage> {
+        let output = Command::new("pacman")
+            .args(["-Qi"])
+            .env_remove("LC_ALL")
+            .env("LC_TIME", "C")
+            .output();
+        let mut packages = match output {
+            Ok(o) if o.status.success() => {
+                parse_installed_packages(&String::from_utf8_lossy(&o.stdout))
+            }
+            _ => Vec::new(),
         };
-        match output {
-            Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+        packages.sort_by_key(|p| std::cmp::Reverse(parse_size(&p.size)));
+        packages.truncate(n);
+        packages
+    }
+
+    /// 获取所有已安装包名 (pacman -Qq)
+    pub fn installed_names(&self) -> std::collections::HashSet<String> {
+        Command::new("pacman")
+            .args(["-Qq"])
+            .output()
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 检查可用更新（不实际执行更新）
+    ///
+    /// 返回 Ok(空列表) 表示系统已是最新；命令本身失败（如无网络）时返回 Err。
+    pub fn check_updates(&self) -> Result<Vec<String>> {
+        let lines = |stdout: &[u8]| -> Vec<String> {
+            String::from_utf8_lossy(stdout)
                 .lines()
                 .map(|s| s.to_string())
-                .collect(),
-            _ => Vec::new(),
+                .collect()
+        };
+
+        // checkupdates: 0 = 有更新，2 = 没有更新，其余为出错
+        if let Ok(o) = Command::new("checkupdates").output() {
+            return match o.status.code() {
+                Some(0) => Ok(lines(&o.stdout)),
+                Some(2) => Ok(Vec::new()),
+                _ => {
+                    let stderr = String::from_utf8_lossy(&o.stderr).trim().to_string();
+                    if stderr.is_empty() {
+                        anyhow::bail!("checkupdates 执行失败");
+                    }
+                    anyhow::bail!("{}", stderr)
+                }
+            };
+        }
+
+        // 未安装 pacman-contrib 时回退到 -Qu：没有可更新的包时返回 1，但不输出任何内容
+        let o = Command::new(&self.command).args(["-Qu"]).output()?;
+        if o.status.success() {
+            return Ok(lines(&o.stdout));
+        }
+        let stderr = String::from_utf8_lossy(&o.stderr).trim().to_string();
+        if o.stdout.is_empty() && stderr.is_empty() {
+            return Ok(Vec::new());
+        }
+        if stderr.is_empty() {
+            anyhow::bail!("{} -Qu 执行失败", self.command);
         }
+        anyhow::bail!("{}", stderr)
     }
 
     /// 获取当前已安装的显式安装包列表
@@ -86,9 +194,36 @@ impl PackageManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// 导出显式安装包名列表（等价于 pacman -Qqe）到文件
+    pub fn export_explicit(&self, path: &Path) -> Result<()> {
+        let explicit = self.get_explicit_packages()?;
+        let names: Vec<&str> = explicit
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .collect();
+        write_package_list(path, &names)
+    }
+
+    /// 导出外部包（AUR/本地构建）名列表（pacman -Qqm）到文件
+    pub fn export_foreign(&self, path: &Path) -> Result<()> {
+        let output = Command::new("pacman").args(["-Qqm"]).output()?;
+        // 没有外部包时 pacman -Qqm 以 1 退出且无输出，视为空列表
+        if !output.status.success() && !output.stdout.is_empty() {
+            anyhow::bail!("pacman -Qqm 执行失败");
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let names: Vec<&str> = stdout.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+        write_package_list(path, &names)
+    }
+
     /// 获取显式安装的包列表（含大小和描述）
     pub fn get_installed_packages_with_size(&self) -> Vec<InstalledPackage> {
-        let output = Command::new("pacman").args(["-Qei"]).output();
+        // 固定日期格式以便解析 Install Date
+        let output = Command::new("pacman")
+            .args(["-Qei"])
+            .env_remove("LC_ALL")
+            .env("LC_TIME", "C")
+            .output();
         match output {
             Ok(o) if o.status.success() => {
                 parse_installed_packages(&String::from_utf8_lossy(&o.stdout))
@@ -104,27 +239,73 @@ impl PackageManager {
         if keyword.trim().is_empty() {
             return Vec::new();
         }
-        let output = Command::new("pacman").args(["-Qs", keyword]).output();
+        // 列出全部已安装包后模糊匹配，如 "fxfx" 可匹配 firefox
+        let output = Command::new("pacman").arg("-Qs").output();
         match output {
-            Ok(o) if o.status.success() => {
-                parse_search_output(&String::from_utf8_lossy(&o.stdout), true)
-            }
+            Ok(o) if o.status.success() => fuzzy::rank_packages(
+                keyword,
+                parse_search_output(&String::from_utf8_lossy(&o.stdout), true),
+            ),
             _ => Vec::new(),
         }
     }
 
     /// 搜索远程仓库包 (paru/yay/pacman -Ss)
-    pub fn search_remote(&self, keyword: &str) -> Vec<PackageInfo> {
+    ///
+    /// 新搜索开始时会经 cancel 终止同一调用方上一次尚未结束的搜索进程，避免连续输入时堆积 paru -Ss。
+    /// 没有匹配时返回 Ok(空列表)；命令出错（如无网络时 AUR 查询失败）且没有任何结果时返回 Err。
+    pub fn search_remote(&self, keyword: &str, cancel: &SearchCancel) -> Result<Vec<PackageInfo>> {
         if keyword.trim().is_empty() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
-        let output = Command::new(&self.command).args(["-Ss", keyword]).output();
-        match output {
-            Ok(o) if o.status.success() => {
-                parse_search_output(&String::from_utf8_lossy(&o.stdout), false)
+        cancel.cancel();
+        // 独立进程组，终止时连同 paru 派生的 pacman 一起结束
+        let mut child = Command::new(&self.command)
+            .args(["-Ss", keyword])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()?;
+        let pid = child.id();
+        cancel.set(Some(pid));
+
+        let stderr = child.stderr.take();
+        let stderr_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut stderr) = stderr {
+                let _ = stderr.read_to_end(&mut buf);
             }
-            _ => Vec::new(),
+            buf
+        });
+        let mut stdout = Vec::new();
+        if let Some(mut out) = child.stdout.take() {
+            let _ = out.read_to_end(&mut stdout);
+        }
+        let stderr = stderr_reader.join().unwrap_or_default();
+
+        // 先等待退出但不回收，清空句柄后再回收：回收前 PID 不会被复用，cancel 不会误杀其他进程组
+        wait_exited(pid);
+        cancel.clear(pid);
+        let status = child.wait()?;
+
+        let o = std::process::Output { status, stdout, stderr };
+        let mut results = parse_search_output(&String::from_utf8_lossy(&o.stdout), false);
+        fuzzy::sort_packages(keyword, &mut results);
+        // 离线时 paru 仍会输出同步库中的结果，此时照常返回
+        if o.status.success() || !results.is_empty() {
+            return Ok(results);
+        }
+        // -Ss 无匹配时退出码为 1 且没有错误输出
+        let stderr = parser::clean_terminal_output(&String::from_utf8_lossy(&o.stderr));
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            return Ok(results);
+        }
+        if parser::is_network_error(stderr) {
+            anyhow::bail!("网络不可用");
         }
+        anyhow::bail!("{}", stderr.lines().next().unwrap_or(stderr))
     }
 
     /// 获取本地包详情 (pacman -Qi)
@@ -147,6 +328,46 @@ impl PackageManager {
         Ok(parse_package_detail(&raw))
     }
 
+    /// 获取 AUR 包的 PKGBUILD
+    ///
+    /// 优先使用 paru/yay -Gp，失败或只有 pacman 时从 AUR 网站下载。
+    pub fn fetch_pkgbuild(&self, name: &str) -> Result<String> {
+        if self.command != "pacman" {
+            if let Ok(o) = Command::new(&self.command).args(["-Gp", name]).output() {
+                let text = String::from_utf8_lossy(&o.stdout).to_string();
+                if o.status.success() && !text.trim().is_empty() {
+                    return Ok(text);
+                }
+            }
+        }
+
+        // 拆分包的 PKGBUILD 在 pkgbase 分支下，先经 AUR RPC 查询，查询失败时按包名尝试
+        let base = aur_package_base(name).unwrap_or_else(|| name.to_string());
+        let url = format!("https://aur.archlinux.org/cgit/aur.git/plain/PKGBUILD?h={}", base);
+        let output = Command::new("curl")
+            .args(["-fsSL", "--max-time", "15", &url])
+            .output()
+            .map_err(|e| anyhow!("无法执行 curl: {}", e))?;
+        if !output.status.success() {
+            anyhow::bail!("获取 {} 的 PKGBUILD 失败", name);
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// 批量获取仓库包的下载/安装大小 (pacman -Si)
+    ///
+    /// 不在同步数据库中的包（如 AUR）会被 pacman 忽略，不出现在结果里。
+    pub fn package_sizes(&self, names: &[String]) -> HashMap<String, PackageSize> {
+        if names.is_empty() {
+            return HashMap::new();
+        }
+        // 部分包找不到时 pacman 以非零退出，但仍会输出其余包的信息
+        match Command::new("pacman").arg("-Si").args(names).output() {
+            Ok(o) => parse_package_sizes(&String::from_utf8_lossy(&o.stdout)),
+            Err(_) => HashMap::new(),
+        }
+    }
+
     /// 获取已安装包的文件列表 (pacman -Ql)
     pub fn package_files(&self, name: &str) -> Vec<String> {
         let output = Command::new("pacman").args(["-Ql", name]).output();
@@ -196,40 +417,65 @@ impl PackageManager {
     // ===== 预览 =====
 
     /// 预览安装操作（显示将安装的包和依赖）
-    pub fn preview_install(&self, packages: &[String]) -> Vec<String> {
+    ///
+    /// min_free: 安装后根分区至少保留的字节数，空间不足时在最前面插入 DANGER 警告
+    pub fn preview_install(&self, packages: &[String], min_free: u64) -> Vec<String> {
         let mut lines = Vec::new();
+        let mut total_download: u64 = 0;
+        let mut total_installed: u64 = 0;
+
+        // 同步数据库中存在的包，用于 -Sp 试运行（AUR 包 pacman 无法解析）
+        let mut repo_specs: Vec<String> = Vec::new();
+
+        // name=version 只用包名查询信息
+        let specs: Vec<(&str, Option<&str>)> = packages
+            .iter()
+            .map(|spec| match parse_version_spec(spec) {
+                Some((name, version)) => (name, Some(version)),
+                None => (spec.as_str(), None),
+            })
+            .collect();
+        let names: Vec<&str> = specs.iter().map(|(name, _)| *name).collect();
+        let details = batch_package_details("pacman", "-Si", &names);
+        // pacman 找不到的包（通常来自 AUR）交给 AUR 助手查询
+        let missing: Vec<&str> = names.iter().copied().filter(|n| !details.contains_key(*n)).collect();
+        let aur_details = if self.command != "pacman" && !missing.is_empty() {
+            batch_package_details(&self.command, "-Si", &missing)
+        } else {
+            HashMap::new()
+        };
 
-        for pkg in packages {
-            let output = Command::new("pacman").args(["-Si", pkg]).output();
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    let info = String::from_utf8_lossy(&o.stdout);
-                    let mut name = String::new();
-                    let mut version = String::new();
-                    let mut size = String::new();
-                    let mut depends = String::new();
-
-                    for line in info.lines() {
-                        if let Some(colon) = line.find(':') {
-                            let key = line[..colon].trim();
-                            let val = line[colon + 1..].trim();
-                            match key {
-                                "Name" | "名称" | "名字" => name = val.to_string(),
-                                "Version" | "版本" => version = val.to_string(),
-                                "Installed Size" | "Download Size" | "安装大小" | "安装后大小"
-                                | "下载大小" => {
-                                    if size.is_empty() {
-                                        size = val.to_string();
-                                    }
-                                }
-                                "Depends On" | "依赖于" => depends = val.to_string(),
-                                _ => {}
-                            }
+        for (spec, (pkg, pinned)) in packages.iter().zip(&specs) {
+            let from_aur = !details.contains_key(*pkg);
+            match details.get(*pkg).or_else(|| aur_details.get(*pkg)) {
+                Some(detail) => {
+                    // AUR 包无法被 pacman -Sp 解析，不参与试运行
+                    if !from_aur {
+                        repo_specs.push(spec.clone());
+                    }
+                    let name = detail.get(&["Name", "名称", "名字"]).unwrap_or(pkg);
+                    let version = detail.get(&["Version", "版本"]).unwrap_or_default();
+                    let size = detail
+                        .get(&["Installed Size", "Download Size", "安装大小", "安装后大小", "下载大小"])
+                        .unwrap_or_default();
+                    let depends = detail.get(&["Depends On", "依赖于"]).unwrap_or_default();
+                    total_download += parse_size(detail.get(&["Download Size", "下载大小"]).unwrap_or_default());
+                    total_installed +=
+                        parse_size(detail.get(&["Installed Size", "安装大小", "安装后大小"]).unwrap_or_default());
+
+                    match pinned {
+                        Some(pin) if *pin != version => {
+                            lines.push(format!("  {} {} (固定版本，仓库当前为 {})", name, pin, version));
+                            // -S name=ver 只在同步数据库中解析，不会读取包缓存
+                            lines.push(format!(
+                                "WARN:    仓库中没有该版本，安装会失败；旧版本请用 pacman -U {}/{}-{}-*.pkg.tar.zst 安装",
+                                PACKAGE_CACHE_DIR, name, pin
+                            ));
                         }
+                        Some(pin) => lines.push(format!("  {} {} (固定版本)", name, pin)),
+                        None if from_aur => lines.push(format!("  {} {} (AUR)", name, version)),
+                        None => lines.push(format!("  {} {}", name, version)),
                     }
-
-                    lines.push(format!("  {} {}", name, version));
                     if !size.is_empty() {
                         lines.push(format!("    大小: {}", size));
                     }
@@ -237,83 +483,160 @@ impl PackageManager {
                         lines.push(format!("    依赖: {}", depends));
                     }
                     lines.push(String::new());
-                } else {
-                    lines.push(format!("  {} (未找到包信息)", pkg));
+                }
+                None => {
+                    lines.push(format!("  {} (未找到包信息)", spec));
                     lines.push(String::new());
                 }
             }
         }
 
+        // 磁盘空间检查：下载的包缓存和安装后的文件都在根分区上
+        let needed = total_download + total_installed;
+        if needed > 0 {
+            let available = crate::sysinfo::available_space(Path::new("/"));
+            if available != u64::MAX {
+                lines.push(format!(
+                    "合计: 下载 {}，安装后占用 {}，/ 可用 {}",
+                    format_size(total_download),
+                    format_size(total_installed),
+                    format_size(available)
+                ));
+                lines.push(String::new());
+            }
+            if available < needed.saturating_add(min_free) {
+                let danger = vec![
+                    format!(
+                        "DANGER:⛔ 根分区空间不足：需要约 {}（另需保留 {}），仅剩 {}",
+                        format_size(needed),
+                        format_size(min_free),
+                        format_size(available)
+                    ),
+                    "DANGER:  空间耗尽会导致事务中途失败，如确需继续，请按两次 Enter 确认".to_string(),
+                    String::new(),
+                ];
+                lines.splice(0..0, danger);
+            }
+        }
+
+        if !repo_specs.is_empty() {
+            lines.extend(self.dry_run_install(&repo_specs));
+        }
+
+        lines
+    }
+
+    /// 用 pacman -Sp 试运行安装，列出完整事务（含依赖）以及冲突/替换警告
+    fn dry_run_install(&self, packages: &[String]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let output = Command::new("pacman")
+            .args(["-Sp", "--print-format", "%r/%n %v"])
+            .args(packages)
+            .stdin(std::process::Stdio::null())
+            .output();
+        let Ok(o) = output else {
+            return lines;
+        };
+
+        let stdout = String::from_utf8_lossy(&o.stdout);
+        let stderr = String::from_utf8_lossy(&o.stderr);
+        let warnings = transaction_warnings(&format!("{}\n{}", stdout, stderr));
+        if !warnings.is_empty() {
+            lines.push("WARN:⚠ 事务警告（冲突/替换/依赖问题）:".to_string());
+            lines.extend(warnings.iter().map(|w| format!("WARN:  {}", w)));
+            lines.push(String::new());
+        }
+
+        if o.status.success() {
+            let targets: Vec<&str> = stdout
+                .lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty() && !l.starts_with("::"))
+                .collect();
+            lines.push(format!("完整事务（pacman -Sp，共 {} 个包）:", targets.len()));
+            lines.extend(targets.iter().map(|t| format!("  {}", t)));
+        } else if warnings.is_empty() {
+            lines.push("WARN:⚠ pacman -Sp 试运行失败，安装可能无法完成".to_string());
+        }
         lines
     }
 
     /// 预览卸载操作（显示将被移除的包）
-    pub fn preview_remove(&self, packages: &[String]) -> Vec<String> {
+    pub fn preview_remove(&self, packages: &[String], variant: RemoveVariant) -> Vec<String> {
         let mut lines = Vec::new();
 
+        let names: Vec<&str> = packages.iter().map(|p| p.as_str()).collect();
+        let details = batch_package_details("pacman", "-Qi", &names);
+
         for pkg in packages {
-            let output = Command::new("pacman").args(["-Qi", pkg]).output();
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    let info = String::from_utf8_lossy(&o.stdout);
-                    let mut name = String::new();
-                    let mut version = String::new();
-                    let mut size = String::new();
-                    let mut required_by = String::new();
-
-                    for line in info.lines() {
-                        if let Some(colon) = line.find(':') {
-                            let key = line[..colon].trim();
-                            let val = line[colon + 1..].trim();
-                            match key {
-                                "Name" | "名称" | "名字" => name = val.to_string(),
-                                "Version" | "版本" => version = val.to_string(),
-                                "Installed Size" | "安装大小" | "安装后大小" => {
-                                    size = val.to_string()
-                                }
-                                "Required By" | "依赖它" => required_by = val.to_string(),
-                                _ => {}
-                            }
-                        }
-                    }
+            match details.get(pkg.as_str()) {
+                Some(detail) => {
+                    let name = detail.get(&["Name", "名称", "名字"]).unwrap_or(pkg);
+                    let version = detail.get(&["Version", "版本"]).unwrap_or_default();
+                    let size = detail
+                        .get(&["Installed Size", "安装大小", "安装后大小"])
+                        .unwrap_or_default();
+                    let required_by = detail.get_list(&["Required By", "依赖它", "要求被"]).join(", ");
 
                     lines.push(format!("  {} {}", name, version));
                     if !size.is_empty() {
                         lines.push(format!("    大小: {}", size));
                     }
-                    if !required_by.is_empty() && required_by != "None" && required_by != "无" {
+                    if !required_by.is_empty() {
                         lines.push(format!("    ⚠ 被依赖: {}", required_by));
                     }
                     lines.push(String::new());
-                } else {
+                }
+                None => {
                     lines.push(format!("  {} (未找到包信息)", pkg));
                     lines.push(String::new());
                 }
             }
         }
 
-        // 用 pacman -Rns --print 获取完整移除列表（含依赖）
-        let mut args = vec!["-Rns".to_string(), "--print".to_string()];
+        // 用 pacman <flag> --print 获取完整移除列表（含依赖）
+        let mut args = vec![
+            variant.flag().to_string(),
+            "--print".to_string(),
+            "--print-format".to_string(),
+            "%n %v".to_string(),
+        ];
         args.extend(packages.iter().cloned());
 
         let output = Command::new("pacman").args(&args).output();
+        // 实际会被移除的包名（含级联依赖），用于受保护包检查
+        let mut removed: Vec<String> = packages.to_vec();
 
         if let Ok(o) = output {
+            let warnings = transaction_warnings(&String::from_utf8_lossy(&o.stderr));
+            if !warnings.is_empty() {
+                lines.push("WARN:⚠ 事务警告（依赖问题）:".to_string());
+                lines.extend(warnings.iter().map(|w| format!("WARN:  {}", w)));
+                lines.push(String::new());
+            }
             if o.status.success() {
                 let stdout = String::from_utf8_lossy(&o.stdout);
                 let remove_list: Vec<&str> = stdout.lines().collect();
                 if !remove_list.is_empty() {
                     lines.push(format!(
-                        "将移除以下 {} 个包（含孤立依赖）:",
-                        remove_list.len()
+                        "将移除以下 {} 个包{}:",
+                        remove_list.len(),
+                        if variant.removes_deps() { "（含孤立依赖）" } else { "" }
                     ));
                     for l in &remove_list {
                         lines.push(format!("  {}", l));
                     }
                 }
-            } else {
-                let mut args2 = vec!["-Rn".to_string(), "--print".to_string()];
+                removed.extend(remove_list.iter().filter_map(|l| l.split_whitespace().next()).map(String::from));
+            } else if variant.removes_deps() {
+                // 连带依赖的移除失败时，退回只移除所选包
+                let fallback = if variant == RemoveVariant::Rns { "-Rn" } else { "-R" };
+                let mut args2 = vec![
+                    fallback.to_string(),
+                    "--print".to_string(),
+                    "--print-format".to_string(),
+                    "%n %v".to_string(),
+                ];
                 args2.extend(packages.iter().cloned());
                 if let Ok(o2) = Command::new("pacman").args(&args2).output() {
                     if o2.status.success() {
@@ -325,11 +648,306 @@ impl PackageManager {
                                 lines.push(format!("  {}", l));
                             }
                         }
+                        removed.extend(remove_list.iter().filter_map(|l| l.split_whitespace().next()).map(String::from));
                     }
                 }
             }
         }
 
+        // 受保护包检查：放在最前面，确保一眼可见
+        let protected = protected_packages();
+        let mut hits: Vec<&str> = removed
+            .iter()
+            .map(|n| n.as_str())
+            .filter(|n| protected.contains(*n))
+            .collect();
+        hits.sort_unstable();
+        hits.dedup();
+        if !hits.is_empty() {
+            let danger = vec![
+                "DANGER:⛔ 以下为系统基础/关键包，卸载可能导致系统无法启动:".to_string(),
+                format!("DANGER:  {}", hits.join(", ")),
+                "DANGER:  如确需卸载，请按两次 Enter 确认".to_string(),
+                String::new(),
+            ];
+            lines.splice(0..0, danger);
+        }
+
         lines
     }
 }
+
+/// 一次调用（-Si/-Qi）查询多个包的信息，按包名索引；部分包不存在时其余照常返回
+/// command 为 pacman 或 AUR 助手（paru/yay 的 -Si 也能查询 AUR 包）
+fn batch_package_details(command: &str, op: &str, names: &[&str]) -> HashMap<String, PackageDetail> {
+    let mut details = HashMap::new();
+    if names.is_empty() {
+        return details;
+    }
+    let Ok(output) = Command::new(command).arg(op).args(names).output() else {
+        return details;
+    };
+    for detail in parser::parse_package_details(&String::from_utf8_lossy(&output.stdout)) {
+        if let Some(name) = detail.get(&["Name", "名称", "名字"]) {
+            // 同名包出现在多个仓库时取第一个（与 pacman 的仓库优先级一致）
+            details.entry(name.to_string()).or_insert(detail);
+        }
+    }
+    details
+}
+
+/// 远程搜索的取消句柄：每个界面持有自己的句柄，只终止自己启动的搜索进程
+#[derive(Clone, Default)]
+pub struct SearchCancel(Arc<Mutex<Option<u32>>>);
+
+impl SearchCancel {
+    /// 终止正在运行的搜索（被终止的搜索返回空结果，由调用方的 seq 检查丢弃）
+    pub fn cancel(&self) {
+        let slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(pid) = *slot {
+            // SAFETY: killpg 只发送信号，不涉及内存；持锁期间该进程尚未被回收
+            //（search_remote 先 clear 再 wait），PID 与进程组不会被复用
+            unsafe {
+                libc::killpg(pid as libc::pid_t, libc::SIGTERM);
+            }
+        }
+    }
+
+    fn set(&self, pid: Option<u32>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = pid;
+    }
+
+    /// 仅当句柄仍指向 pid 时清空（之后的新搜索可能已经写入了自己的 PID）
+    fn clear(&self, pid: u32) {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if *slot == Some(pid) {
+            *slot = None;
+        }
+    }
+}
+
+/// 等待子进程退出但不回收（WNOWAIT），进程保持僵尸状态直到调用方 wait
+fn wait_exited(pid: u32) {
+    loop {
+        // SAFETY: info 是有效的可写 siginfo_t，waitid 只写入该结构
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let ret = unsafe {
+            libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, libc::WEXITED | libc::WNOWAIT)
+        };
+        if ret == 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            return;
+        }
+    }
+}
+
+/// 内置的关键包列表，即使不属于 base/base-devel 组也受保护
+const ESSENTIAL_PACKAGES: [&str; 16] = [
+    "base", "base-devel", "filesystem", "glibc", "gcc-libs", "bash", "coreutils", "util-linux",
+    "systemd", "systemd-libs", "shadow", "pacman", "archlinux-keyring", "linux", "linux-firmware", "sudo",
+];
+
+/// 受保护的包：内置关键包 + pacman -Qg base base-devel 的组成员
+pub fn protected_packages() -> HashSet<String> {
+    let mut set: HashSet<String> = ESSENTIAL_PACKAGES.iter().map(|s| s.to_string()).collect();
+    // -Qg 输出格式为 "<组名> <包名>"；组不存在时 pacman 返回非零，忽略即可
+    if let Ok(o) = Command::new("pacman").args(["-Qg", "base", "base-devel"]).output() {
+        let stdout = String::from_utf8_lossy(&o.stdout);
+        set.extend(
+            stdout
+                .lines()
+                .filter_map(|l| l.split_whitespace().nth(1))
+                .map(String::from),
+        );
+    }
+    set
+}
+
+/// 从 pacman 输出中提取冲突、替换、依赖破坏等警告/错误行
+///
+/// 只匹配 pacman 的固定措辞，避免包名或描述中的 conflict / Replace 等词误报
+fn transaction_warnings(output: &str) -> Vec<String> {
+    const PREFIXES: [&str; 6] = ["error:", "错误：", "warning:", "警告：", ":: Replace ", ":: 替换"];
+    const PHRASES: [&str; 9] = [
+        "are in conflict",
+        "有冲突",
+        "conflicting files",
+        "conflicting dependencies",
+        "冲突的文件",
+        "exists in filesystem",
+        "已经存在于文件系统中",
+        "breaks dependency",
+        "破坏依赖",
+    ];
+    let mut warnings: Vec<String> = Vec::new();
+    for line in output.lines().map(|l| l.trim()) {
+        let matched = PREFIXES.iter().any(|p| line.starts_with(p)) || PHRASES.iter().any(|p| line.contains(p));
+        if matched && !warnings.iter().any(|w| w == line) {
+            warnings.push(line.to_string());
+        }
+    }
+    warnings
+}
+
+/// 判断路径是否为本地软件包文件（*.pkg.tar.*，排除签名文件）
+pub fn is_local_package(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.contains(".pkg.tar") && !n.ends_with(".sig"))
+}
+
+/// 列出目录下的本地软件包文件，按文件名排序
+pub fn find_local_packages(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| anyhow!("读取目录 {} 失败: {}", dir.display(), e))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && is_local_package(p))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// pacman 配置文件路径
+pub const PACMAN_CONF: &str = "/etc/pacman.conf";
+
+/// pacman 日志文件路径
+pub const PACMAN_LOG: &str = "/var/log/pacman.log";
+
+/// pacman.log 中最近的 n 条包操作，最新的在前；日志不可读时返回空列表
+pub fn recent_log_entries(n: usize) -> Vec<PacmanLogEntry> {
+    // 日志中可能混有钩子输出的非 UTF-8 字节
+    let Ok(bytes) = std::fs::read(PACMAN_LOG) else {
+        return Vec::new();
+    };
+    let mut entries = parse_pacman_log(&String::from_utf8_lossy(&bytes));
+    let start = entries.len().saturating_sub(n);
+    entries.drain(..start);
+    entries.reverse();
+    entries
+}
+
+/// 通过 AUR RPC 查询包所属的 PackageBase（拆分包与包名不同）
+fn aur_package_base(name: &str) -> Option<String> {
+    let url = format!("https://aur.archlinux.org/rpc/v5/info?arg[]={}", name);
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", "10", "--globoff", &url])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    response["results"]
+        .get(0)?
+        .get("PackageBase")?
+        .as_str()
+        .filter(|base| !base.is_empty())
+        .map(str::to_string)
+}
+
+/// pacman 默认的包缓存目录（pacman.conf 未设置 CacheDir 时）
+pub const PACKAGE_CACHE_DIR: &str = "/var/cache/pacman/pkg";
+
+/// 读取 /etc/pacman.conf，文件不存在或不可读时返回空配置（即 pacman 默认行为）
+pub fn parse_pacman_conf() -> PacmanConf {
+    std::fs::read_to_string(PACMAN_CONF)
+        .map(|content| parse_pacman_conf_text(&content))
+        .unwrap_or_default()
+}
+
+/// 实际使用的包缓存目录：pacman.conf 中的 CacheDir，未设置时为默认目录
+pub fn cache_dirs() -> Vec<PathBuf> {
+    let dirs = parse_pacman_conf().cache_dirs;
+    if dirs.is_empty() {
+        vec![PathBuf::from(PACKAGE_CACHE_DIR)]
+    } else {
+        dirs
+    }
+}
+
+/// 包缓存占用的字节数（各缓存目录下文件大小之和）
+pub fn cache_size() -> u64 {
+    cache_dirs()
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// 清理包缓存的命令：有 paccache 时保留每个包最近 2 个版本，否则 pacman -Sc
+///
+/// paccache 不读取 pacman.conf，CacheDir 不是默认目录时逐个用 -c 指定
+pub fn cache_clean_command() -> String {
+    let has_paccache = std::env::var("PATH")
+        .unwrap_or_default()
+        .split(':')
+        .any(|dir| Path::new(dir).join("paccache").is_file());
+    if has_paccache {
+        let dirs = cache_dirs();
+        let custom = dirs.len() != 1 || dirs[0] != Path::new(PACKAGE_CACHE_DIR);
+        let dir_args: String = if custom {
+            dirs.iter().map(|d| format!(" -c {}", d.display())).collect()
+        } else {
+            String::new()
+        };
+        format!("{} paccache -rk2{}", privilege::name(), dir_args)
+    } else {
+        format!("{} pacman -Sc", privilege::name())
+    }
+}
+
+/// 扫描 /etc 下待合并的 .pacnew / .pacsave（含编号备份）配置文件，无权限读取的目录直接跳过
+pub fn find_pacnew_files() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut dirs = vec![PathBuf::from("/etc")];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() && pacnew_target(&path).is_some() {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// 读取包名列表文件：每行一个包名，忽略空行和 # 注释
+pub fn read_package_list(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("读取 {} 失败: {}", path.display(), e))?;
+    let mut names: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        // 兼容 pacman -Qe 格式（"name version"），只取包名
+        if let Some(name) = line.split_whitespace().next() {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// 将包名列表写入文件（每行一个），自动创建父目录
+fn write_package_list(path: &Path, names: &[&str]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut content = names.join("\n");
+    content.push('\n');
+    std::fs::write(path, content)?;
+    Ok(())
+}
@@ -1,6 +1,11 @@
 //! 输出解析函数
 
-use super::types::{InstalledPackage, PackageDetail, PackageInfo};
+use super::types::{InstalledPackage, LogAction, OptionalDep, PackageDetail, PackageInfo, PackageSize, PacmanConf, PacmanLogEntry};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, NaiveDateTime};
+use regex::Regex;
+use std::sync::LazyLock;
 
 /// 清理终端输出中的 ANSI 转义序列和特殊字符
 pub fn clean_terminal_output(input: &str) -> String {
@@ -9,17 +14,39 @@ pub fn clean_terminal_output(input: &str) -> String {
 
     while let Some(c) = chars.next() {
         match c {
-            '\x1b' => {
-                if chars.peek() == Some(&'[') {
-                    chars.next();
-                    while let Some(&next) = chars.peek() {
-                        chars.next();
-                        if next.is_ascii_alphabetic() {
+            '\x1b' => match chars.next() {
+                // CSI: ESC [ 参数 终止字节(0x40-0x7E)
+                Some('[') => {
+                    for next in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&next) {
                             break;
                         }
                     }
                 }
-            }
+                // OSC/DCS/PM/APC（如 ESC ] 0 ; 标题 BEL）：以 BEL 或 ST(ESC \) 结束
+                Some(']' | 'P' | '^' | '_') => {
+                    while let Some(next) = chars.next() {
+                        if next == '\x07' {
+                            break;
+                        }
+                        if next == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // 带中间字节的转义（如字符集切换 ESC ( B）：跳过中间字节和终止字节
+                Some(c) if ('\x20'..='\x2f').contains(&c) => {
+                    for next in chars.by_ref() {
+                        if !('\x20'..='\x2f').contains(&next) {
+                            break;
+                        }
+                    }
+                }
+                Some('\n') => result.push('\n'),
+                // 其余单字符转义（如 ESC = / ESC 7）直接丢弃
+                _ => {}
+            },
             '\r' => {
                 if chars.peek() != Some(&'\n') && !result.ends_with('\n') {
                     result.push('\n');
@@ -61,6 +88,8 @@ pub struct ProgressInfo {
     pub speed: String,
     /// 剩余时间，如 "00:23"
     pub eta: String,
+    /// 进度百分比（来自 "45%" 字段），AUR 构建等无百分比的阶段为 None
+    pub percent: Option<u8>,
 }
 
 impl ProgressInfo {
@@ -70,7 +99,7 @@ impl ProgressInfo {
 
     /// 格式化为状态栏文本：label  ⬇ speed  / total  剩余 eta
     pub fn footer_text(&self) -> String {
-        if self.raw.is_empty() {
+        if self.is_empty() {
             return String::new();
         }
         let mut parts: Vec<String> = Vec::new();
@@ -114,6 +143,7 @@ pub fn parse_progress_info(raw: &str) -> ProgressInfo {
     let mut total_size = String::new();
     let mut speed = String::new();
     let mut eta = String::new();
+    let mut percent = None;
 
     let mut i = 0;
     while i < n {
@@ -124,8 +154,9 @@ pub fn parse_progress_info(raw: &str) -> ProgressInfo {
             i += 1;
             continue;
         }
-        // 跳过百分比 "100%"
-        if t.ends_with('%') && t[..t.len() - 1].chars().all(|c| c.is_ascii_digit()) {
+        // 百分比 "100%"
+        if let Some(value) = t.strip_suffix('%').and_then(|v| v.parse::<u8>().ok()) {
+            percent = Some(value.min(100));
             i += 1;
             continue;
         }
@@ -138,12 +169,10 @@ pub fn parse_progress_info(raw: &str) -> ProgressInfo {
             continue;
         }
         // 速度：两个 token "1.23 MiB/s"
-        if i + 1 < n && contains_speed_unit(tokens[i + 1]) {
-            if is_number_token(t) {
-                speed = format!("{} {}", t, tokens[i + 1]);
-                i += 2;
-                continue;
-            }
+        if i + 1 < n && contains_speed_unit(tokens[i + 1]) && is_number_token(t) {
+            speed = format!("{} {}", t, tokens[i + 1]);
+            i += 2;
+            continue;
         }
         // 速度：单个 token "1.23MiB/s"
         if contains_speed_unit(t) {
@@ -154,14 +183,16 @@ pub fn parse_progress_info(raw: &str) -> ProgressInfo {
             continue;
         }
         // 大小：两个 token "200.0 KiB"（不跟 /s）
-        if i + 1 < n && contains_size_unit(tokens[i + 1]) && !contains_speed_unit(tokens[i + 1]) {
-            if is_number_token(t) {
-                if total_size.is_empty() {
-                    total_size = format!("{} {}", t, tokens[i + 1]);
-                }
-                i += 2;
-                continue;
+        if i + 1 < n
+            && contains_size_unit(tokens[i + 1])
+            && !contains_speed_unit(tokens[i + 1])
+            && is_number_token(t)
+        {
+            if total_size.is_empty() {
+                total_size = format!("{} {}", t, tokens[i + 1]);
             }
+            i += 2;
+            continue;
         }
         // 大小：单个 token "200.0KiB"
         if contains_size_unit(t) && !contains_speed_unit(t) {
@@ -187,7 +218,7 @@ pub fn parse_progress_info(raw: &str) -> ProgressInfo {
         };
     }
 
-    ProgressInfo { raw: trimmed.to_string(), label, total_size, speed, eta }
+    ProgressInfo { raw: trimmed.to_string(), label, total_size, speed, eta, percent }
 }
 
 fn is_number_token(s: &str) -> bool {
@@ -266,9 +297,44 @@ pub fn parse_search_output(output: &str, is_local: bool) -> Vec<PackageInfo> {
     results
 }
 
+/// 多值字段名（英文及中文语言环境），值之间以两个空格或换行分隔
+const MULTI_VALUE_KEYS: [&str; 23] = [
+    "Groups", "Licenses", "Provides", "Depends On", "Optional Deps", "Make Deps", "Check Deps",
+    "Required By", "Optional For", "Conflicts With", "Replaces",
+    "组", "许可协议", "提供", "依赖于", "可选依赖", "编译依赖", "检查依赖",
+    "依赖它", "要求被", "被可选依赖", "与它冲突", "取代",
+];
+
+/// 拆分多值字段的一行：条目间以两个及以上空格分隔，忽略 None/无
+fn split_multi_value(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split("  ")
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty() && *v != "None" && *v != "无")
+        .map(String::from)
+}
+
+/// 错误输出是否为网络问题（无法解析域名、连接失败、超时等）
+pub fn is_network_error(stderr: &str) -> bool {
+    const PATTERNS: [&str; 10] = [
+        "could not resolve",
+        "couldn't resolve",
+        "failed to connect",
+        "couldn't connect",
+        "connection timed out",
+        "operation timed out",
+        "network is unreachable",
+        "dns error",
+        "error sending request",
+        "无法解析",
+    ];
+    let lower = stderr.to_lowercase();
+    PATTERNS.iter().any(|p| lower.contains(p))
+}
+
 /// 解析 pacman -Qi / -Si 的详情输出
 pub fn parse_package_detail(output: &str) -> PackageDetail {
     let mut fields: Vec<(String, String)> = Vec::new();
+    let mut lists: HashMap<String, Vec<String>> = HashMap::new();
 
     for line in output.lines() {
         if let Some(colon_pos) = line.find(':') {
@@ -278,6 +344,12 @@ pub fn parse_package_detail(output: &str) -> PackageDetail {
             if !key_part.starts_with(' ') || key_part.trim().is_empty() {
                 let key = key_part.trim();
                 if !key.is_empty() {
+                    if MULTI_VALUE_KEYS.contains(&key) {
+                        let values: Vec<String> = split_multi_value(value_part).collect();
+                        if !values.is_empty() {
+                            lists.insert(key.to_string(), values);
+                        }
+                    }
                     fields.push((key.to_string(), value_part.to_string()));
                     continue;
                 }
@@ -287,58 +359,433 @@ pub fn parse_package_detail(output: &str) -> PackageDetail {
             let last = fields.last_mut().unwrap();
             last.1.push(' ');
             last.1.push_str(line.trim());
+            // 续行（如每行一条的可选依赖）追加到同一列表
+            if MULTI_VALUE_KEYS.contains(&last.0.as_str()) {
+                lists
+                    .entry(last.0.clone())
+                    .or_default()
+                    .extend(split_multi_value(line.trim()));
+            }
         }
     }
 
-    PackageDetail { fields }
+    PackageDetail { fields, lists }
+}
+
+/// 解析详情中的可选依赖，条目形如 "name: 说明 [installed]"
+///
+/// -Qi 会在已满足的条目后标注 [installed]/[已安装]；-Si 没有该标注，需调用方补充
+pub fn parse_optional_deps(detail: &PackageDetail) -> Vec<OptionalDep> {
+    detail
+        .get_list(&["Optional Deps", "可选依赖"])
+        .iter()
+        .filter_map(|entry| {
+            let mut entry = entry.trim();
+            let mut installed = false;
+            for marker in ["[installed]", "[已安装]"] {
+                if let Some(rest) = entry.strip_suffix(marker) {
+                    entry = rest.trim_end();
+                    installed = true;
+                }
+            }
+            let (spec, description) = match entry.split_once(": ") {
+                Some((spec, desc)) => (spec, desc.trim()),
+                None => (entry.trim_end_matches(':'), ""),
+            };
+            // 去掉版本约束，如 "python>=3.10"
+            let name = spec.split(['<', '>', '=']).next().unwrap_or(spec).trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some(OptionalDep {
+                name: name.to_string(),
+                description: description.to_string(),
+                installed,
+            })
+        })
+        .collect()
+}
+
+/// 解析多个包的 pacman -Qi/-Si 输出（包之间以空行分隔）
+pub fn parse_package_details(output: &str) -> Vec<PackageDetail> {
+    let mut details = Vec::new();
+    let mut block = String::new();
+    for line in output.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                details.push(parse_package_detail(&block));
+                block.clear();
+            }
+        } else {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    details
 }
 
 /// 解析 pacman -Qei 输出为 InstalledPackage 列表
 pub fn parse_installed_packages(output: &str) -> Vec<InstalledPackage> {
-    let mut packages = Vec::new();
-    let mut name = String::new();
-    let mut version = String::new();
-    let mut size = String::new();
-    let mut description = String::new();
+    parse_package_details(output)
+        .into_iter()
+        .filter_map(|d| {
+            let field = |keys: &[&str]| d.get(keys).unwrap_or_default().to_string();
+            let name = d.get(&["Name", "名称", "名字"])?.to_string();
+            Some(InstalledPackage {
+                name,
+                version: field(&["Version", "版本"]),
+                size: field(&["Installed Size", "安装大小", "安装后大小"]),
+                description: field(&["Description", "描述"]),
+                install_date: d.get(&["Install Date", "安装日期"]).and_then(parse_pacman_date),
+            })
+        })
+        .collect()
+}
 
-    for line in output.lines() {
-        if line.is_empty() || line.trim().is_empty() {
-            if !name.is_empty() {
-                packages.push(InstalledPackage {
-                    name: name.clone(),
-                    version: version.clone(),
-                    size: size.clone(),
-                    description: description.clone(),
-                });
-                name.clear();
-                version.clear();
-                size.clear();
-                description.clear();
+/// 解析待合并配置文件名：返回对应的在用配置路径，以及是否为 .pacsave
+///
+/// .pacsave 已存在时 pacman 会另存为 .pacsave.0、.pacsave.1 …，同样按 .pacsave 处理
+pub fn pacnew_target(path: &Path) -> Option<(PathBuf, bool)> {
+    let name = path.file_name()?.to_str()?;
+    if let Some(live) = name.strip_suffix(".pacnew") {
+        return (!live.is_empty()).then(|| (path.with_file_name(live), false));
+    }
+    let (live, suffix) = name.rsplit_once(".pacsave")?;
+    let numbered = suffix
+        .strip_prefix('.')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+    (!live.is_empty() && (suffix.is_empty() || numbered)).then(|| (path.with_file_name(live), true))
+}
+
+/// 解析 "name=version" 形式的固定版本写法，包名或版本不合法时返回 None
+pub fn parse_version_spec(spec: &str) -> Option<(&str, &str)> {
+    let (name, version) = spec.trim().split_once('=')?;
+    let name_ok = !name.is_empty()
+        && !name.starts_with(['-', '.'])
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "@._+-".contains(c));
+    let version_ok = !version.is_empty()
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || "._+:~-".contains(c));
+    (name_ok && version_ok).then_some((name, version))
+}
+
+/// 解析 pacman.conf 内容：[options] 中的 IgnorePkg / CacheDir，以及其余各节的仓库名
+///
+/// 同一选项可出现多次，值以空格分隔；注释掉的仓库不会出现在结果中
+pub fn parse_pacman_conf_text(content: &str) -> PacmanConf {
+    let mut conf = PacmanConf::default();
+    let mut in_options = false;
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_options = section == "options";
+            if !in_options {
+                conf.repos.push(section.to_string());
             }
             continue;
         }
+        if !in_options {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "IgnorePkg" => conf.ignore_pkg.extend(value.split_whitespace().map(String::from)),
+            "CacheDir" => conf.cache_dirs.extend(value.split_whitespace().map(Into::into)),
+            _ => {}
+        }
+    }
+    conf
+}
 
-        if let Some(colon) = line.find(':') {
-            let key = line[..colon].trim();
-            let val = line[colon + 1..].trim();
-            match key {
-                "Name" | "名称" | "名字" => name = val.to_string(),
-                "Version" | "版本" => version = val.to_string(),
-                "Installed Size" | "安装大小" | "安装后大小" => size = val.to_string(),
-                "Description" | "描述" => description = val.to_string(),
-                _ => {}
+/// 解析多个包的 pacman -Si 输出，提取每个包的下载/安装大小
+pub fn parse_package_sizes(output: &str) -> HashMap<String, PackageSize> {
+    parse_package_details(output)
+        .into_iter()
+        .filter_map(|d| {
+            let name = d.get(&["Name", "名称", "名字"])?.to_string();
+            let size = PackageSize {
+                download: d.get(&["Download Size", "下载大小"]).unwrap_or_default().to_string(),
+                installed: d
+                    .get(&["Installed Size", "安装大小", "安装后大小"])
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            Some((name, size))
+        })
+        .collect()
+}
+
+/// 解析 pacman 在 LC_TIME=C 下输出的日期，如 "Wed Jan 15 10:23:45 2025"
+pub fn parse_pacman_date(s: &str) -> Option<NaiveDateTime> {
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&s, "%a %b %e %H:%M:%S %Y").ok()
+}
+
+/// 解析 pacman.log 中 [ALPM] 的包操作行，按日志顺序返回
+///
+/// 行形如 `[2025-01-15T10:23:45+0800] [ALPM] upgraded firefox (134.0-1 -> 134.0.1-1)`，
+/// 也兼容旧版的 `[2019-01-15 10:23]` 时间格式；其余行（钩子、事务起止等）忽略
+pub fn parse_pacman_log(content: &str) -> Vec<PacmanLogEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (time, rest) = line.strip_prefix('[')?.split_once("] ")?;
+            let rest = rest.strip_prefix("[ALPM] ")?;
+            let (verb, rest) = rest.split_once(' ')?;
+            let action = LogAction::ALL.into_iter().find(|a| a.name() == verb)?;
+            let (name, version) = rest.split_once(" (")?;
+            let time = DateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%z")
+                .map(|t| t.naive_local())
+                .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M"))
+                .ok()?;
+            Some(PacmanLogEntry {
+                time,
+                action,
+                name: name.to_string(),
+                version: version.trim_end_matches(')').to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 将字节数格式化为 "4.50 MiB" 形式
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.2} {}", value, UNITS[unit])
+    }
+}
+
+/// 将 "4.50 MiB" 这类大小文本解析为字节数，无法解析时返回 0
+///
+/// 单位可紧贴数字（"4.5MiB"）、大小写不限；缺省单位按字节计。
+pub fn parse_size(s: &str) -> u64 {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    // 部分语言环境使用逗号作为小数点
+    let Ok(value) = number.replace(',', ".").parse::<f64>() else {
+        return 0;
+    };
+    let multiplier: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" | "byte" | "bytes" => 1.0,
+        "kib" | "kb" | "k" => 1024.0,
+        "mib" | "mb" | "m" => 1024.0 * 1024.0,
+        "gib" | "gb" | "g" => 1024.0 * 1024.0 * 1024.0,
+        "tib" | "tb" | "t" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return 0,
+    };
+    (value * multiplier).max(0.0) as u64
+}
+
+/// 解析 pacman -Qe 输出为 (包名, 版本) 列表
+fn parse_explicit_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next().unwrap_or("");
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// 比较更新前后的 pacman -Qe 输出，返回 (新增, 移除) 的包
+///
+/// 仅版本变化的包不计入新增/移除，见 `diff_explicit_versions`。
+pub fn diff_explicit_packages(before: &str, after: &str) -> (Vec<String>, Vec<String>) {
+    let before_list = parse_explicit_list(before);
+    let after_list = parse_explicit_list(after);
+    let before_names: std::collections::HashSet<&str> =
+        before_list.iter().map(|(n, _)| n.as_str()).collect();
+    let after_names: std::collections::HashSet<&str> =
+        after_list.iter().map(|(n, _)| n.as_str()).collect();
+
+    let added = after_list
+        .iter()
+        .filter(|(n, _)| !before_names.contains(n.as_str()))
+        .map(|(n, v)| format!("{} {}", n, v))
+        .collect();
+    let removed = before_list
+        .iter()
+        .filter(|(n, _)| !after_names.contains(n.as_str()))
+        .map(|(n, v)| format!("{} {}", n, v))
+        .collect();
+
+    (added, removed)
+}
+
+/// 比较更新前后的 pacman -Qe 输出，返回仅版本变化的包 (包名, 旧版本, 新版本)
+pub fn diff_explicit_versions(before: &str, after: &str) -> Vec<(String, String, String)> {
+    let before_map: std::collections::HashMap<String, String> =
+        parse_explicit_list(before).into_iter().collect();
+    parse_explicit_list(after)
+        .into_iter()
+        .filter_map(|(name, new_ver)| {
+            let old_ver = before_map.get(&name)?;
+            if *old_ver != new_ver {
+                Some((name, old_ver.clone(), new_ver))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 安全敏感的包（精确匹配）
+const SECURITY_PACKAGES: &[&str] = &[
+    "openssl", "openssh", "sudo", "opendoas", "glibc", "gnutls", "gnupg", "gpgme",
+    "polkit", "pam", "shadow", "systemd", "curl", "ca-certificates", "nss", "krb5",
+    "xz", "zlib", "libgcrypt", "wpa_supplicant", "networkmanager",
+];
+
+/// 内核包名：linux / linux-lts / linux-zen / linux-hardened / linux-rt 等
+static KERNEL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^linux(-lts|-zen|-hardened|-rt(-lts)?)?$").unwrap());
+
+/// 安全相关包名模式：内核及头文件、固件、微码、ssl/tls 库
+static SECURITY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(linux(-[a-z]+)*|.*-firmware|.*-ucode|.*ssl.*|.*tls.*|lib(sodium|ssh.*|seccomp))$")
+        .unwrap()
+});
+
+/// 更新后建议重启（或至少重新登录）才能完全生效的关键包
+const CRITICAL_PACKAGES: &[&str] = &[
+    "systemd", "systemd-libs", "glibc", "dbus", "linux-firmware", "amd-ucode", "intel-ucode",
+    "mesa", "nvidia", "nvidia-open", "nvidia-dkms", "nvidia-utils",
+];
+
+/// 在更新列表（"name old -> new"）中查找关键包，返回包名
+pub fn find_critical_updates(updates: &[String]) -> Vec<String> {
+    updates
+        .iter()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| CRITICAL_PACKAGES.contains(name))
+        .map(String::from)
+        .collect()
+}
+
+/// 签名密钥环包：过旧时新打包者签名的包会校验失败，需先于其余包更新
+pub const KEYRING_PACKAGE: &str = "archlinux-keyring";
+
+/// 更新列表（"name old -> new"）中是否包含 archlinux-keyring
+pub fn has_keyring_update(updates: &[String]) -> bool {
+    updates
+        .iter()
+        .any(|line| line.split_whitespace().next() == Some(KEYRING_PACKAGE))
+}
+
+/// 是否为安全敏感的包（用于更新预览中的 🔒 标记）
+pub fn is_security_sensitive(name: &str) -> bool {
+    SECURITY_PACKAGES.contains(&name) || SECURITY_RE.is_match(name)
+}
+
+/// 在更新列表（"name old -> new"）中查找版本发生变化的内核包
+///
+/// 返回 (包名, 旧版本, 新版本)，内核更新后需要重启才能生效。
+pub fn find_kernel_update(updates: &[String]) -> Option<(String, String, String)> {
+    updates.iter().find_map(|line| {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.as_slice() {
+            [name, old, "->", new, ..] if KERNEL_RE.is_match(name) && old != new => {
+                Some((name.to_string(), old.to_string(), new.to_string()))
             }
+            _ => None,
         }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_strips_osc_terminated_by_bel() {
+        assert_eq!(
+            clean_terminal_output("\x1b]0;paru: updating\x07:: Synchronizing package databases..."),
+            ":: Synchronizing package databases..."
+        );
     }
 
-    if !name.is_empty() {
-        packages.push(InstalledPackage {
-            name,
-            version,
-            size,
-            description,
-        });
+    #[test]
+    fn clean_strips_osc_terminated_by_st() {
+        assert_eq!(clean_terminal_output("\x1b]2;paru\x1b\\ core is up to date"), " core is up to date");
     }
 
-    packages
+    #[test]
+    fn clean_strips_charset_switch() {
+        assert_eq!(clean_terminal_output("\x1b(B\x1b[m==> Making package: foo 1.0-1"), "==> Making package: foo 1.0-1");
+    }
+
+    #[test]
+    fn clean_strips_csi_colour_and_erase() {
+        let raw = "\x1b[1;34m::\x1b[0;1m Synchronizing package databases...\x1b[0m\x1b[K\r\n \x1b[1;32mextra\x1b[0m is up to date\r\n";
+        assert_eq!(clean_terminal_output(raw), ":: Synchronizing package databases...\n extra is up to date");
+    }
+
+    #[test]
+    fn version_spec_pins_exact_version() {
+        assert_eq!(parse_version_spec("linux=6.9.1.arch1-1"), Some(("linux", "6.9.1.arch1-1")));
+        assert_eq!(parse_version_spec(" python-foo=1:2.0-3 "), Some(("python-foo", "1:2.0-3")));
+    }
+
+    #[test]
+    fn version_spec_rejects_comparisons() {
+        assert_eq!(parse_version_spec("linux>=6.9"), None);
+        assert_eq!(parse_version_spec("linux<=6.9"), None);
+    }
+
+    #[test]
+    fn version_spec_rejects_invalid_input() {
+        assert_eq!(parse_version_spec("linux"), None);
+        assert_eq!(parse_version_spec("=1.0"), None);
+        assert_eq!(parse_version_spec("linux="), None);
+        assert_eq!(parse_version_spec("-linux=1.0"), None);
+        assert_eq!(parse_version_spec("Linux=1.0"), None);
+        assert_eq!(parse_version_spec("linux=1.0 bad"), None);
+    }
+
+    #[test]
+    fn pacnew_target_maps_to_live_config() {
+        assert_eq!(
+            pacnew_target(Path::new("/etc/pacman.conf.pacnew")),
+            Some((PathBuf::from("/etc/pacman.conf"), false))
+        );
+        assert_eq!(
+            pacnew_target(Path::new("/etc/ssh/sshd_config.pacsave")),
+            Some((PathBuf::from("/etc/ssh/sshd_config"), true))
+        );
+    }
+
+    #[test]
+    fn pacnew_target_accepts_numbered_pacsave() {
+        assert_eq!(
+            pacnew_target(Path::new("/etc/foo.conf.pacsave.0")),
+            Some((PathBuf::from("/etc/foo.conf"), true))
+        );
+        assert_eq!(
+            pacnew_target(Path::new("/etc/foo.conf.pacsave.12")),
+            Some((PathBuf::from("/etc/foo.conf"), true))
+        );
+    }
+
+    #[test]
+    fn pacnew_target_rejects_other_files() {
+        assert_eq!(pacnew_target(Path::new("/etc/foo.conf")), None);
+        assert_eq!(pacnew_target(Path::new("/etc/foo.conf.pacsave.old")), None);
+        assert_eq!(pacnew_target(Path::new("/etc/foo.conf.pacsave.")), None);
+        assert_eq!(pacnew_target(Path::new("/etc/.pacnew")), None);
+    }
 }
@@ -0,0 +1,66 @@
+//! 提权命令：默认 sudo，可通过 `privilege_command` 配置为 doas、pkexec 等
+//!
+//! 启动时由 [`set_command`] 写入，所有需要 root 的调用都经由这里构造命令。
+
+use std::process::Command;
+use std::sync::RwLock;
+
+static COMMAND: RwLock<String> = RwLock::new(String::new());
+
+/// 设置提权命令，空字符串视为 sudo
+pub fn set_command(cmd: &str) {
+    if let Ok(mut current) = COMMAND.write() {
+        *current = cmd.trim().to_string();
+    }
+}
+
+/// 当前提权命令名
+pub fn name() -> String {
+    COMMAND
+        .read()
+        .ok()
+        .map(|c| c.clone())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| "sudo".to_string())
+}
+
+/// 构造提权命令；non_interactive 时尽量加上 -n，凭证失效时直接失败而不是等待输入
+///
+/// pkexec 等没有非交互选项的命令原样返回
+pub fn command(non_interactive: bool) -> Command {
+    let name = name();
+    let mut cmd = Command::new(&name);
+    if non_interactive && matches!(name.as_str(), "sudo" | "doas") {
+        cmd.arg("-n");
+    }
+    cmd
+}
+
+/// 鉴权参数：sudo 用 -v 只刷新凭证，doas 等没有 -v 的命令执行一次 true
+///（doas 需在 doas.conf 中启用 persist 才能缓存凭证）
+pub fn validate_args() -> &'static [&'static str] {
+    if name() == "sudo" {
+        &["-v"]
+    } else {
+        &["true"]
+    }
+}
+
+/// 非交互刷新凭证的参数；pkexec 等每次都会弹出认证时返回 None
+pub fn refresh_args() -> Option<&'static [&'static str]> {
+    match name().as_str() {
+        "sudo" => Some(&["-n", "-v"]),
+        "doas" => Some(&["-n", "true"]),
+        _ => None,
+    }
+}
+
+/// 传给 paru / yay 的提权参数（两者都支持 --sudo 指定命令）
+pub fn aur_helper_args() -> Vec<String> {
+    let name = name();
+    if name == "sudo" {
+        Vec::new()
+    } else {
+        vec!["--sudo".to_string(), name]
+    }
+}
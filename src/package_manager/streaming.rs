@@ -1,17 +1,20 @@
 //! 流式命令执行（update / install / remove）
 
-use super::parser::clean_terminal_output;
-use super::types::UpdateOutput;
+use super::parser::{clean_terminal_output, KEYRING_PACKAGE};
+use super::privilege;
+use super::types::{RemoveVariant, UpdateOutput};
 use super::PackageManager;
 use anyhow::Result;
 use std::io::Read;
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 /// 尝试删除 pacman db.lck（仅在确认没有 pacman 进程在运行时调用）
 ///
-/// 锁文件归 root 所有，普通用户无写权限，必须通过 `sudo -n rm -f` 删除。
+/// 锁文件归 root 所有，普通用户无写权限，必须通过 `sudo -n rm -f`（或配置的提权命令）删除。
 /// `-n`（non-interactive）保证若 sudo 凭证已过期时静默失败，不阻塞 TUI。
 fn try_remove_db_lock() {
     let lock_path = "/var/lib/pacman/db.lck";
@@ -26,17 +29,62 @@ fn try_remove_db_lock() {
         .unwrap_or(false);
     if !any_pacman {
         // 锁文件属于 root，必须借助 sudo 删除；-n 保证非交互静默失败
-        let status = std::process::Command::new("sudo")
-            .args(["-n", "rm", "-f", lock_path])
+        let status = privilege::command(true)
+            .args(["rm", "-f", lock_path])
             .status();
         if let Ok(s) = status {
             if !s.success() {
-                log::warn!("try_remove_db_lock: sudo rm -f {} 失败（exit={:?}）", lock_path, s.code());
+                log::warn!("try_remove_db_lock: {} rm -f {} 失败（exit={:?}）", privilege::name(), lock_path, s.code());
             }
         }
     }
 }
 
+/// 操作进行期间定期执行 `sudo -n -v` 刷新凭证
+///
+/// 长时间的 AUR 构建可能超过 sudo 的凭证有效期，之后的 sudo 调用会等待密码而卡住。
+/// `-n` 保证凭证已失效时静默失败而不是阻塞；drop 时后台线程在 1 秒内退出。
+/// 提权命令不支持非交互刷新（如 pkexec）时不启动。
+pub struct SudoKeepalive {
+    stop: Arc<AtomicBool>,
+}
+
+impl SudoKeepalive {
+    /// 启动刷新线程，interval_secs 为 0 时不启动
+    pub fn start(interval_secs: u64) -> Option<Self> {
+        if interval_secs == 0 {
+            return None;
+        }
+        let args = privilege::refresh_args()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let interval = Duration::from_secs(interval_secs);
+        std::thread::spawn(move || {
+            let mut last = Instant::now();
+            while !flag.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_secs(1));
+                if last.elapsed() < interval || flag.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let _ = privilege::command(false)
+                    .args(args)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status();
+                last = Instant::now();
+            }
+        });
+        Some(Self { stop })
+    }
+}
+
+impl Drop for SudoKeepalive {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
 /// 全局变量用于存储当前运行的子进程 PID
 static CHILD_PID: AtomicU32 = AtomicU32::new(0);
 static SHOULD_CANCEL: AtomicBool = AtomicBool::new(false);
@@ -303,7 +351,7 @@ fn run_streaming_command(
     use std::os::unix::process::CommandExt;
 
     let mut child = if pm.command == "pacman" {
-        let mut cmd = Command::new("sudo");
+        let mut cmd = privilege::command(false);
         let mut args: Vec<&str> = vec!["pacman"];
         args.extend_from_slice(pacman_args);
         let pkg_refs: Vec<&str> = extra_packages.iter().map(|s| s.as_str()).collect();
@@ -322,7 +370,8 @@ fn run_streaming_command(
         cmd.spawn()?
     } else {
         let mut cmd = Command::new(&pm.command);
-        let mut args: Vec<String> = aur_args.iter().map(|s| s.to_string()).collect();
+        let mut args = privilege::aur_helper_args();
+        args.extend(aur_args.iter().map(|s| s.to_string()));
         args.extend(extra_packages.iter().cloned());
         cmd.args(&args);
         cmd.stdout(Stdio::piped());
@@ -367,9 +416,8 @@ fn run_streaming_command(
         // 超时才升级为 SIGKILL（最后手段，不触发 pacman 清理）
         let deadline = std::time::Instant::now() + std::time::Duration::from_secs(6);
         loop {
-            match child.try_wait() {
-                Ok(Some(_)) => break,
-                _ => {}
+            if let Ok(Some(_)) = child.try_wait() {
+                break;
             }
             if std::time::Instant::now() >= deadline {
                 let pid = CHILD_PID.load(Ordering::SeqCst);
@@ -403,46 +451,55 @@ fn run_streaming_command(
 
 impl PackageManager {
     /// 执行系统更新命令（流式输出）
+    /// packages 为空时完整更新 (-Syu)，否则刷新数据库后只升级指定包 (-Sy，部分更新)
+    /// 部分更新也要先 -y：预览来自 checkupdates 的临时数据库，直接 -S 会按未刷新的本地数据库安装旧版本
     pub fn update_streaming(
         &self,
+        packages: &[String],
         output_tx: mpsc::UnboundedSender<String>,
     ) -> Result<UpdateOutput> {
-        run_streaming_command(
-            self,
-            &["-Syu", "--noconfirm"],
-            &["-Syu", "--noconfirm"],
-            &[],
-            output_tx,
-            "更新",
-        )
+        let args: &[&str] = if packages.is_empty() {
+            &["-Syu", "--noconfirm"]
+        } else {
+            &["-Sy", "--noconfirm"]
+        };
+        run_streaming_command(self, args, args, packages, output_tx, "更新")
+    }
+
+    /// 刷新数据库并单独更新 archlinux-keyring (-Sy archlinux-keyring)，避免新包因签名密钥缺失校验失败
+    pub fn keyring_streaming(
+        &self,
+        output_tx: mpsc::UnboundedSender<String>,
+    ) -> Result<UpdateOutput> {
+        let args = ["-Sy", "--noconfirm"];
+        run_streaming_command(self, &args, &args, &[KEYRING_PACKAGE.to_string()], output_tx, "密钥环更新")
     }
 
     /// 执行安装命令（流式输出）
+    /// extra_flags: 追加在 -S --noconfirm 之后的参数，如 --asexplicit
     pub fn install_streaming(
         &self,
         packages: &[String],
+        extra_flags: &[String],
         output_tx: mpsc::UnboundedSender<String>,
     ) -> Result<UpdateOutput> {
-        run_streaming_command(
-            self,
-            &["-S", "--noconfirm"],
-            &["-S", "--noconfirm"],
-            packages,
-            output_tx,
-            "安装",
-        )
+        let mut args = vec!["-S", "--noconfirm"];
+        args.extend(extra_flags.iter().map(|s| s.as_str()));
+        run_streaming_command(self, &args, &args, packages, output_tx, "安装")
     }
 
     /// 执行卸载命令（流式输出）
     pub fn remove_streaming(
         &self,
         packages: &[String],
+        variant: RemoveVariant,
         output_tx: mpsc::UnboundedSender<String>,
     ) -> Result<UpdateOutput> {
+        let flags = [variant.flag(), "--noconfirm"];
         run_streaming_command(
             self,
-            &["-Rns", "--noconfirm"],
-            &["-Rns", "--noconfirm"],
+            &flags,
+            &flags,
             packages,
             output_tx,
             "卸载",
@@ -507,9 +564,8 @@ pub fn run_custom_command_streaming(
     if cancelled {
         let deadline = std::time::Instant::now() + std::time::Duration::from_secs(6);
         loop {
-            match child.try_wait() {
-                Ok(Some(_)) => break,
-                _ => {}
+            if let Ok(Some(_)) = child.try_wait() {
+                break;
             }
             if std::time::Instant::now() >= deadline {
                 let pid = CHILD_PID.load(Ordering::SeqCst);
@@ -539,3 +595,14 @@ pub fn run_custom_command_streaming(
         success: status.success(),
     })
 }
+
+/// 执行更新前钩子（如快照命令），经 sh -c 运行以支持引号与管道；失败或被取消时返回错误，调用方应中止更新
+pub fn run_pre_update_hook(command: &str, output_tx: mpsc::UnboundedSender<String>) -> Result<()> {
+    let _ = output_tx.send(format!("$ {}", command));
+    let parts = vec!["sh".to_string(), "-c".to_string(), command.to_string()];
+    let output = run_custom_command_streaming(parts, output_tx)?;
+    if let Some(reason) = output.failure_reason() {
+        anyhow::bail!("更新前命令失败，已中止更新: {}", reason);
+    }
+    Ok(())
+}
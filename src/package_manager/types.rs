@@ -1,21 +1,105 @@
 //! PackageManager 相关数据类型定义
 
-/// 命令输出结果
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// 流式命令（更新 / 安装 / 卸载 / 自定义命令）的完整输出
+///
+/// stdout / stderr 为去除终端控制序列后的文本，不含进度刷新行；被取消时 stderr 为 "…已取消"
 #[derive(Debug, Clone)]
 pub struct UpdateOutput {
     pub stdout: String,
     pub stderr: String,
+    /// 进程退出码为 0
     pub success: bool,
 }
 
 impl UpdateOutput {
+    /// stdout 与 stderr 合并，用作 AI 分析的日志
     pub fn combined_output(&self) -> String {
         format!("{}\n{}", self.stdout, self.stderr)
     }
+
+    /// 输出中包含 PGP 签名校验失败（通常是 archlinux-keyring 过旧）
+    pub fn has_signature_error(&self) -> bool {
+        const PATTERNS: [&str; 4] = [
+            "invalid or corrupted package (PGP signature)",
+            "无效或已损坏的软件包 (PGP 签名)",
+            "is unknown trust",
+            "is marginal trust",
+        ];
+        let log = self.combined_output();
+        PATTERNS.iter().any(|p| log.contains(p))
+    }
+
+    /// 失败时的简短原因：stderr 最后一行非空内容
+    pub fn failure_reason(&self) -> Option<&str> {
+        if self.success {
+            return None;
+        }
+        Some(
+            self.stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or("命令执行失败"),
+        )
+    }
+}
+
+/// 卸载方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RemoveVariant {
+    /// -Rns：同时删除配置文件和孤立依赖
+    #[default]
+    Rns,
+    /// -Rs：删除孤立依赖，保留配置文件
+    Rs,
+    /// -R：只删除包本身，保留依赖和配置文件
+    R,
+}
+
+impl RemoveVariant {
+    pub const ALL: [RemoveVariant; 3] = [RemoveVariant::Rns, RemoveVariant::Rs, RemoveVariant::R];
+
+    /// pacman 参数，如 "-Rns"
+    pub fn flag(&self) -> &'static str {
+        match self {
+            RemoveVariant::Rns => "-Rns",
+            RemoveVariant::Rs => "-Rs",
+            RemoveVariant::R => "-R",
+        }
+    }
+
+    /// 报告中的操作标识，如 "Rns"
+    pub fn operation(&self) -> &'static str {
+        &self.flag()[1..]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RemoveVariant::Rns => "删除配置和孤立依赖",
+            RemoveVariant::Rs => "保留配置",
+            RemoveVariant::R => "保留依赖和配置",
+        }
+    }
+
+    /// 是否连带删除孤立依赖
+    pub fn removes_deps(&self) -> bool {
+        matches!(self, RemoveVariant::Rns | RemoveVariant::Rs)
+    }
+
+    pub fn next(&self) -> RemoveVariant {
+        let pos = Self::ALL.iter().position(|v| v == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
 }
 
 /// 搜索结果条目
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PackageInfo {
     pub repo: String,
     pub name: String,
@@ -24,10 +108,46 @@ pub struct PackageInfo {
     pub installed: bool,
 }
 
+/// 远程包的下载/安装大小（pacman -Si 原文，如 "4.50 MiB"）
+#[derive(Debug, Clone, Default)]
+pub struct PackageSize {
+    pub download: String,
+    pub installed: String,
+}
+
+/// 可选依赖条目（来自 -Qi / -Si 的 Optional Deps 字段）
+#[derive(Debug, Clone)]
+pub struct OptionalDep {
+    pub name: String,
+    pub description: String,
+    pub installed: bool,
+}
+
 /// 包详情
 #[derive(Debug, Clone)]
 pub struct PackageDetail {
+    /// 原始字段（多行值以空格拼接）
     pub fields: Vec<(String, String)>,
+    /// 多值字段（依赖、可选依赖等）拆分后的条目，键与 fields 相同；值为 None 时不记录
+    pub lists: HashMap<String, Vec<String>>,
+}
+
+impl PackageDetail {
+    /// 按字段名（可传入多个语言的名称）取值
+    pub fn get(&self, keys: &[&str]) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| keys.contains(&k.as_str()))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 按字段名取多值字段的条目列表，不存在时返回空
+    pub fn get_list(&self, keys: &[&str]) -> &[String] {
+        keys.iter()
+            .find_map(|k| self.lists.get(*k))
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
 }
 
 /// 已安装包信息
@@ -37,4 +157,109 @@ pub struct InstalledPackage {
     pub version: String,
     pub size: String,
     pub description: String,
+    /// 安装时间（解析失败时为 None）
+    pub install_date: Option<NaiveDateTime>,
+}
+
+/// pacman.log 中记录的包操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogAction {
+    Installed,
+    Upgraded,
+    Downgraded,
+    Reinstalled,
+    Removed,
+}
+
+impl LogAction {
+    pub const ALL: [LogAction; 5] = [
+        LogAction::Installed,
+        LogAction::Upgraded,
+        LogAction::Downgraded,
+        LogAction::Reinstalled,
+        LogAction::Removed,
+    ];
+
+    /// 日志中的动词，如 "upgraded"
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogAction::Installed => "installed",
+            LogAction::Upgraded => "upgraded",
+            LogAction::Downgraded => "downgraded",
+            LogAction::Reinstalled => "reinstalled",
+            LogAction::Removed => "removed",
+        }
+    }
+}
+
+/// pacman.log 中的一条包操作记录
+#[derive(Debug, Clone)]
+pub struct PacmanLogEntry {
+    /// 日志时间（按日志中的本地时间）
+    pub time: NaiveDateTime,
+    pub action: LogAction,
+    pub name: String,
+    /// 括号内的版本，升级 / 降级时形如 "1.0-1 -> 1.1-1"
+    pub version: String,
+}
+
+impl PacmanLogEntry {
+    /// 操作后的版本（升级 / 降级取箭头右侧）
+    pub fn new_version(&self) -> &str {
+        self.version.rsplit(" -> ").next().unwrap_or(&self.version)
+    }
+}
+
+/// 已安装包的构成
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackageCounts {
+    /// 显式安装 (pacman -Qqe)
+    pub explicit: usize,
+    /// 作为依赖安装 (pacman -Qqd)
+    pub dependencies: usize,
+    /// 不在同步数据库中的外部包，通常来自 AUR (pacman -Qqm)
+    pub foreign: usize,
+}
+
+/// /etc/pacman.conf 中与本工具相关的设置
+#[derive(Debug, Clone, Default)]
+pub struct PacmanConf {
+    /// 启用的仓库（按配置顺序，不含 [options]）
+    pub repos: Vec<String>,
+    /// IgnorePkg 条目，可含 * ? 通配符
+    pub ignore_pkg: Vec<String>,
+    /// CacheDir 条目，未配置时为空
+    pub cache_dirs: Vec<PathBuf>,
+}
+
+impl PacmanConf {
+    /// 包是否被 IgnorePkg 忽略
+    pub fn is_ignored(&self, name: &str) -> bool {
+        self.ignore_pkg.iter().any(|pattern| glob_match(pattern, name))
+    }
+}
+
+/// 简单的 shell 通配符匹配：* 匹配任意长度，? 匹配单个字符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 * 的位置及其当时对应的文本位置，失配时回溯
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
@@ -1,5 +1,13 @@
 use crate::sysinfo::SystemInfo;
 
+/// 输出语言指令，默认中文时返回空串
+fn language_instruction(language: &str) -> &'static str {
+    match language {
+        "en" => "\n【输出语言】Respond in English. 整份报告（包括模板中的标题和标注）都使用英文。\n",
+        _ => "",
+    }
+}
+
 /// 生成用于 AI 的系统更新分析提示词
 pub fn generate_analysis_prompt(
     package_manager: &str,
@@ -7,6 +15,7 @@ pub fn generate_analysis_prompt(
     packages_before: Option<&str>,
     packages_after: Option<&str>,
     system_info: Option<&SystemInfo>,
+    language: &str,
 ) -> String {
     // 从系统信息中获取发行版名称，没有则使用通用说法
     let distro_name = system_info
@@ -15,7 +24,6 @@ pub fn generate_analysis_prompt(
 
     let mut prompt = format!(
         "# {distro_name} 系统更新分析任务\n\n\
-          你是一个专业的 Linux 系统管理员和软件包分析专家。\n\
           请仔细分析以下系统更新日志，生成一份结构化的更新报告。\n\n",
     );
 
@@ -50,6 +58,10 @@ pub fn generate_analysis_prompt(
   （新特性、安全修复、已知问题、重大架构变更等）
 3. 对于涉及内核、显卡驱动、桌面环境等关键组件的更新，重点说明影响和注意事项
 4. 如果有重大变更（如 NVIDIA 开源内核模块、重大 API 变更等），着重提醒
+5. 明确标出安全相关的更新：修复了已知 CVE 的包，以及 linux 内核、openssl、openssh、
+  sudo、glibc、systemd 等安全敏感组件，在重点关注中用 [安全] 标注
+6. 列出需要重启（内核、微码、显卡驱动、glibc、systemd 等）或重新登录才能生效的更新，
+  并对照版本清单确认内核版本是否确实发生变化
 
 ## 输出格式要求
 
@@ -61,7 +73,7 @@ pub fn generate_analysis_prompt(
 3. 列表用 * 或 -
 4. 版本变更严格对齐，每列固定宽度，用空格填充：
   包名（左对齐，24字符宽）  旧版本（左对齐，16字符宽）  新版本
-5. 重要程度用 [警告] [注意] [正常] 标注
+5. 重要程度用 [安全] [警告] [注意] [正常] 标注
 6. 禁止使用表格、Unicode 方框字符、Markdown 语法
 
 ## 报告结构
@@ -170,6 +182,88 @@ firefox                   133.0             134.0.1
 10. 超出你知识范围的版本，明确说明并建议查阅官方 changelog
 "#,
     );
+    prompt.push_str(language_instruction(language));
+
+    prompt
+}
+
+/// 生成安装结果分析提示词
+pub fn generate_install_prompt(
+    package_manager: &str,
+    packages: &[String],
+    install_log: &str,
+    system_info: Option<&SystemInfo>,
+    language: &str,
+) -> String {
+    let mut prompt = format!(
+        "以下是在 {} 系统上使用 {} 安装软件包的日志。\n\
+         安装的包: {}\n\n\
+         安装日志:\n{}\n\n\
+         请简要分析安装结果，说明是否成功，安装了哪些包及其依赖，是否有需要注意的问题。",
+        system_info.map(|i| i.distro.as_str()).unwrap_or("Linux"),
+        package_manager,
+        packages.join(", "),
+        install_log
+    );
+    prompt.push_str(language_instruction(language));
+    prompt
+}
 
+/// 生成事务计划结果分析提示词（先卸载后安装，合并为一份报告）
+pub fn generate_plan_prompt(
+    package_manager: &str,
+    removed: &[String],
+    remove_flag: &str,
+    installed: &[String],
+    plan_log: &str,
+    system_info: Option<&SystemInfo>,
+    language: &str,
+) -> String {
+    let list = |packages: &[String]| {
+        if packages.is_empty() {
+            "无".to_string()
+        } else {
+            packages.join(", ")
+        }
+    };
+    let mut prompt = format!(
+        "以下是在 {} 系统上使用 {} 执行事务计划的日志：先卸载 ({}) 再安装，前一步失败时跳过后续步骤。\n\
+         卸载的包: {}\n\
+         安装的包: {}\n\n\
+         事务日志:\n{}\n\n\
+         请简要分析整个事务的结果，分别说明卸载和安装是否成功、涉及哪些包及其依赖，\
+         以及两步之间是否有需要注意的问题（如被替换的包、残留的配置、被跳过的步骤）。",
+        system_info.map(|i| i.distro.as_str()).unwrap_or("Linux"),
+        package_manager,
+        remove_flag,
+        list(removed),
+        list(installed),
+        plan_log
+    );
+    prompt.push_str(language_instruction(language));
+    prompt
+}
+
+/// 生成卸载结果分析提示词
+pub fn generate_remove_prompt(
+    package_manager: &str,
+    packages: &[String],
+    remove_flag: &str,
+    remove_log: &str,
+    system_info: Option<&SystemInfo>,
+    language: &str,
+) -> String {
+    let mut prompt = format!(
+        "以下是在 {} 系统上使用 {} {} 卸载软件包的日志。\n\
+         卸载的包: {}\n\n\
+         卸载日志:\n{}\n\n\
+         请简要分析卸载结果，说明是否成功，移除了哪些包及其依赖和配置，是否有需要注意的问题。",
+        system_info.map(|i| i.distro.as_str()).unwrap_or("Linux"),
+        package_manager,
+        remove_flag,
+        packages.join(", "),
+        remove_log
+    );
+    prompt.push_str(language_instruction(language));
     prompt
 }
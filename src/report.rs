@@ -1,25 +1,99 @@
+use crate::config::{default_report_filename, Config, ReportFormat, ReportMode};
 use anyhow::Result;
-use chrono::Local;
-use std::fs;
-use std::path::PathBuf;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// 进程内追加写的互斥锁，跨进程由 flock 保证
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// JSON 格式报告的内容
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    timestamp: String,
+    distro: &'a str,
+    operation: &'a str,
+    analysis: &'a str,
+    log: &'a str,
+}
 
 pub struct ReportSaver {
     base_dir: PathBuf,
+    format: ReportFormat,
+    mode: ReportMode,
+    /// 文件名模板（不含扩展名）
+    filename_template: String,
+    /// 最多保留的报告数 (0 = 不限制)
+    max_files: usize,
 }
 
 impl ReportSaver {
     pub fn new(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+        Self {
+            base_dir,
+            format: ReportFormat::default(),
+            mode: ReportMode::default(),
+            filename_template: default_report_filename(),
+            max_files: 0,
+        }
+    }
+
+    /// 按配置创建（报告目录、格式、保存方式、文件名模板、保留数量）
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.report_dir.clone())
+            .with_format(config.report_format)
+            .with_mode(config.report_mode)
+            .with_filename_template(&config.report_filename)
+            .with_max_files(config.max_reports)
+    }
+
+    pub fn with_filename_template(mut self, template: &str) -> Self {
+        if !template.trim().is_empty() {
+            self.filename_template = template.to_string();
+        }
+        self
+    }
+
+    pub fn with_format(mut self, format: ReportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ReportMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
     }
 
-    /// operation: 操作类型标识，如 "Syu"(更新), "S"(安装), "Rns"(卸载)
-    pub fn save(&self, content: &str, distro_name: &str, operation: &str) -> Result<PathBuf> {
+    /// operation: 操作类型标识，如 "Syu"(更新), "S"(安装), "Rns"/"Rs"/"R"(卸载), "plan"(事务计划)
+    /// log: 包管理器输出，仅 JSON 格式写入
+    pub fn save(
+        &self,
+        content: &str,
+        log: &str,
+        distro_name: &str,
+        operation: &str,
+    ) -> Result<PathBuf> {
         // 创建基础目录
         fs::create_dir_all(&self.base_dir)?;
 
         // 获取当前时间
         let now = Local::now();
 
+        if self.mode == ReportMode::Append {
+            return self.append(content, log, distro_name, operation, &now);
+        }
+
         // 创建目录结构: YYYY/MM/DD/
         let year = now.format("%Y").to_string();
         let month = now.format("%m").to_string();
@@ -28,30 +102,387 @@ impl ReportSaver {
         let dir = self.base_dir.join(&year).join(&month).join(&day);
         fs::create_dir_all(&dir)?;
 
-        // 文件名: {operation}-HH-MM.md
-        let filename = format!("{}-{}.md", operation, now.format("%H-%M"));
+        // 文件名: 模板展开后加上 .md / .json
+        let filename = format!(
+            "{}.{}",
+            render_filename(&self.filename_template, &now, distro_name, operation),
+            self.format.extension()
+        );
         let filepath = dir.join(filename);
 
-        let op_label = match operation {
-            "Syu" => "系统更新",
-            "S" => "软件包安装",
-            "Rns" => "软件包卸载",
-            _ => "操作",
+        let full_content = match self.format {
+            ReportFormat::Markdown => {
+                // 添加元数据头部（纯文本格式）
+                let mut text = String::new();
+                text.push_str(&format!(
+                    "{} {}报告\n生成时间: {}\n\n",
+                    distro_name,
+                    operation_label(operation),
+                    now.format("%Y-%m-%d %H:%M:%S")
+                ));
+                text.push_str(content);
+                text
+            }
+            ReportFormat::Json => serde_json::to_string_pretty(&JsonReport {
+                timestamp: now.to_rfc3339(),
+                distro: distro_name,
+                operation,
+                analysis: content,
+                log,
+            })?,
         };
 
-        // 添加元数据头部（纯文本格式）
-        let mut full_content = String::new();
-        full_content.push_str(&format!(
-            "{} {}报告\n生成时间: {}\n\n",
-            distro_name,
-            op_label,
-            now.format("%Y-%m-%d %H:%M:%S")
-        ));
-        full_content.push_str(content);
-
         // 保存文件
         fs::write(&filepath, full_content)?;
 
+        // 清理旧报告，失败不影响本次保存
+        if let Err(e) = self.prune(self.max_files) {
+            log::warn!("清理旧报告失败: {}", e);
+        }
+
         Ok(filepath)
     }
+
+    /// 追加到 history.md / history.jsonl，整条记录一次写入并持有文件锁，避免交错
+    fn append(
+        &self,
+        content: &str,
+        log: &str,
+        distro_name: &str,
+        operation: &str,
+        now: &DateTime<Local>,
+    ) -> Result<PathBuf> {
+        let (filepath, entry) = match self.format {
+            ReportFormat::Markdown => (
+                self.history_path(),
+                format!(
+                    "## {} {}\n\n{} {}报告\n\n{}\n\n",
+                    now.format("%Y-%m-%d %H:%M:%S"),
+                    operation,
+                    distro_name,
+                    operation_label(operation),
+                    content.trim_end()
+                ),
+            ),
+            ReportFormat::Json => {
+                let mut line = serde_json::to_string(&JsonReport {
+                    timestamp: now.to_rfc3339(),
+                    distro: distro_name,
+                    operation,
+                    analysis: content,
+                    log,
+                })?;
+                line.push('\n');
+                (self.history_path(), line)
+            }
+        };
+
+        let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = OpenOptions::new().create(true).append(true).open(&filepath)?;
+        // 跨进程排他锁，file 关闭时自动释放
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        file.write_all(entry.as_bytes())?;
+
+        Ok(filepath)
+    }
+
+    /// 只保留最新的 max_files 份报告，返回删除的数量 (0 = 不限制)
+    pub fn prune(&self, max_files: usize) -> Result<usize> {
+        if max_files == 0 {
+            return Ok(0);
+        }
+
+        let mut reports = self.collect_reports()?;
+        if reports.len() <= max_files {
+            return Ok(0);
+        }
+
+        reports.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        let mut removed = 0;
+        for (path, _) in &reports[max_files..] {
+            fs::remove_file(path)?;
+            removed += 1;
+            // 顺带删除空的日/月/年目录
+            let mut dir = path.parent();
+            while let Some(d) = dir {
+                if d == self.base_dir || fs::remove_dir(d).is_err() {
+                    break;
+                }
+                dir = d.parent();
+            }
+        }
+        Ok(removed)
+    }
+
+    /// 所有按文件保存的报告及其生成时间，最新的在前
+    pub fn list(&self) -> Result<Vec<(PathBuf, NaiveDateTime)>> {
+        let mut reports = self.collect_reports()?;
+        reports.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        Ok(reports)
+    }
+
+    /// 读取一份按文件保存的报告，返回可供比较的正文
+    ///
+    /// Markdown 报告去掉含生成时间的头部；JSON 报告取 analysis 与 log 两节
+    pub fn load(path: &Path) -> Result<String> {
+        let content = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|e| e == "json") {
+            let report: serde_json::Value = serde_json::from_str(&content)?;
+            let field = |key: &str| report.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            return Ok(format!("## AI 分析\n\n{}\n\n## 包管理器日志\n\n{}\n", field("analysis"), field("log")));
+        }
+        match content.split_once("\n\n") {
+            Some((header, body)) if header.contains("生成时间: ") => Ok(body.to_string()),
+            _ => Ok(content),
+        }
+    }
+
+    /// 追加模式的报告文件
+    fn history_path(&self) -> PathBuf {
+        let name = match self.format {
+            ReportFormat::Markdown => "history.md",
+            ReportFormat::Json => "history.jsonl",
+        };
+        self.base_dir.join(name)
+    }
+
+    /// 最新一份报告；追加模式下为 history.md / history.jsonl
+    pub fn latest(&self) -> Result<Option<PathBuf>> {
+        if self.mode == ReportMode::Append {
+            let path = self.history_path();
+            return Ok(path.is_file().then_some(path));
+        }
+        Ok(self.list()?.into_iter().next().map(|(path, _)| path))
+    }
+
+    /// 收集 YYYY/MM/DD/ 下的所有报告及其生成时间
+    fn collect_reports(&self) -> Result<Vec<(PathBuf, NaiveDateTime)>> {
+        let stem_re = filename_time_regex(&self.filename_template);
+        let mut reports = Vec::new();
+        for year in numeric_subdirs(&self.base_dir)? {
+            for month in numeric_subdirs(&year)? {
+                for day in numeric_subdirs(&month)? {
+                    for entry in fs::read_dir(&day)?.filter_map(|e| e.ok()) {
+                        let path = entry.path();
+                        if !path.is_file() {
+                            continue;
+                        }
+                        if let Some(time) = report_time(&path, stem_re.as_ref()) {
+                            reports.push((path, time));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(reports)
+    }
+}
+
+/// 报告目录的汇总统计
+#[derive(Debug, Default, Serialize)]
+pub struct ReportStats {
+    /// 报告（含追加模式的记录）总数
+    pub total: usize,
+    /// 各类操作（系统更新 / 软件包安装 / 软件包卸载 / 事务计划）的次数
+    pub by_operation: BTreeMap<String, usize>,
+    /// 各月份（YYYY-MM）的次数
+    pub by_month: BTreeMap<String, usize>,
+    /// 日志中记录的包操作总数；只有 JSON 报告保存了日志
+    pub packages: usize,
+}
+
+impl ReportStats {
+    fn add(&mut self, time: NaiveDateTime, operation: &str, packages: usize) {
+        self.total += 1;
+        *self.by_operation.entry(operation.to_string()).or_default() += 1;
+        *self.by_month.entry(time.format("%Y-%m").to_string()).or_default() += 1;
+        self.packages += packages;
+    }
+}
+
+/// 日志中的包操作行，如 "(3/12) upgrading firefox" / "(3/12) 正在升级 firefox"
+static PACKAGE_ACTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\(\s*\d+/\d+\)\s+(upgrading|installing|removing|reinstalling|downgrading|正在(升级|安装|删除|重新安装|降级))\s")
+        .unwrap()
+});
+
+/// 汇总报告目录：按文件保存的报告及 history.md / history.jsonl 中的记录
+///
+/// 操作类型取自报告内容（Markdown 头部或 JSON 的 operation 字段），无法识别的文件跳过
+pub fn aggregate_reports(dir: &Path) -> ReportStats {
+    let mut stats = ReportStats::default();
+    let reports = ReportSaver::new(dir.to_path_buf()).collect_reports().unwrap_or_default();
+    for (path, time) in reports {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if path.extension().is_some_and(|e| e == "json") {
+            if let Ok(report) = serde_json::from_str::<serde_json::Value>(&content) {
+                add_json_record(&mut stats, &report, Some(time));
+            }
+        } else if let Some(label) = content.lines().next().and_then(markdown_operation) {
+            stats.add(time, label, 0);
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(dir.join("history.jsonl")) {
+        for report in content.lines().filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok()) {
+            add_json_record(&mut stats, &report, None);
+        }
+    }
+    if let Ok(content) = fs::read_to_string(dir.join("history.md")) {
+        // 每条记录以 "## YYYY-MM-DD HH:MM:SS 操作" 开头
+        for heading in content.lines().filter_map(|l| l.strip_prefix("## ")) {
+            let Some((time, operation)) = heading.rsplit_once(' ') else {
+                continue;
+            };
+            if let Ok(time) = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S") {
+                stats.add(time, operation_label(operation), 0);
+            }
+        }
+    }
+    stats
+}
+
+/// 统计一条 JSON 报告；fallback_time 为按文件保存时从路径得到的时间
+fn add_json_record(stats: &mut ReportStats, report: &serde_json::Value, fallback_time: Option<NaiveDateTime>) {
+    let field = |key: &str| report.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    let time = DateTime::parse_from_rfc3339(field("timestamp"))
+        .map(|t| t.naive_local())
+        .ok()
+        .or(fallback_time);
+    if let Some(time) = time {
+        let packages = PACKAGE_ACTION_RE.find_iter(field("log")).count();
+        stats.add(time, operation_label(field("operation")), packages);
+    }
+}
+
+/// 操作标识与中文名称，报告头部的写入和统计时的识别都以此为准
+const OPERATION_LABELS: [(&[&str], &str); 4] = [
+    (&["Syu"], "系统更新"),
+    (&["S"], "软件包安装"),
+    (&["Rns", "Rs", "R"], "软件包卸载"),
+    (&["plan"], "事务计划"),
+];
+
+/// 从 Markdown 报告首行 "{distro} {操作}报告" 取操作类型
+fn markdown_operation(header: &str) -> Option<&'static str> {
+    OPERATION_LABELS
+        .into_iter()
+        .map(|(_, label)| label)
+        .find(|label| header.ends_with(&format!("{}报告", label)))
 }
+
+/// 操作类型的中文名称
+fn operation_label(operation: &str) -> &'static str {
+    OPERATION_LABELS
+        .into_iter()
+        .find(|(ops, _)| ops.contains(&operation))
+        .map_or("操作", |(_, label)| label)
+}
+
+/// 展开文件名模板中的 {date} {time} {distro} {operation}，并替换文件名非法字符
+fn render_filename(
+    template: &str,
+    now: &DateTime<Local>,
+    distro_name: &str,
+    operation: &str,
+) -> String {
+    let name = template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H-%M").to_string())
+        .replace("{distro}", distro_name)
+        .replace("{operation}", operation);
+    let name = sanitize_filename(&name);
+    if name.is_empty() {
+        sanitize_filename(&format!("{}-{}", operation, now.format("%H-%M")))
+    } else {
+        name
+    }
+}
+
+/// 将路径分隔符、Windows 保留字符和控制字符替换为 _，去掉首尾空白和点
+fn sanitize_filename(name: &str) -> String {
+    replace_illegal_chars(name).trim().trim_matches('.').to_string()
+}
+
+/// 列出名称全为数字的子目录（跳过 .cache 等）
+fn numeric_subdirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .collect())
+}
+
+/// 报告生成时间：文件名按模板匹配时从中解析，否则使用修改时间
+fn report_time(path: &Path, stem_re: Option<&Regex>) -> Option<NaiveDateTime> {
+    stem_re.and_then(|re| parse_report_time(path, re)).or_else(|| {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        Some(DateTime::<Local>::from(modified).naive_local())
+    })
+}
+
+/// 由文件名模板构造匹配文件名（不含扩展名）的正则，{time} 捕获为 hour / minute
+///
+/// 模板不含 {time} 时无法从文件名得到时间，返回 None
+fn filename_time_regex(template: &str) -> Option<Regex> {
+    if !template.contains("{time}") {
+        return None;
+    }
+    let placeholders = Regex::new(r"\{(date|time|distro|operation)\}").ok()?;
+    let mut pattern = String::from("^");
+    let mut last = 0;
+    let mut time_seen = false;
+    for m in placeholders.find_iter(template) {
+        // 字面部分按 render_filename 的规则替换非法字符后再转义
+        pattern.push_str(&regex::escape(&replace_illegal_chars(&template[last..m.start()])));
+        pattern.push_str(match m.as_str() {
+            "{time}" if !time_seen => r"(?P<hour>\d{2})-(?P<minute>\d{2})",
+            "{time}" => r"\d{2}-\d{2}",
+            "{date}" => r"\d{4}-\d{2}-\d{2}",
+            _ => ".+?",
+        });
+        time_seen |= m.as_str() == "{time}";
+        last = m.end();
+    }
+    pattern.push_str(&regex::escape(&replace_illegal_chars(&template[last..])));
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+/// 将路径分隔符、Windows 保留字符和控制字符替换为 _
+fn replace_illegal_chars(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// 从 YYYY/MM/DD/<文件名> 路径解析时间，时分取自模板中 {time} 的位置
+fn parse_report_time(path: &Path, stem_re: &Regex) -> Option<NaiveDateTime> {
+    let day_dir = path.parent()?;
+    let month_dir = day_dir.parent()?;
+    let year_dir = month_dir.parent()?;
+    let num = |p: &Path| p.file_name()?.to_str()?.parse::<u32>().ok();
+    let date = NaiveDate::from_ymd_opt(num(year_dir)? as i32, num(month_dir)?, num(day_dir)?)?;
+
+    let caps = stem_re.captures(path.file_stem()?.to_str()?)?;
+    let hour = caps.name("hour")?.as_str().parse::<u32>().ok()?;
+    let minute = caps.name("minute")?.as_str().parse::<u32>().ok()?;
+    date.and_hms_opt(hour, minute, 0)
+}
+
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::process::Command;
 
 /// 系统环境信息，用于注入 AI 提示词
@@ -10,6 +11,12 @@ pub struct SystemInfo {
     pub display_protocol: String,
     pub cpu: String,
     pub memory: String,
+    /// 开机时长，如 "3 天 4 小时"
+    pub uptime: String,
+    /// 根分区剩余空间，如 "120.50 GiB / 465.76 GiB"
+    pub disk_free: String,
+    /// pacman 及 AUR 助手版本，如 "pacman 7.0.0 / paru 2.0.4"
+    pub pm_version: String,
 }
 
 impl SystemInfo {
@@ -23,6 +30,9 @@ impl SystemInfo {
             display_protocol: Self::get_display_protocol(),
             cpu: Self::get_cpu(),
             memory: Self::get_memory(),
+            uptime: Self::get_uptime(),
+            disk_free: Self::get_disk_free(),
+            pm_version: Self::get_pm_version(),
         }
     }
 
@@ -127,6 +137,53 @@ impl SystemInfo {
             .unwrap_or(raw)
     }
 
+    fn get_uptime() -> String {
+        // /proc/uptime 第一个字段为开机秒数
+        let secs = std::fs::read_to_string("/proc/uptime")
+            .ok()
+            .and_then(|s| s.split_whitespace().next()?.parse::<f64>().ok());
+        let Some(secs) = secs else {
+            return "未知".to_string();
+        };
+        let mins = secs as u64 / 60;
+        let (days, hours, mins) = (mins / 1440, mins / 60 % 24, mins % 60);
+        if days > 0 {
+            format!("{} 天 {} 小时", days, hours)
+        } else if hours > 0 {
+            format!("{} 小时 {} 分钟", hours, mins)
+        } else {
+            format!("{} 分钟", mins)
+        }
+    }
+
+    fn get_disk_free() -> String {
+        match fs_space(Path::new("/")) {
+            Some((avail, total)) => format!(
+                "{} / {}",
+                crate::package_manager::format_size(avail),
+                crate::package_manager::format_size(total),
+            ),
+            None => "未知".to_string(),
+        }
+    }
+
+    fn get_pm_version() -> String {
+        // 输出中第一个 "v数字" 即版本号，如 "Pacman v7.0.0" / "paru v2.0.4 - libalpm v15.0.0"
+        let version = |cmd: &str| -> Option<String> {
+            let out = Command::new(cmd).arg("--version").output().ok()?;
+            let text = String::from_utf8_lossy(&out.stdout);
+            text.split_whitespace()
+                .find_map(|w| w.strip_prefix('v').filter(|v| v.starts_with(|c: char| c.is_ascii_digit())))
+                .map(|v| format!("{} {}", cmd, v.trim_end_matches(['-', ','])))
+        };
+        let versions: Vec<String> = ["pacman", "paru", "yay"].iter().filter_map(|c| version(c)).collect();
+        if versions.is_empty() {
+            "未知".to_string()
+        } else {
+            versions.join(" / ")
+        }
+    }
+
     fn get_memory() -> String {
         // 兼容中英文 locale: "Mem:" 或 "内存："
         let raw = Self::run_shell(
@@ -144,3 +201,33 @@ impl SystemInfo {
         }
     }
 }
+
+/// 是否需要重启：Arch 升级内核后会删除旧内核的模块目录，
+/// 正在运行的内核在 /usr/lib/modules 下找不到对应目录即说明内核已被替换
+pub fn reboot_required() -> bool {
+    let release = SystemInfo::get_kernel();
+    if release == "未知" {
+        return false;
+    }
+    !Path::new("/usr/lib/modules").join(release).exists()
+}
+
+/// 路径所在文件系统对普通用户可用的字节数（statvfs），获取失败时返回 u64::MAX（视为空间充足）
+pub fn available_space(path: &Path) -> u64 {
+    fs_space(path).map(|(avail, _)| avail).unwrap_or(u64::MAX)
+}
+
+/// 文件系统 (可用字节数, 总字节数)
+fn fs_space(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let frsize = stat.f_frsize as u64;
+    Some((
+        (stat.f_bavail as u64).saturating_mul(frsize),
+        (stat.f_blocks as u64).saturating_mul(frsize),
+    ))
+}
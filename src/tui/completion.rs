@@ -0,0 +1,164 @@
+//! Shell 模式的 Tab 补全：命令名（$PATH）、pacman 参数、包名
+//!
+//! 命令名与包名列表在后台收集并缓存（[`refresh_in_background`]），Tab 只查缓存，不阻塞界面
+
+use std::collections::BTreeSet;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+use std::sync::RwLock;
+
+/// 候选数量上限，避免空前缀时列出整个 $PATH
+const MAX_CANDIDATES: usize = 50;
+
+/// 包管理器常用操作与参数
+const PACMAN_FLAGS: [&str; 27] = [
+    "-S", "-Syu", "-Syyu", "-Ss", "-Si", "-Sc", "-Scc", "-Sw", "-R", "-Rs", "-Rns", "-Rdd", "-Q",
+    "-Qe", "-Qi", "-Ql", "-Qo", "-Qs", "-Qm", "-Qdt", "-Qkk", "-U", "-F", "-Fy", "--needed",
+    "--noconfirm", "--asdeps",
+];
+
+/// 补全用的候选列表（均已排序去重）
+struct Lists {
+    commands: Vec<String>,
+    sync_packages: Vec<String>,
+    installed_packages: Vec<String>,
+}
+
+/// 候选列表缓存，尚未收集完成时为 None（此时不补全命令名和包名）
+static LISTS: RwLock<Option<Lists>> = RwLock::new(None);
+
+/// 在后台重新收集候选列表；进入 Shell 模式和每条命令结束后调用
+pub fn refresh_in_background() {
+    tokio::task::spawn_blocking(|| {
+        let lists = Lists {
+            commands: list_commands(),
+            sync_packages: list_packages("-Slq"),
+            installed_packages: list_packages("-Qq"),
+        };
+        if let Ok(mut current) = LISTS.write() {
+            *current = Some(lists);
+        }
+    });
+}
+
+/// 补全输入行的最后一个词，返回补全后的完整输入行候选
+pub fn complete(input: &str) -> Vec<String> {
+    let (head, partial) = match input.rfind(char::is_whitespace) {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let words: Vec<&str> = head.split_whitespace().collect();
+    // 跳过 sudo / doas 等提权命令，定位真正的命令
+    let args: &[&str] = match words.first() {
+        Some(&"sudo" | &"doas") => &words[1..],
+        _ => &words,
+    };
+
+    let candidates = match args.first() {
+        None => complete_command(partial),
+        Some(cmd) if matches!(*cmd, "pacman" | "paru" | "yay") => {
+            if partial.starts_with('-') {
+                PACMAN_FLAGS
+                    .iter()
+                    .filter(|f| f.starts_with(partial))
+                    .map(|f| f.to_string())
+                    .collect()
+            } else {
+                complete_package(args, partial)
+            }
+        }
+        Some(_) => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .map(|c| format!("{}{}", head, c))
+        .collect()
+}
+
+/// 缓存中以 partial 开头的候选
+fn cached_matches(partial: &str, pick: fn(&Lists) -> &Vec<String>) -> Vec<String> {
+    let Ok(lists) = LISTS.read() else {
+        return Vec::new();
+    };
+    let Some(lists) = lists.as_ref() else {
+        return Vec::new();
+    };
+    pick(lists)
+        .iter()
+        .filter(|name| name.starts_with(partial))
+        .take(MAX_CANDIDATES)
+        .cloned()
+        .collect()
+}
+
+/// $PATH 中以 partial 开头的可执行文件
+fn complete_command(partial: &str) -> Vec<String> {
+    cached_matches(partial, |lists| &lists.commands)
+}
+
+/// 根据操作类型补全包名：-S 取同步库，-R/-Q 取已安装包
+fn complete_package(args: &[&str], partial: &str) -> Vec<String> {
+    if partial.is_empty() {
+        return Vec::new();
+    }
+    let op = args.iter().find(|a| a.starts_with('-') && !a.starts_with("--"));
+    match op {
+        Some(op) if op.starts_with("-S") => cached_matches(partial, |lists| &lists.sync_packages),
+        Some(op) if op.starts_with("-R") || op.starts_with("-Q") => {
+            cached_matches(partial, |lists| &lists.installed_packages)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// $PATH 中的所有可执行文件名
+fn list_commands() -> Vec<String> {
+    let path = std::env::var("PATH").unwrap_or_default();
+    let mut names = BTreeSet::new();
+    for dir in path.split(':').filter(|d| !d.is_empty()) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let executable = entry
+                .metadata()
+                .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false);
+            if executable {
+                names.insert(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// pacman -Slq / -Qq 列出的包名
+fn list_packages(list_flag: &str) -> Vec<String> {
+    let Ok(output) = Command::new("pacman").arg(list_flag).output() else {
+        return Vec::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let names: BTreeSet<&str> = stdout.lines().filter_map(|l| l.split_whitespace().last()).collect();
+    names.into_iter().map(String::from).collect()
+}
+
+/// 多个候选的最长公共前缀
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for c in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(c.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((i, a), _)| i + a.len_utf8())
+            .unwrap_or(0)
+            .min(len);
+    }
+    first[..len].to_string()
+}
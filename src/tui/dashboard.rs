@@ -1,4 +1,7 @@
-use super::state::App;
+use super::help::DASHBOARD_KEYS;
+use super::i18n::{t, tf};
+use super::state::{App, AppEvent};
+use crate::package_manager::LogAction;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
@@ -6,6 +9,8 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use tokio::sync::mpsc;
+use unicode_width::UnicodeWidthStr;
 
 const ASCII_LOGO: &str = r#"
 ██       ██                   
@@ -17,6 +22,50 @@ const ASCII_LOGO: &str = r#"
 ░████████░██░░████████░██  ░██
 ░░░░░░░░ ░░  ░░░░░░░░ ░░   ░░"#;
 
+/// 仪表盘 l 打开的大包列表长度
+pub const LARGEST_LIMIT: usize = 30;
+
+/// 仪表盘最近变更面板的条目数
+pub const RECENT_LIMIT: usize = 15;
+
+/// 宽度不小于此值时才在右侧显示最近变更面板
+const RECENT_PANEL_MIN_WIDTH: u16 = 110;
+const RECENT_PANEL_WIDTH: u16 = 48;
+
+/// 后台统计包构成、孤立包数量、包缓存大小、最大的包与最近变更，结果缓存在 App 上供仪表盘显示
+pub fn spawn_maintenance_stats(app: &App, tx: &mpsc::Sender<AppEvent>) {
+    if let Some(pm) = app.package_manager.clone() {
+        let tx_clone = tx.clone();
+        tokio::spawn(async move {
+            let counts_pm = pm.clone();
+            let counts = tokio::task::spawn_blocking(move || counts_pm.count_breakdown())
+                .await
+                .unwrap_or_default();
+            let _ = tx_clone.send(AppEvent::PackageCounts(counts)).await;
+            let orphans_pm = pm.clone();
+            let count = tokio::task::spawn_blocking(move || orphans_pm.count_orphans())
+                .await
+                .unwrap_or(0);
+            let _ = tx_clone.send(AppEvent::OrphanCount(count)).await;
+            let largest = tokio::task::spawn_blocking(move || pm.largest_packages(LARGEST_LIMIT))
+                .await
+                .unwrap_or_default();
+            let _ = tx_clone.send(AppEvent::LargestPackages(largest)).await;
+        });
+    }
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        let size = tokio::task::spawn_blocking(crate::package_manager::cache_size)
+            .await
+            .unwrap_or(0);
+        let _ = tx_clone.send(AppEvent::CacheSize(size)).await;
+        let entries = tokio::task::spawn_blocking(|| crate::package_manager::recent_log_entries(RECENT_LIMIT))
+            .await
+            .unwrap_or_default();
+        let _ = tx_clone.send(AppEvent::RecentChanges(entries)).await;
+    });
+}
+
 pub fn render_dashboard(f: &mut Frame, app: &App) {
     let area = f.area();
 
@@ -44,51 +93,109 @@ pub fn render_dashboard(f: &mut Frame, app: &App) {
 
     // 系统信息标题
     lines.push(Line::from(vec![Span::styled(
-        "── 系统信息 ──",
+        t("dashboard.system_info"),
         Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
     )]));
     lines.push(Line::from(""));
 
     // 系统信息内容
+    let detecting = || t("dashboard.detecting").to_string();
+    let mut rows: Vec<(&str, String, Color)> = Vec::new();
     if let Some(info) = &app.system_info {
-        lines.push(info_line("发行版  ", &info.distro));
-        lines.push(info_line("内核  ", &info.kernel));
+        rows.push((t("dashboard.distro"), info.distro.clone(), Color::White));
+        rows.push((t("dashboard.kernel"), info.kernel.clone(), Color::White));
+        rows.push((t("dashboard.uptime"), info.uptime.clone(), Color::White));
+        rows.push((t("dashboard.disk_free"), info.disk_free.clone(), Color::White));
     } else {
-        lines.push(info_line("发行版  ", "检测中..."));
-        lines.push(info_line("内核  ", "检测中..."));
+        rows.push((t("dashboard.distro"), detecting(), Color::DarkGray));
+        rows.push((t("dashboard.kernel"), detecting(), Color::DarkGray));
+        rows.push((t("dashboard.uptime"), detecting(), Color::DarkGray));
+        rows.push((t("dashboard.disk_free"), detecting(), Color::DarkGray));
     }
 
-    if let Some(pm) = &app.package_manager {
-        lines.push(info_line("包管理器  ", pm.name()));
-    } else {
-        lines.push(info_line("包管理器  ", "检测中..."));
+    match &app.package_manager {
+        Some(pm) => rows.push((t("dashboard.pm"), pm.name().to_string(), Color::White)),
+        None => rows.push((t("dashboard.pm"), detecting(), Color::DarkGray)),
+    }
+    match &app.system_info {
+        Some(info) => rows.push((t("dashboard.pm_version"), info.pm_version.clone(), Color::White)),
+        None => rows.push((t("dashboard.pm_version"), detecting(), Color::DarkGray)),
     }
 
-    if let Some(count) = app.installed_count {
-        let count_str = format!("{count} 个");
-        lines.push(info_line("已安装包  ", &count_str));
-    } else {
-        lines.push(info_line("已安装包  ", "统计中..."));
+    if let Some(profile) = &app.config.active_profile {
+        rows.push((t("dashboard.profile"), profile.clone(), Color::White));
+    }
+    if let Some(mode) = app.insecure_config_mode {
+        rows.push((t("dashboard.config_perms"), tf("dashboard.config_perms_value", &[&format!("{:o}", mode)]), Color::Red));
+    }
+
+    match app.installed_count {
+        Some(count) => rows.push((t("dashboard.installed"), tf("dashboard.installed_count", &[&count]), Color::White)),
+        None => rows.push((t("dashboard.installed"), t("dashboard.counting").to_string(), Color::DarkGray)),
+    }
+    match app.package_counts {
+        Some(c) => rows.push((
+            t("dashboard.breakdown"),
+            tf("dashboard.breakdown_value", &[&c.explicit, &c.dependencies, &c.foreign]),
+            Color::White,
+        )),
+        None => rows.push((t("dashboard.breakdown"), t("dashboard.counting").to_string(), Color::DarkGray)),
     }
 
+    let updates = match (&app.available_updates, &app.update_check_error) {
+        _ if app.checking_updates => (tf("dashboard.checking", &[&spinner()]), Color::DarkGray),
+        (Some(list), _) if list.is_empty() => (t("dashboard.up_to_date").to_string(), Color::Green),
+        (Some(list), _) => (tf("dashboard.updates_count", &[&list.len()]), Color::Yellow),
+        (None, Some(_)) => (t("dashboard.check_failed").to_string(), Color::Red),
+        (None, None) => (detecting(), Color::DarkGray),
+    };
+    rows.push((t("dashboard.updates"), updates.0, updates.1));
+
+    match app.orphan_count {
+        Some(0) => rows.push((t("dashboard.orphans"), t("dashboard.no_orphans").to_string(), Color::Green)),
+        Some(count) => rows.push((t("dashboard.orphans"), tf("dashboard.orphans_count", &[&count]), Color::Yellow)),
+        None => rows.push((t("dashboard.orphans"), t("dashboard.counting").to_string(), Color::DarkGray)),
+    }
+    match app.cache_size {
+        Some(size) => rows.push((
+            t("dashboard.cache"),
+            tf("dashboard.cache_size", &[&crate::package_manager::format_size(size)]),
+            Color::White,
+        )),
+        None => rows.push((t("dashboard.cache"), t("dashboard.counting").to_string(), Color::DarkGray)),
+    }
+    match app.largest_packages.as_deref() {
+        Some([top, ..]) => rows.push((
+            t("dashboard.largest"),
+            tf(
+                "dashboard.largest_value",
+                &[&top.name, &crate::package_manager::format_size(crate::package_manager::parse_size(&top.size))],
+            ),
+            Color::White,
+        )),
+        Some([]) => {}
+        None => rows.push((t("dashboard.largest"), t("dashboard.counting").to_string(), Color::DarkGray)),
+    }
+
+    lines.extend(info_lines(&rows));
+
     lines.push(Line::from(""));
     lines.push(Line::from(""));
 
     // 快捷键标题
     lines.push(Line::from(vec![Span::styled(
-        "── 快捷键 ──",
+        t("dashboard.shortcuts"),
         Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
     )]));
     lines.push(Line::from(""));
 
-    // 快捷键列表
-    lines.push(shortcut_line("U", " 系统更新 (Syu)  "));
-    lines.push(shortcut_line("S", " 安装软件包       "));
-    lines.push(shortcut_line("R", " 卸载软件包       "));
-    lines.push(shortcut_line("Q", " 查询软件包       "));
-    lines.push(shortcut_line("X", " 自定义命令       "));
-    lines.push(shortcut_line("C", " 设置             "));
-    lines.push(shortcut_line("q", " 退出             "));
+    // 快捷键列表（与帮助浮层共用按键表），描述补齐到同一宽度以保持居中对齐
+    let desc_width = DASHBOARD_KEYS.iter().map(|(_, d)| t(d).width()).max().unwrap_or(0);
+    for (key, desc) in DASHBOARD_KEYS {
+        let desc = t(desc);
+        let padded = format!(" {}{}  ", desc, " ".repeat(desc_width - desc.width()));
+        lines.push(shortcut_line(key, padded));
+    }
 
     lines.push(Line::from(""));
     lines.push(Line::from(""));
@@ -104,11 +211,21 @@ pub fn render_dashboard(f: &mut Frame, app: &App) {
 
     // 垂直居中：计算内容高度，用 Layout 居中
     let content_height = lines_count as u16;
-    let inner = area.inner(ratatui::layout::Margin {
+    let mut inner = area.inner(ratatui::layout::Margin {
         horizontal: 1,
         vertical: 1,
     });
 
+    // 足够宽时右侧显示最近变更面板
+    if inner.width >= RECENT_PANEL_MIN_WIDTH {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(RECENT_PANEL_WIDTH)])
+            .split(inner);
+        inner = columns[0];
+        render_recent_changes(f, app, columns[1]);
+    }
+
     let vertical = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -121,19 +238,80 @@ pub fn render_dashboard(f: &mut Frame, app: &App) {
     f.render_widget(paragraph, vertical[1]);
 }
 
-/// 系统信息行: "  标签: 值"
-fn info_line(label: &str, value: &str) -> Line<'static> {
-    Line::from(vec![
-        Span::styled(
-            format!("{label}: "),
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(value.to_string(), Style::default().fg(Color::White)),
-    ])
+/// 最近变更面板：pacman.log 中最近的安装 / 升级 / 卸载，按操作着色
+fn render_recent_changes(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let block = Block::default()
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(
+            format!(" {} ", t("dashboard.recent")),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    let lines: Vec<Line> = match &app.recent_changes {
+        None => vec![Line::styled(t("dashboard.counting"), Style::default().fg(Color::DarkGray))],
+        Some(entries) if entries.is_empty() => {
+            vec![Line::styled(t("dashboard.recent_empty"), Style::default().fg(Color::DarkGray))]
+        }
+        Some(entries) => entries
+            .iter()
+            .map(|entry| {
+                let (symbol, color) = action_style(entry.action);
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", entry.time.format("%m-%d %H:%M")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{symbol} {}", entry.name), Style::default().fg(color)),
+                    Span::styled(format!(" {}", entry.new_version()), Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect(),
+    };
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// 各操作的标记与颜色
+fn action_style(action: LogAction) -> (&'static str, Color) {
+    match action {
+        LogAction::Installed => ("+", Color::Green),
+        LogAction::Upgraded => ("↑", Color::Cyan),
+        LogAction::Downgraded => ("↓", Color::Yellow),
+        LogAction::Reinstalled => ("↻", Color::Blue),
+        LogAction::Removed => ("-", Color::Red),
+    }
+}
+
+/// 系统信息行: "  标签: 值"，标签右对齐、值左对齐并补齐到同一宽度，居中后各列仍对齐
+fn info_lines(rows: &[(&str, String, Color)]) -> Vec<Line<'static>> {
+    let label_width = rows.iter().map(|(l, _, _)| l.width()).max().unwrap_or(0);
+    let value_width = rows.iter().map(|(_, v, _)| v.width()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, value, color)| {
+            let label_pad = " ".repeat(label_width - label.width());
+            let value_pad = " ".repeat(value_width - value.width());
+            Line::from(vec![
+                Span::styled(
+                    format!("{label_pad}{label}: "),
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{value}{value_pad}"), Style::default().fg(*color)),
+            ])
+        })
+        .collect()
+}
+
+/// 按当前时间选取的旋转指示符（主循环约每 100ms 重绘一次）
+fn spinner() -> char {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    FRAMES[(millis / 100) as usize % FRAMES.len()]
 }
 
 /// 快捷键行: "  X  描述"
-fn shortcut_line<'a>(key: &'a str, desc: &'a str) -> Line<'a> {
+fn shortcut_line(key: &str, desc: String) -> Line<'static> {
     Line::from(vec![
         Span::styled(
             format!("  {key}"),
@@ -141,6 +319,6 @@ fn shortcut_line<'a>(key: &'a str, desc: &'a str) -> Line<'a> {
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(desc.to_string(), Style::default().fg(Color::White)),
+        Span::styled(desc, Style::default().fg(Color::White)),
     ])
 }
@@ -0,0 +1,209 @@
+//! 快捷键帮助：按键表是唯一来源，仪表盘快捷键列表与 "?" 帮助浮层都由此生成
+//!
+//! 分组名和说明文字都是目录键，渲染时经 [`t`] 翻译
+
+use super::i18n::t;
+use super::layout;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// (按键, 说明目录键)
+pub type KeyHelp = (&'static str, &'static str);
+
+/// 主页快捷键（Shift + 字母在任意非输入界面也可切换模式）
+pub const DASHBOARD_KEYS: &[KeyHelp] = &[
+    ("U", "keys.update"),
+    ("S", "keys.install"),
+    ("R", "keys.remove"),
+    ("Q", "keys.query"),
+    ("X", "keys.shell"),
+    ("P", "keys.plan"),
+    ("C", "keys.settings"),
+    ("o", "keys.orphans"),
+    ("l", "keys.largest"),
+    ("c", "keys.cache"),
+    ("p", "keys.pacnew"),
+    ("?", "keys.help"),
+    ("q", "keys.quit"),
+];
+
+/// 按模式分组的完整按键表
+pub const KEYMAP: &[(&str, &[KeyHelp])] = &[
+    ("help.dashboard", DASHBOARD_KEYS),
+    (
+        "help.general",
+        &[
+            ("Esc", "help.general.back"),
+            ("F1", "help.general.help"),
+            ("Ctrl+C", "help.general.quit"),
+            ("↑↓ PgUp PgDn", "help.general.scroll"),
+            ("g G / Home End", "help.general.jump"),
+            ("f", "help.general.follow"),
+            ("help.key.wheel", "help.general.wheel"),
+            ("Ctrl+A E", "help.general.line"),
+            ("Ctrl+← →", "help.general.word"),
+            ("Ctrl+W U K", "help.general.kill"),
+            ("Ctrl+V", "help.general.paste"),
+            ("r", "help.general.raw"),
+        ],
+    ),
+    (
+        "help.update",
+        &[
+            ("Enter", "help.update.start"),
+            ("Space", "help.update.partial"),
+            ("k", "help.update.keyring"),
+            ("r", "help.update.retry"),
+            ("Tab", "help.update.tab"),
+            ("i", "help.update.chat"),
+            ("y", "help.update.copy"),
+            ("/", "help.update.search"),
+            ("n N", "help.update.next"),
+            ("p", "help.update.pacnew"),
+        ],
+    ),
+    (
+        "help.install",
+        &[
+            ("Space", "help.install.mark"),
+            ("Enter", "help.install.confirm"),
+            ("Ctrl+T", "help.install.sort"),
+            ("Ctrl+F", "help.install.repo"),
+            ("Ctrl+D", "help.install.sizes"),
+            ("Ctrl+O", "help.install.import"),
+            ("Ctrl+P", "help.install.plan"),
+            ("e", "help.install.explicit"),
+            ("y", "help.install.copy"),
+            ("/", "help.install.search"),
+            ("n N", "help.install.next"),
+        ],
+    ),
+    (
+        "help.remove",
+        &[
+            ("Space", "help.remove.mark"),
+            ("Enter", "help.remove.confirm"),
+            ("Ctrl+T", "help.remove.sort"),
+            ("Ctrl+P", "help.remove.plan"),
+            ("m", "help.remove.variant"),
+            ("y", "help.remove.copy"),
+            ("/", "help.remove.search"),
+            ("n N", "help.remove.next"),
+        ],
+    ),
+    (
+        "help.plan",
+        &[
+            ("Enter", "help.plan.confirm"),
+            ("d", "help.plan.delete"),
+            ("c", "help.plan.clear"),
+            ("m", "help.plan.variant"),
+            ("y", "help.plan.copy"),
+        ],
+    ),
+    (
+        "help.query",
+        &[
+            ("Tab", "help.query.tab"),
+            ("Enter", "help.query.detail"),
+            ("Ctrl+F", "help.query.repo"),
+            ("Space", "help.query.mark"),
+            ("Ctrl+S Ctrl+R", "help.query.batch"),
+            ("o", "help.query.open"),
+            ("i r", "help.query.action"),
+            ("[ ] Enter", "help.query.optdeps"),
+        ],
+    ),
+    (
+        "help.shell",
+        &[
+            ("Enter", "help.shell.run"),
+            ("Tab", "help.shell.complete"),
+            ("↑↓", "help.shell.history"),
+            ("Ctrl+L", "help.shell.local"),
+            ("/", "help.shell.search"),
+            ("n N", "help.shell.next"),
+        ],
+    ),
+    (
+        "help.pacnew",
+        &[
+            ("Enter", "help.pacnew.diff"),
+            ("a", "help.pacnew.accept"),
+            ("d", "help.pacnew.delete"),
+        ],
+    ),
+    (
+        "help.settings",
+        &[
+            ("Enter Space", "help.settings.edit"),
+            ("←→", "help.settings.option"),
+            ("/", "help.settings.filter"),
+            ("Ctrl+S", "help.settings.save"),
+            ("Ctrl+R", "help.settings.reload"),
+            ("Ctrl+D", "help.settings.defaults"),
+            ("Ctrl+T", "help.settings.test"),
+            ("Ctrl+K", "help.settings.keyring"),
+            ("Ctrl+E Ctrl+F", "help.settings.export"),
+        ],
+    ),
+];
+
+/// 居中绘制帮助浮层，左右两栏分摊各分组
+pub fn render_help_overlay(f: &mut Frame) {
+    let area = layout::centered_rect(f.area(), 84, 90);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(t("help.title"))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area).inner(Margin {
+        horizontal: 1,
+        vertical: 0,
+    });
+    f.render_widget(block, area);
+
+    let key_width = KEYMAP
+        .iter()
+        .flat_map(|(_, keys)| keys.iter())
+        .map(|(k, _)| t(k).width())
+        .max()
+        .unwrap_or(0);
+    // 前一半分组放左栏，其余放右栏
+    let mut columns: [Vec<Line>; 2] = [Vec::new(), Vec::new()];
+    let half = KEYMAP.len().div_ceil(2);
+    for (i, (group, keys)) in KEYMAP.iter().enumerate() {
+        let column = &mut columns[usize::from(i >= half)];
+        column.push(Line::from(Span::styled(
+            format!("── {} ──", t(group)),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )));
+        for (key, desc) in keys.iter() {
+            // 按键名多为字面文本，目录中没有时 t 原样返回
+            let key = t(key);
+            let pad = " ".repeat(key_width - key.width());
+            column.push(Line::from(vec![
+                Span::styled(
+                    format!("{}{}  ", key, pad),
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(t(desc)),
+            ]));
+        }
+        column.push(Line::from(""));
+    }
+
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    for (column, rect) in columns.into_iter().zip(halves.iter()) {
+        f.render_widget(Paragraph::new(column), *rect);
+    }
+}
@@ -0,0 +1,651 @@
+//! 界面文本目录：按 `language` 配置在中文 / 英文之间切换
+//!
+//! 启动和修改设置时由 [`set_language`] 写入，渲染时用 [`t`] / [`tf`] 按键查找。
+//! 当前语言缺少的键回退到中文，中文也没有时原样返回键本身。
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+/// (语言代码, 文本目录)，第一项为默认语言
+const CATALOGS: [(&str, Catalog); 2] = [("zh", ZH), ("en", EN)];
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// 切换界面语言，未知代码视为中文
+pub fn set_language(code: &str) {
+    let index = CATALOGS.iter().position(|(c, _)| *c == code).unwrap_or(0);
+    CURRENT.store(index, Ordering::Relaxed);
+}
+
+/// 查找当前语言的文本
+pub fn t(key: &'static str) -> &'static str {
+    let catalog = CATALOGS[CURRENT.load(Ordering::Relaxed)].1;
+    lookup(catalog, key)
+        .or_else(|| lookup(ZH, key))
+        .unwrap_or(key)
+}
+
+/// 查找文本并依次用 args 替换其中的 `{}`
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = t(key).split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+fn lookup(catalog: Catalog, key: &str) -> Option<&'static str> {
+    catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+const ZH: Catalog = &[
+    // 仪表盘
+    ("dashboard.system_info", "── 系统信息 ──"),
+    ("dashboard.shortcuts", "── 快捷键 ──"),
+    ("dashboard.detecting", "检测中..."),
+    ("dashboard.counting", "统计中..."),
+    ("dashboard.distro", "发行版"),
+    ("dashboard.kernel", "内核"),
+    ("dashboard.uptime", "运行时间"),
+    ("dashboard.disk_free", "根分区可用"),
+    ("dashboard.pm", "包管理器"),
+    ("dashboard.pm_version", "版本"),
+    ("dashboard.profile", "配置档案"),
+    ("dashboard.config_perms", "配置权限"),
+    ("dashboard.config_perms_value", "{} 其他用户可读 API Key (Shift+C → Ctrl+P 修复)"),
+    ("dashboard.installed", "已安装包"),
+    ("dashboard.installed_count", "{} 个"),
+    ("dashboard.breakdown", "构成"),
+    ("dashboard.breakdown_value", "显式 {} · 依赖 {} · 外部 {}"),
+    ("dashboard.updates", "更新"),
+    ("dashboard.checking", "{} 检查中..."),
+    ("dashboard.up_to_date", "已是最新"),
+    ("dashboard.updates_count", "{} 个可用更新"),
+    ("dashboard.check_failed", "检查失败"),
+    ("dashboard.orphans", "孤立包"),
+    ("dashboard.no_orphans", "无"),
+    ("dashboard.orphans_count", "{} 个 (o 清理)"),
+    ("dashboard.cache", "包缓存"),
+    ("dashboard.cache_size", "{} (c 清理)"),
+    ("dashboard.largest", "最大的包"),
+    ("dashboard.largest_value", "{} {} (l 查看)"),
+    ("dashboard.recent", "最近变更"),
+    ("dashboard.recent_empty", "pacman.log 中没有记录"),
+    // 主页快捷键
+    ("keys.update", "系统更新 (Syu)"),
+    ("keys.install", "安装软件包"),
+    ("keys.remove", "卸载软件包"),
+    ("keys.query", "查询软件包"),
+    ("keys.shell", "自定义命令"),
+    ("keys.plan", "事务计划"),
+    ("keys.settings", "设置"),
+    ("keys.orphans", "清理孤立包"),
+    ("keys.largest", "查看最大的包"),
+    ("keys.cache", "清理包缓存"),
+    ("keys.pacnew", "合并 .pacnew 配置"),
+    ("keys.help", "快捷键帮助"),
+    ("keys.quit", "退出"),
+    ("help.title", " 快捷键帮助 (Esc / ? 关闭) "),
+    ("help.dashboard", "主页"),
+    ("help.general", "通用"),
+    ("help.general.back", "返回 / 取消"),
+    ("help.general.help", "快捷键帮助（任意界面）"),
+    ("help.general.quit", "退出"),
+    ("help.general.scroll", "选择 / 滚动"),
+    ("help.general.jump", "输出：跳到顶部 / 底部"),
+    ("help.general.follow", "执行中：跟随输出 开 / 关"),
+    ("help.key.wheel", "鼠标滚轮"),
+    ("help.general.wheel", "滚动输出"),
+    ("help.general.line", "输入框：行首 / 行尾"),
+    ("help.general.word", "输入框：按词移动"),
+    ("help.general.kill", "输入框：删除前一词 / 到行首 / 到行尾"),
+    ("help.general.paste", "输入框：粘贴剪贴板"),
+    ("help.general.raw", "AI 分析：切换 markdown 原文 / 渲染"),
+    ("help.update", "系统更新"),
+    ("help.update.start", "开始更新"),
+    ("help.update.partial", "选择部分更新的包（预览中）"),
+    ("help.update.keyring", "先更新 archlinux-keyring（预览中 / 签名失败后）"),
+    ("help.update.retry", "重试失败的更新"),
+    ("help.update.tab", "切换更新日志 / AI 分析"),
+    ("help.update.chat", "追问 AI"),
+    ("help.update.copy", "复制内容"),
+    ("help.update.search", "搜索日志"),
+    ("help.update.next", "下一个 / 上一个匹配"),
+    ("help.update.pacnew", "合并 .pacnew 配置（更新后）"),
+    ("help.install", "安装"),
+    ("help.install.mark", "多选"),
+    ("help.install.confirm", "预览 / 确认安装"),
+    ("help.install.sort", "切换排序"),
+    ("help.install.repo", "筛选仓库"),
+    ("help.install.sizes", "显示大小"),
+    ("help.install.import", "导入包列表"),
+    ("help.install.plan", "将选中 / 标记的包加入事务计划"),
+    ("help.install.explicit", "切换 --asexplicit（预览中）"),
+    ("help.install.copy", "复制内容"),
+    ("help.install.search", "搜索日志"),
+    ("help.install.next", "下一个 / 上一个匹配"),
+    ("help.remove", "卸载"),
+    ("help.remove.mark", "多选"),
+    ("help.remove.confirm", "预览 / 确认卸载"),
+    ("help.remove.sort", "切换排序"),
+    ("help.remove.plan", "将选中 / 标记的包加入事务计划"),
+    ("help.remove.variant", "切换卸载方式（预览中）"),
+    ("help.remove.copy", "复制内容"),
+    ("help.remove.search", "搜索日志"),
+    ("help.remove.next", "下一个 / 上一个匹配"),
+    ("help.plan", "事务计划"),
+    ("help.plan.confirm", "预览 / 确认执行"),
+    ("help.plan.delete", "移除选中项"),
+    ("help.plan.clear", "清空计划"),
+    ("help.plan.variant", "切换卸载方式"),
+    ("help.plan.copy", "复制输出"),
+    ("help.query", "查询"),
+    ("help.query.tab", "切换本地 / 远程面板"),
+    ("help.query.detail", "查看详情"),
+    ("help.query.repo", "筛选仓库"),
+    ("help.query.mark", "多选标记"),
+    ("help.query.batch", "批量安装未安装的 / 卸载已安装的标记包"),
+    ("help.query.open", "打开主页（详情中）"),
+    ("help.query.action", "安装 / 卸载（详情中）"),
+    ("help.query.optdeps", "选择 / 安装可选依赖（详情中）"),
+    ("help.shell", "自定义命令"),
+    ("help.shell.run", "执行 / 重新执行"),
+    ("help.shell.complete", "补全"),
+    ("help.shell.history", "历史记录"),
+    ("help.shell.local", "安装本地包"),
+    ("help.shell.search", "搜索输出"),
+    ("help.shell.next", "下一个 / 上一个匹配"),
+    ("help.pacnew", "合并配置"),
+    ("help.pacnew.diff", "查看差异"),
+    ("help.pacnew.accept", "接受新文件（覆盖在用配置，仅 .pacnew）"),
+    ("help.pacnew.delete", "删除新文件 / 旧配置 (.pacsave)"),
+    ("help.settings", "设置"),
+    ("help.settings.edit", "切换 / 编辑"),
+    ("help.settings.option", "切换选项"),
+    ("help.settings.filter", "按名称筛选设置项"),
+    ("help.settings.save", "保存"),
+    ("help.settings.reload", "重新加载"),
+    ("help.settings.defaults", "恢复默认（保留 API Key）"),
+    ("help.settings.test", "测试 API 连接（使用未保存的设置）"),
+    ("help.settings.keyring", "API Key 存入密钥环"),
+    ("help.settings.export", "导出包列表 / 外部包"),
+    // 通用
+    ("common.pm_info", " | 包管理器: {}"),
+    ("common.error", "❌ 错误"),
+    ("common.analyzing", "🤖 AI 分析中..."),
+    ("common.analysis_done", "✨ 分析完成"),
+    ("common.follow_on", "跟随: 开 (f)"),
+    ("common.follow_off", "跟随: 关 (f)"),
+    ("common.report_saved", "报告已保存: {}"),
+    ("common.error_back", "发生错误 | Esc 返回主页"),
+    ("common.back", "Esc 返回"),
+    ("common.loading", "加载中..."),
+    // 系统更新
+    ("update.title.check", "🔍 检测包管理器..."),
+    ("update.title.preview", "📝 可用更新列表"),
+    ("update.title.updating", "⚙️  正在更新系统..."),
+    ("update.title.complete", "✅ 更新完成"),
+    ("update.reboot", " | ⚠ 需要重启"),
+    ("update.view.log", "更新日志 [Tab 切换到 AI 分析]"),
+    ("update.view.analysis", "AI 分析报告 [Tab 切换到更新日志]"),
+    ("update.footer.checking", "正在检测包管理器..."),
+    ("update.footer.no_updates", "Esc 返回 | q 退出"),
+    ("update.footer.preview", "按 Enter 开始更新 | Space 选择部分更新 | Esc 返回 | ↑↓ 选择"),
+    ("update.footer.keyring_on", " | k 密钥环优先: 开"),
+    ("update.footer.keyring_off", " | k 密钥环优先: 关"),
+    (
+        "update.footer.partial",
+        "⚠ 部分更新: 已选 {}/{} 个包，将先刷新数据库再只升级所选包 (-Sy 而非 -Syu)。Arch 不支持部分更新，其余包留在旧版本，可能因库版本不一致导致程序无法启动 | Enter 更新所选 | Space 取消选择（全部取消即完整更新） | Esc 返回",
+    ),
+    ("update.footer.news", "⚠ 有新公告 | {}"),
+    ("update.footer.download", "总下载 {}"),
+    ("update.footer.running", "更新进行中..."),
+    ("update.footer.done_keys", "y 复制 | / 搜索 | ↑↓ 滚动 | Esc 返回主页"),
+    ("update.footer.signature", "✗ 签名校验失败 | k 先更新密钥环再重试 | r 直接重试 | y 复制 | / 搜索 | ↑↓ 滚动 | Esc 返回主页"),
+    ("update.footer.failed", "✗ 更新失败 | r 重试 | y 复制 | / 搜索 | ↑↓ 滚动 | Esc 返回主页"),
+    ("update.footer.done", "更新完成 | y 复制 | / 搜索 | ↑↓ 滚动 | Esc 返回主页"),
+    ("update.footer.analyzing", "AI 正在分析更新内容... | Tab 切换视图 | r 原文/渲染 | ↑↓ 滚动 | Esc 取消分析"),
+    ("update.footer.chat_input", "追问: {}▏ | Enter 发送 | Esc 取消"),
+    ("update.footer.chat_pending", "AI 正在回答... | ↑↓ 滚动"),
+    ("update.footer.analysis_keys", "Tab 切换视图 | r 原文/渲染 | i 追问 | y 复制 | / 搜索 | ↑↓ 滚动 | Esc 返回主页"),
+    ("update.footer.analysis", "Tab 切换视图 | r 原文/渲染 | i 追问 | y 复制 | / 搜索 | ↑↓ 滚动 | Esc 返回主页 | q 退出"),
+    ("update.footer.retry", " | r 重试"),
+    ("update.footer.error_back", "{}{} | Esc 返回主页"),
+    ("update.footer.error", "发生错误{} | Esc 返回主页 | q 退出"),
+    // 安装
+    ("install.title", "📦 安装软件包 (-S)"),
+    ("install.title.repo", "📦 安装软件包 (-S) | 仓库: {}"),
+    ("install.title.review", "📜 PKGBUILD 审查 - {}"),
+    ("install.title.preview", "📦 安装预览 - {} 个包: {}"),
+    ("install.title.installing", "⚙️  正在安装..."),
+    ("install.title.complete", "✅ 安装完成"),
+    ("install.title.default", "📦 安装"),
+    ("install.view.log", "安装日志"),
+    ("install.view.log_tab", "安装日志 [Tab 切换到 AI 分析]"),
+    ("install.view.analysis", "AI 分析报告 [Tab 切换到安装日志]"),
+    ("install.footer.empty", "输入关键词搜索远程仓库包 | Ctrl+O 导入包列表(路径/默认) | Esc 返回"),
+    ("install.footer.marked_empty", "已标记 {} 个 | 输入关键词继续搜索 | Enter 安装标记项 | Esc 返回"),
+    ("install.footer.results", "↑↓ 选择 | Space 多选 | Enter 安装选中 | Ctrl+T 排序: {} | Ctrl+F 筛选仓库 | Ctrl+D 大小 | Esc 返回"),
+    (
+        "install.footer.marked",
+        "已标记 {} 个 | ↑↓ 选择 | Space 多选/取消 | Enter 安装标记项 | Ctrl+T 排序: {} | Ctrl+F 筛选仓库 | Ctrl+D 大小 | Esc 返回",
+    ),
+    ("install.footer.fetching_pkgbuild", "正在获取 PKGBUILD..."),
+    ("install.footer.review", "Enter 继续到安装预览 | Esc 返回搜索 | ↑↓ 滚动 | PgUp/PgDn 翻页"),
+    ("install.footer.space_confirm", "⛔ 根分区空间不足！再按一次 Enter 确认安装 | Esc 取消"),
+    ("install.footer.space_warning", "⛔ 根分区空间不足 | Enter 确认（需两次）| e 切换 --asexplicit | Esc 返回搜索 | ↑↓ 滚动"),
+    ("install.footer.fetching_preview", "正在获取安装预览..."),
+    ("install.footer.preview", "参数: {} | e 切换 --asexplicit | 按 Enter 确认安装 | Esc 返回搜索 | ↑↓ 滚动"),
+    ("install.footer.running", "安装进行中..."),
+    ("install.footer.done", "安装完成 | y 复制 | ↑↓ 滚动 | Esc 返回主页"),
+    ("install.footer.analyzing", "AI 正在分析安装内容..."),
+    // 卸载
+    ("remove.title.remove", "卸载软件包"),
+    ("remove.title.orphans", "清理孤立包"),
+    ("remove.title.largest", "占用空间最大的包"),
+    ("remove.title.preview", "🗑️  卸载预览 - {} 个包: {}"),
+    ("remove.title.removing", "⚙️  正在卸载..."),
+    ("remove.title.complete", "✅ 卸载完成"),
+    ("remove.title.default", "🗑️  卸载"),
+    ("remove.view.log", "卸载日志"),
+    ("remove.view.log_tab", "卸载日志 [Tab 切换到 AI 分析]"),
+    ("remove.view.analysis", "AI 分析报告 [Tab 切换到卸载日志]"),
+    ("remove.footer.no_orphans", "没有孤立包 | Esc 返回"),
+    ("remove.footer.empty", "输入关键词筛选已安装包 | Esc 返回"),
+    ("remove.footer.list", "↑↓ 选择 | Space 多选 | Enter 卸载选中 | Ctrl+T 排序: {} | Esc 返回"),
+    ("remove.footer.marked", "↑↓ 选择 | Space 多选/取消 | Enter 卸载标记项 | Ctrl+T 排序: {} | Esc 返回"),
+    ("remove.footer.fetching_preview", "正在获取卸载预览..."),
+    ("remove.footer.protected_confirm", "⛔ 将卸载系统关键包！再按一次 Enter 确认 | Esc 取消"),
+    ("remove.footer.protected", "⛔ 含系统关键包 | 方式: {} | m 切换 | Enter 确认（需两次）| Esc 返回列表 | ↑↓ 滚动"),
+    ("remove.footer.preview", "方式: {} ({}) | m 切换 | Enter 确认卸载 | Esc 返回列表 | ↑↓ 滚动"),
+    ("remove.footer.running", "卸载进行中..."),
+    ("remove.footer.done", "卸载完成 | y 复制 | ↑↓ 滚动 | Esc 返回主页"),
+    ("remove.footer.analyzing", "AI 正在分析卸载内容..."),
+    // 安装 / 卸载共用的输出页脚
+    ("output.footer.done_keys", "y 复制 | ↑↓ 滚动 | Esc 返回主页"),
+    ("output.footer.analysis_keys", "Tab 切换视图 | r 原文/渲染 | y 复制 | Esc 返回主页"),
+    ("output.footer.analysis", "Tab 切换视图 | r 原文/渲染 | y 复制 | ↑↓ 滚动 | Esc 返回主页"),
+    // 查询
+    ("query.title", "🔍 查询软件包 (Shift+Q)"),
+    ("query.title.repo", "🔍 查询软件包 (Shift+Q) | 仓库: {}"),
+    ("query.title.detail", "📦 包信息 - {}"),
+    (
+        "query.footer.marked",
+        "已标记 {} 个（未安装 {} / 已安装 {}）| Space 标记 | Ctrl+S 安装未安装的 | Ctrl+R 卸载已安装的 | Esc 返回",
+    ),
+    ("query.footer.searching", "搜索中... | Tab 切换面板 | ↑↓ 选择 | Enter 查看详情 | Esc 返回"),
+    ("query.footer.history", "↑ 更早的搜索 | ↓ 较新的搜索 | Enter 查看详情 | Esc 返回"),
+    ("query.footer.empty", "输入关键词搜索 | ↑ 搜索历史 | Tab 切换面板 | Esc 返回"),
+    ("query.footer.list", "输入关键词搜索 | Tab 切换面板 | ↑↓ 选择 | Space 标记 | Enter 查看详情 | Ctrl+F 筛选仓库 | Esc 返回"),
+    ("query.footer.remote_error", "✗ 远程搜索: {} | {}"),
+    ("query.footer.detail", "↑↓ 滚动 | PgUp/PgDn 翻页 | o 打开主页 | i 安装 | Esc 返回列表"),
+    ("query.footer.detail_installed", "↑↓ 滚动 | PgUp/PgDn 翻页 | o 打开主页 | r 卸载 | Esc 返回列表"),
+    ("query.footer.detail_files", "↑↓ 滚动 | PgUp/PgDn 翻页 | Tab 切换目录视图 | o 打开主页 | r 卸载 | Esc 返回列表"),
+    ("query.footer.detail_dirs", "↑↓ 滚动 | PgUp/PgDn 翻页 | Tab 切换文件视图 | o 打开主页 | r 卸载 | Esc 返回列表"),
+    ("query.footer.optdeps", "[ ] 选择可选依赖 | Enter 安装 | {}"),
+    // 设置
+    ("settings.title", "⚙  设置"),
+    ("settings.footer.edit_multiline", "输入新值 | Ctrl+J 换行 | Enter 确认 | Esc 取消"),
+    ("settings.footer.edit", "输入新值 | Enter 确认 | Esc 取消"),
+    ("settings.footer.filter_editing", "筛选: {}_ | ↑↓ 选择 | Enter 确认 | Esc 清除"),
+    ("settings.footer.filtered", "筛选: {} ({} 项) | / 修改 | Esc 清除筛选 | ↑↓ 选择 | Enter/Space 切换/编辑 | Ctrl+S 保存"),
+    (
+        "settings.footer.browse",
+        "↑↓ 选择 | / 筛选 | Enter/Space 切换/编辑 | ←→ 选项 | Ctrl+S 保存 | Ctrl+R 重新加载 | Ctrl+D 恢复默认 | Ctrl+T 测试连接 | Ctrl+K Key 存入密钥环 | Ctrl+E/F 导出包列表/外部包 | Esc 返回",
+    ),
+    // 自定义命令
+    ("shell.title.input", "💻 自定义命令"),
+    ("shell.title.picking", "📦 安装本地软件包"),
+    ("shell.title.running", "⚙️  命令执行中..."),
+    ("shell.title.done", "✅ 命令完成"),
+    ("shell.title.error", "❌ 命令错误"),
+    ("shell.input.title", "输入命令"),
+    ("shell.footer.input_empty", "输入命令后 Enter 执行（含 | > ; 等时经 sh -c）| Tab 补全 | ↑↓ 历史 | Ctrl+L 安装本地包 | Esc 返回"),
+    ("shell.footer.input", "Enter 执行（管道/重定向经 sh -c）| Tab 补全 | ↑↓ 历史记录 | Ctrl+L 安装本地包 | Esc 返回主页"),
+    ("shell.picker.title", "本地软件包 ({})"),
+    ("shell.picker.empty", "未找到 *.pkg.tar.* 文件（可在输入框中填写目录或文件路径后按 Ctrl+L）"),
+    ("shell.footer.picker_empty", "Esc 返回"),
+    ("shell.footer.picker", "Enter 安装 ({} pacman -U) | ↑↓ 选择 | Esc 返回"),
+    ("shell.footer.picker_progress", "{} | Enter 安装 | ↑↓ 选择 | Esc 返回"),
+    ("shell.output.title", "输出"),
+    ("shell.output.running", "输出 (Esc 取消)"),
+    ("shell.output.error", "输出 (错误)"),
+    ("shell.footer.running", "执行中... | Esc 取消 | / 搜索 | ↑↓ 滚动 | {}"),
+    ("shell.footer.running_progress", "{} | Esc 取消 | ↑↓ 滚动 | {}"),
+    ("shell.footer.done_progress", "{} | Enter 重新执行 | Esc 新命令 | y 复制 | / 搜索 | ↑↓ 滚动"),
+    ("shell.footer.done_ok", "✓ 命令成功 | Enter 重新执行 | Esc 新命令 | y 复制 | / 搜索 | ↑↓ 滚动"),
+    ("shell.footer.done_failed", "✗ 命令失败 | Enter 重新执行 | Esc 新命令 | y 复制 | / 搜索 | ↑↓ 滚动"),
+    ("shell.footer.done", "Esc 返回输入 | Enter 重新执行 | y 复制 | / 搜索 | ↑↓ 滚动"),
+    ("shell.footer.error", "❌ 执行出错 | Esc 新命令 | ↑↓ 滚动"),
+    // 合并 .pacnew
+    ("pacnew.title", "🧩 合并配置文件 | 待处理: {} 个"),
+    ("pacnew.footer.list", "Enter 查看差异 | a 接受新文件 | d 删除新文件 | ↑↓ 选择 | Esc 返回"),
+    ("pacnew.footer.diff", "a 接受新文件 | d 删除新文件 | ↑↓ PgUp PgDn 滚动 | Esc 返回列表"),
+    ("pacnew.footer.list_pacsave", "Enter 查看差异 | d 删除旧配置 (.pacsave) | ↑↓ 选择 | Esc 返回"),
+    ("pacnew.footer.diff_pacsave", "d 删除旧配置 (.pacsave) | ↑↓ PgUp PgDn 滚动 | Esc 返回列表"),
+    // 事务计划
+    ("plan.title", "📋 事务计划 | 卸载 {} 个 · 安装 {} 个"),
+    ("plan.added", "已加入计划: 卸载 {} · 安装 {} (Shift+P 查看)"),
+    ("plan.empty", "计划为空：在安装或卸载界面按 Ctrl+P 将选中的包加入计划"),
+    ("plan.view.entries", "待执行（先卸载后安装）"),
+    ("plan.view.preview", "事务预览"),
+    ("plan.view.output", "执行输出"),
+    ("plan.footer.empty", "计划为空 | Esc 返回"),
+    ("plan.footer.edit", "↑↓ 选择 | d 移除 | c 清空 | m 卸载方式: {} | Enter 预览 | Esc 返回"),
+    ("plan.footer.preview", "卸载方式: {} | m 切换 | Enter 确认执行 | Esc 返回计划 | ↑↓ 滚动"),
+    ("plan.footer.danger", "⛔ 含系统关键包或空间不足 | Enter 确认（需两次）| Esc 返回计划 | ↑↓ 滚动"),
+    ("plan.footer.danger_confirm", "⛔ 再按一次 Enter 确认执行 | Esc 取消"),
+    ("plan.footer.running", "事务执行中..."),
+    ("plan.footer.done", "事务完成 | y 复制 | ↑↓ 滚动 | Esc 返回主页"),
+];
+
+const EN: Catalog = &[
+    // Dashboard
+    ("dashboard.system_info", "── System ──"),
+    ("dashboard.shortcuts", "── Shortcuts ──"),
+    ("dashboard.detecting", "Detecting..."),
+    ("dashboard.counting", "Counting..."),
+    ("dashboard.distro", "Distro"),
+    ("dashboard.kernel", "Kernel"),
+    ("dashboard.uptime", "Uptime"),
+    ("dashboard.disk_free", "Root free"),
+    ("dashboard.pm", "Package manager"),
+    ("dashboard.pm_version", "Version"),
+    ("dashboard.profile", "Profile"),
+    ("dashboard.config_perms", "Config perms"),
+    ("dashboard.config_perms_value", "{} API key readable by others (Shift+C → Ctrl+P to fix)"),
+    ("dashboard.installed", "Installed"),
+    ("dashboard.installed_count", "{} packages"),
+    ("dashboard.breakdown", "Breakdown"),
+    ("dashboard.breakdown_value", "explicit {} · deps {} · foreign {}"),
+    ("dashboard.updates", "Updates"),
+    ("dashboard.checking", "{} checking..."),
+    ("dashboard.up_to_date", "Up to date"),
+    ("dashboard.updates_count", "{} available"),
+    ("dashboard.check_failed", "Check failed"),
+    ("dashboard.orphans", "Orphans"),
+    ("dashboard.no_orphans", "None"),
+    ("dashboard.orphans_count", "{} (o to clean)"),
+    ("dashboard.cache", "Package cache"),
+    ("dashboard.cache_size", "{} (c to clean)"),
+    ("dashboard.largest", "Largest"),
+    ("dashboard.largest_value", "{} {} (l to view)"),
+    ("dashboard.recent", "Recent changes"),
+    ("dashboard.recent_empty", "No entries in pacman.log"),
+    // Dashboard shortcuts
+    ("keys.update", "System update (Syu)"),
+    ("keys.install", "Install packages"),
+    ("keys.remove", "Remove packages"),
+    ("keys.query", "Query packages"),
+    ("keys.shell", "Custom command"),
+    ("keys.plan", "Transaction plan"),
+    ("keys.settings", "Settings"),
+    ("keys.orphans", "Clean orphans"),
+    ("keys.largest", "Largest packages"),
+    ("keys.cache", "Clean package cache"),
+    ("keys.pacnew", "Merge .pacnew files"),
+    ("keys.help", "Shortcut help"),
+    ("keys.quit", "Quit"),
+    ("help.title", " Shortcuts (Esc / ? to close) "),
+    ("help.dashboard", "Home"),
+    ("help.general", "General"),
+    ("help.general.back", "Back / cancel"),
+    ("help.general.help", "Shortcut help (anywhere)"),
+    ("help.general.quit", "Quit"),
+    ("help.general.scroll", "Select / scroll"),
+    ("help.general.jump", "Output: jump to top / bottom"),
+    ("help.general.follow", "Running: follow output on / off"),
+    ("help.key.wheel", "Mouse wheel"),
+    ("help.general.wheel", "Scroll output"),
+    ("help.general.line", "Input: line start / end"),
+    ("help.general.word", "Input: move by word"),
+    ("help.general.kill", "Input: delete word / to start / to end"),
+    ("help.general.paste", "Input: paste clipboard"),
+    ("help.general.raw", "AI analysis: toggle raw / rendered markdown"),
+    ("help.update", "System update"),
+    ("help.update.start", "Start update"),
+    ("help.update.partial", "Select packages for a partial update (preview)"),
+    ("help.update.keyring", "Update archlinux-keyring first (preview / after signature errors)"),
+    ("help.update.retry", "Retry a failed update"),
+    ("help.update.tab", "Switch update log / AI analysis"),
+    ("help.update.chat", "Ask the AI a follow-up"),
+    ("help.update.copy", "Copy content"),
+    ("help.update.search", "Search log"),
+    ("help.update.next", "Next / previous match"),
+    ("help.update.pacnew", "Merge .pacnew files (after update)"),
+    ("help.install", "Install"),
+    ("help.install.mark", "Multi-select"),
+    ("help.install.confirm", "Preview / confirm install"),
+    ("help.install.sort", "Change sort order"),
+    ("help.install.repo", "Filter by repository"),
+    ("help.install.sizes", "Show sizes"),
+    ("help.install.import", "Import package list"),
+    ("help.install.plan", "Add selected / marked packages to the plan"),
+    ("help.install.explicit", "Toggle --asexplicit (preview)"),
+    ("help.install.copy", "Copy content"),
+    ("help.install.search", "Search log"),
+    ("help.install.next", "Next / previous match"),
+    ("help.remove", "Remove"),
+    ("help.remove.mark", "Multi-select"),
+    ("help.remove.confirm", "Preview / confirm removal"),
+    ("help.remove.sort", "Change sort order"),
+    ("help.remove.plan", "Add selected / marked packages to the plan"),
+    ("help.remove.variant", "Switch removal mode (preview)"),
+    ("help.remove.copy", "Copy content"),
+    ("help.remove.search", "Search log"),
+    ("help.remove.next", "Next / previous match"),
+    ("help.plan", "Transaction plan"),
+    ("help.plan.confirm", "Preview / confirm run"),
+    ("help.plan.delete", "Remove selected entry"),
+    ("help.plan.clear", "Clear the plan"),
+    ("help.plan.variant", "Switch removal mode"),
+    ("help.plan.copy", "Copy output"),
+    ("help.query", "Query"),
+    ("help.query.tab", "Switch local / remote panel"),
+    ("help.query.detail", "Show details"),
+    ("help.query.repo", "Filter by repository"),
+    ("help.query.mark", "Mark for batch actions"),
+    ("help.query.batch", "Install uninstalled / remove installed marked packages"),
+    ("help.query.open", "Open homepage (details)"),
+    ("help.query.action", "Install / remove (details)"),
+    ("help.query.optdeps", "Select / install optional dependencies (details)"),
+    ("help.shell", "Custom command"),
+    ("help.shell.run", "Run / run again"),
+    ("help.shell.complete", "Complete"),
+    ("help.shell.history", "History"),
+    ("help.shell.local", "Install local package"),
+    ("help.shell.search", "Search output"),
+    ("help.shell.next", "Next / previous match"),
+    ("help.pacnew", "Merge configs"),
+    ("help.pacnew.diff", "View diff"),
+    ("help.pacnew.accept", "Accept new file (overwrites the live config, .pacnew only)"),
+    ("help.pacnew.delete", "Delete new file / old config (.pacsave)"),
+    ("help.settings", "Settings"),
+    ("help.settings.edit", "Toggle / edit"),
+    ("help.settings.option", "Cycle options"),
+    ("help.settings.filter", "Filter settings by name"),
+    ("help.settings.save", "Save"),
+    ("help.settings.reload", "Reload"),
+    ("help.settings.defaults", "Restore defaults (keeps API key)"),
+    ("help.settings.test", "Test API connection (with unsaved settings)"),
+    ("help.settings.keyring", "Store API key in keyring"),
+    ("help.settings.export", "Export package list / foreign packages"),
+    // Common
+    ("common.pm_info", " | Package manager: {}"),
+    ("common.error", "❌ Error"),
+    ("common.analyzing", "🤖 AI analyzing..."),
+    ("common.analysis_done", "✨ Analysis complete"),
+    ("common.follow_on", "Follow: on (f)"),
+    ("common.follow_off", "Follow: off (f)"),
+    ("common.report_saved", "Report saved: {}"),
+    ("common.error_back", "An error occurred | Esc home"),
+    ("common.back", "Esc back"),
+    ("common.loading", "Loading..."),
+    // System update
+    ("update.title.check", "🔍 Detecting package manager..."),
+    ("update.title.preview", "📝 Available updates"),
+    ("update.title.updating", "⚙️  Updating system..."),
+    ("update.title.complete", "✅ Update complete"),
+    ("update.reboot", " | ⚠ Reboot required"),
+    ("update.view.log", "Update log [Tab for AI analysis]"),
+    ("update.view.analysis", "AI analysis [Tab for update log]"),
+    ("update.footer.checking", "Detecting package manager..."),
+    ("update.footer.no_updates", "Esc back | q quit"),
+    ("update.footer.preview", "Enter start update | Space partial update | Esc back | ↑↓ select"),
+    ("update.footer.keyring_on", " | k keyring first: on"),
+    ("update.footer.keyring_off", " | k keyring first: off"),
+    (
+        "update.footer.partial",
+        "⚠ Partial update: {}/{} packages selected, refreshes the database and upgrades only those (-Sy instead of -Syu). Arch does not support partial upgrades; the rest stay outdated and mismatched libraries may break programs | Enter update selected | Space unselect (none = full update) | Esc back",
+    ),
+    ("update.footer.news", "⚠ New announcements | {}"),
+    ("update.footer.download", "Download {}"),
+    ("update.footer.running", "Updating..."),
+    ("update.footer.done_keys", "y copy | / search | ↑↓ scroll | Esc home"),
+    ("update.footer.signature", "✗ Signature check failed | k update keyring and retry | r retry | y copy | / search | ↑↓ scroll | Esc home"),
+    ("update.footer.failed", "✗ Update failed | r retry | y copy | / search | ↑↓ scroll | Esc home"),
+    ("update.footer.done", "Update complete | y copy | / search | ↑↓ scroll | Esc home"),
+    ("update.footer.analyzing", "AI is analyzing the update... | Tab switch view | r raw/rendered | ↑↓ scroll | Esc cancel"),
+    ("update.footer.chat_input", "Ask: {}▏ | Enter send | Esc cancel"),
+    ("update.footer.chat_pending", "AI is answering... | ↑↓ scroll"),
+    ("update.footer.analysis_keys", "Tab switch view | r raw/rendered | i ask | y copy | / search | ↑↓ scroll | Esc home"),
+    ("update.footer.analysis", "Tab switch view | r raw/rendered | i ask | y copy | / search | ↑↓ scroll | Esc home | q quit"),
+    ("update.footer.retry", " | r retry"),
+    ("update.footer.error_back", "{}{} | Esc home"),
+    ("update.footer.error", "An error occurred{} | Esc home | q quit"),
+    // Install
+    ("install.title", "📦 Install packages (-S)"),
+    ("install.title.repo", "📦 Install packages (-S) | Repo: {}"),
+    ("install.title.review", "📜 PKGBUILD review - {}"),
+    ("install.title.preview", "📦 Install preview - {} packages: {}"),
+    ("install.title.installing", "⚙️  Installing..."),
+    ("install.title.complete", "✅ Install complete"),
+    ("install.title.default", "📦 Install"),
+    ("install.view.log", "Install log"),
+    ("install.view.log_tab", "Install log [Tab for AI analysis]"),
+    ("install.view.analysis", "AI analysis [Tab for install log]"),
+    ("install.footer.empty", "Type to search remote repos | Ctrl+O import package list (path/default) | Esc back"),
+    ("install.footer.marked_empty", "{} marked | Type to keep searching | Enter install marked | Esc back"),
+    ("install.footer.results", "↑↓ select | Space mark | Enter install selected | Ctrl+T sort: {} | Ctrl+F filter repo | Ctrl+D sizes | Esc back"),
+    (
+        "install.footer.marked",
+        "{} marked | ↑↓ select | Space mark/unmark | Enter install marked | Ctrl+T sort: {} | Ctrl+F filter repo | Ctrl+D sizes | Esc back",
+    ),
+    ("install.footer.fetching_pkgbuild", "Fetching PKGBUILD..."),
+    ("install.footer.review", "Enter continue to preview | Esc back to search | ↑↓ scroll | PgUp/PgDn page"),
+    ("install.footer.space_confirm", "⛔ Low space on root! Press Enter again to install | Esc cancel"),
+    ("install.footer.space_warning", "⛔ Low space on root | Enter confirm (twice) | e toggle --asexplicit | Esc back to search | ↑↓ scroll"),
+    ("install.footer.fetching_preview", "Fetching install preview..."),
+    ("install.footer.preview", "Flags: {} | e toggle --asexplicit | Enter confirm install | Esc back to search | ↑↓ scroll"),
+    ("install.footer.running", "Installing..."),
+    ("install.footer.done", "Install complete | y copy | ↑↓ scroll | Esc home"),
+    ("install.footer.analyzing", "AI is analyzing the install..."),
+    // Remove
+    ("remove.title.remove", "Remove packages"),
+    ("remove.title.orphans", "Clean orphans"),
+    ("remove.title.largest", "Largest packages"),
+    ("remove.title.preview", "🗑️  Remove preview - {} packages: {}"),
+    ("remove.title.removing", "⚙️  Removing..."),
+    ("remove.title.complete", "✅ Remove complete"),
+    ("remove.title.default", "🗑️  Remove"),
+    ("remove.view.log", "Remove log"),
+    ("remove.view.log_tab", "Remove log [Tab for AI analysis]"),
+    ("remove.view.analysis", "AI analysis [Tab for remove log]"),
+    ("remove.footer.no_orphans", "No orphans | Esc back"),
+    ("remove.footer.empty", "Type to filter installed packages | Esc back"),
+    ("remove.footer.list", "↑↓ select | Space mark | Enter remove selected | Ctrl+T sort: {} | Esc back"),
+    ("remove.footer.marked", "↑↓ select | Space mark/unmark | Enter remove marked | Ctrl+T sort: {} | Esc back"),
+    ("remove.footer.fetching_preview", "Fetching remove preview..."),
+    ("remove.footer.protected_confirm", "⛔ This removes critical system packages! Press Enter again to confirm | Esc cancel"),
+    ("remove.footer.protected", "⛔ Includes critical packages | Mode: {} | m switch | Enter confirm (twice) | Esc back to list | ↑↓ scroll"),
+    ("remove.footer.preview", "Mode: {} ({}) | m switch | Enter confirm remove | Esc back to list | ↑↓ scroll"),
+    ("remove.footer.running", "Removing..."),
+    ("remove.footer.done", "Remove complete | y copy | ↑↓ scroll | Esc home"),
+    ("remove.footer.analyzing", "AI is analyzing the removal..."),
+    // Install / remove output footers
+    ("output.footer.done_keys", "y copy | ↑↓ scroll | Esc home"),
+    ("output.footer.analysis_keys", "Tab switch view | r raw/rendered | y copy | Esc home"),
+    ("output.footer.analysis", "Tab switch view | r raw/rendered | y copy | ↑↓ scroll | Esc home"),
+    // Query
+    ("query.title", "🔍 Query packages (Shift+Q)"),
+    ("query.title.repo", "🔍 Query packages (Shift+Q) | Repo: {}"),
+    ("query.title.detail", "📦 Package info - {}"),
+    (
+        "query.footer.marked",
+        "{} marked ({} not installed / {} installed) | Space mark | Ctrl+S install missing | Ctrl+R remove installed | Esc back",
+    ),
+    ("query.footer.searching", "Searching... | Tab switch panel | ↑↓ select | Enter details | Esc back"),
+    ("query.footer.history", "↑ older searches | ↓ newer searches | Enter details | Esc back"),
+    ("query.footer.empty", "Type to search | ↑ search history | Tab switch panel | Esc back"),
+    ("query.footer.list", "Type to search | Tab switch panel | ↑↓ select | Space mark | Enter details | Ctrl+F filter repo | Esc back"),
+    ("query.footer.remote_error", "✗ Remote search: {} | {}"),
+    ("query.footer.detail", "↑↓ scroll | PgUp/PgDn page | o open homepage | i install | Esc back to list"),
+    ("query.footer.detail_installed", "↑↓ scroll | PgUp/PgDn page | o open homepage | r remove | Esc back to list"),
+    ("query.footer.detail_files", "↑↓ scroll | PgUp/PgDn page | Tab directory view | o open homepage | r remove | Esc back to list"),
+    ("query.footer.detail_dirs", "↑↓ scroll | PgUp/PgDn page | Tab file view | o open homepage | r remove | Esc back to list"),
+    ("query.footer.optdeps", "[ ] select optional dep | Enter install | {}"),
+    // Settings
+    ("settings.title", "⚙  Settings"),
+    ("settings.footer.edit_multiline", "Enter a value | Ctrl+J newline | Enter confirm | Esc cancel"),
+    ("settings.footer.edit", "Enter a value | Enter confirm | Esc cancel"),
+    ("settings.footer.filter_editing", "Filter: {}_ | ↑↓ select | Enter confirm | Esc clear"),
+    ("settings.footer.filtered", "Filter: {} ({} items) | / edit | Esc clear filter | ↑↓ select | Enter/Space toggle/edit | Ctrl+S save"),
+    (
+        "settings.footer.browse",
+        "↑↓ select | / filter | Enter/Space toggle/edit | ←→ options | Ctrl+S save | Ctrl+R reload | Ctrl+D defaults | Ctrl+T test API | Ctrl+K key to keyring | Ctrl+E/F export packages/foreign | Esc back",
+    ),
+    // Custom command
+    ("shell.title.input", "💻 Custom command"),
+    ("shell.title.picking", "📦 Install local package"),
+    ("shell.title.running", "⚙️  Running command..."),
+    ("shell.title.done", "✅ Command finished"),
+    ("shell.title.error", "❌ Command error"),
+    ("shell.input.title", "Command"),
+    (
+        "shell.footer.input_empty",
+        "Type a command and press Enter (| > ; etc. run via sh -c) | Tab complete | ↑↓ history | Ctrl+L install local package | Esc back",
+    ),
+    ("shell.footer.input", "Enter run (pipes/redirects via sh -c) | Tab complete | ↑↓ history | Ctrl+L install local package | Esc home"),
+    ("shell.picker.title", "Local packages ({})"),
+    ("shell.picker.empty", "No *.pkg.tar.* files found (type a directory or file path in the input and press Ctrl+L)"),
+    ("shell.footer.picker_empty", "Esc back"),
+    ("shell.footer.picker", "Enter install ({} pacman -U) | ↑↓ select | Esc back"),
+    ("shell.footer.picker_progress", "{} | Enter install | ↑↓ select | Esc back"),
+    ("shell.output.title", "Output"),
+    ("shell.output.running", "Output (Esc to cancel)"),
+    ("shell.output.error", "Output (error)"),
+    ("shell.footer.running", "Running... | Esc cancel | / search | ↑↓ scroll | {}"),
+    ("shell.footer.running_progress", "{} | Esc cancel | ↑↓ scroll | {}"),
+    ("shell.footer.done_progress", "{} | Enter run again | Esc new command | y copy | / search | ↑↓ scroll"),
+    ("shell.footer.done_ok", "✓ Command succeeded | Enter run again | Esc new command | y copy | / search | ↑↓ scroll"),
+    ("shell.footer.done_failed", "✗ Command failed | Enter run again | Esc new command | y copy | / search | ↑↓ scroll"),
+    ("shell.footer.done", "Esc back to input | Enter run again | y copy | / search | ↑↓ scroll"),
+    ("shell.footer.error", "❌ Execution error | Esc new command | ↑↓ scroll"),
+    // .pacnew merge
+    ("pacnew.title", "🧩 Merge config files | Pending: {}"),
+    ("pacnew.footer.list", "Enter view diff | a accept new file | d delete new file | ↑↓ select | Esc back"),
+    ("pacnew.footer.diff", "a accept new file | d delete new file | ↑↓ PgUp PgDn scroll | Esc back to list"),
+    ("pacnew.footer.list_pacsave", "Enter view diff | d delete old config (.pacsave) | ↑↓ select | Esc back"),
+    ("pacnew.footer.diff_pacsave", "d delete old config (.pacsave) | ↑↓ PgUp PgDn scroll | Esc back to list"),
+    // Transaction plan
+    ("plan.title", "📋 Transaction plan | Remove {} · Install {}"),
+    ("plan.added", "Added to plan: remove {} · install {} (Shift+P to view)"),
+    ("plan.empty", "Plan is empty: press Ctrl+P in Install or Remove to add the selected packages"),
+    ("plan.view.entries", "Pending (removals run first)"),
+    ("plan.view.preview", "Transaction preview"),
+    ("plan.view.output", "Output"),
+    ("plan.footer.empty", "Plan is empty | Esc back"),
+    ("plan.footer.edit", "↑↓ select | d drop | c clear | m remove mode: {} | Enter preview | Esc back"),
+    ("plan.footer.preview", "Remove mode: {} | m switch | Enter confirm | Esc back to plan | ↑↓ scroll"),
+    ("plan.footer.danger", "⛔ Critical packages or low space | Enter confirm (twice) | Esc back to plan | ↑↓ scroll"),
+    ("plan.footer.danger_confirm", "⛔ Press Enter again to run | Esc cancel"),
+    ("plan.footer.running", "Running transaction..."),
+    ("plan.footer.done", "Transaction complete | y copy | ↑↓ scroll | Esc home"),
+];
@@ -1,3 +1,4 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -156,3 +157,111 @@ pub fn str_delete_forward(s: &mut String, cursor: &mut usize) {
         s.drain(byte_pos..next_byte_pos);
     }
 }
+
+/// 在光标处插入粘贴的文本：单行输入只取第一行，并丢弃其余控制字符
+pub fn str_insert_str(s: &mut String, cursor: &mut usize, text: &str, multiline: bool) {
+    let text = if multiline {
+        text
+    } else {
+        text.lines().find(|l| !l.trim().is_empty()).unwrap_or("")
+    };
+    for c in text.chars() {
+        if c == '\n' || !c.is_control() {
+            str_insert_char(s, cursor, c);
+        } else if c == '\t' {
+            str_insert_char(s, cursor, ' ');
+        }
+    }
+}
+
+/// 光标前一个词的起始位置（先跳过空白，再到上一个空白处）
+fn word_start_before(s: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = s.chars().collect();
+    let mut pos = cursor.min(chars.len());
+    while pos > 0 && chars[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    while pos > 0 && !chars[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    pos
+}
+
+/// 光标后一个词的结束位置（先跳过空白，再到下一个空白处）
+fn word_end_after(s: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = s.chars().collect();
+    let mut pos = cursor.min(chars.len());
+    while pos < chars.len() && chars[pos].is_whitespace() {
+        pos += 1;
+    }
+    while pos < chars.len() && !chars[pos].is_whitespace() {
+        pos += 1;
+    }
+    pos
+}
+
+/// Ctrl+←: 光标移到前一个词的开头
+pub fn str_word_left(s: &str, cursor: &mut usize) {
+    *cursor = word_start_before(s, *cursor);
+}
+
+/// Ctrl+→: 光标移到后一个词的末尾
+pub fn str_word_right(s: &str, cursor: &mut usize) {
+    *cursor = word_end_after(s, *cursor);
+}
+
+/// Ctrl+W: 删除光标前的一个词
+pub fn str_delete_word_back(s: &mut String, cursor: &mut usize) {
+    let start = word_start_before(s, *cursor);
+    let (from, to) = (char_to_byte(s, start), char_to_byte(s, *cursor));
+    s.drain(from..to);
+    *cursor = start;
+}
+
+/// Ctrl+U: 删除光标前的全部内容
+pub fn str_kill_to_start(s: &mut String, cursor: &mut usize) {
+    let to = char_to_byte(s, *cursor);
+    s.drain(..to);
+    *cursor = 0;
+}
+
+/// Ctrl+K: 删除光标后的全部内容
+pub fn str_kill_to_end(s: &mut String, cursor: &mut usize) {
+    let from = char_to_byte(s, *cursor);
+    s.truncate(from);
+}
+
+/// Emacs 风格编辑键的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditResult {
+    /// 不是编辑键，交由调用方继续处理
+    Ignored,
+    /// 只移动了光标
+    Moved,
+    /// 内容已改变（调用方需刷新筛选 / 搜索）
+    Changed,
+}
+
+/// Ctrl+A / Ctrl+E 行首 / 行尾，Ctrl+← / Ctrl+→ 按词移动，
+/// Ctrl+W 删除前一个词，Ctrl+U 删除到行首，Ctrl+K 删除到行尾
+pub fn handle_emacs_key(key: KeyEvent, s: &mut String, cursor: &mut usize) -> EditResult {
+    if !key.modifiers.contains(KeyModifiers::CONTROL) {
+        return EditResult::Ignored;
+    }
+    let before = s.len();
+    match key.code {
+        KeyCode::Char('a') => *cursor = 0,
+        KeyCode::Char('e') => *cursor = s.chars().count(),
+        KeyCode::Left => str_word_left(s, cursor),
+        KeyCode::Right => str_word_right(s, cursor),
+        KeyCode::Char('w') => str_delete_word_back(s, cursor),
+        KeyCode::Char('u') => str_kill_to_start(s, cursor),
+        KeyCode::Char('k') => str_kill_to_end(s, cursor),
+        _ => return EditResult::Ignored,
+    }
+    if s.len() == before {
+        EditResult::Moved
+    } else {
+        EditResult::Changed
+    }
+}
@@ -1,8 +1,9 @@
 use super::input::InputBox;
+use super::i18n::{t, tf};
 use super::layout;
-use super::state::{App, AppEvent, AppMode, InstallPhase, ViewMode};
-use super::theme::{BLUE, BRIGHT_WHITE, DESC_DIM, DIM, PINK, SEL_BG};
-use crate::tui::input::{str_insert_char, str_delete_back, str_delete_forward};
+use super::state::{follow_label, next_repo_filter, App, AppEvent, AppMode, InstallPhase, SortKey, ViewMode};
+use crate::tui::input::{handle_emacs_key, str_insert_char, str_delete_back, str_delete_forward, EditResult};
+use crate::package_manager::parse_version_spec;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Margin},
@@ -35,6 +36,7 @@ pub fn handle_install_key(
 ) -> bool {
     match app.install.phase {
         InstallPhase::Searching => handle_searching_key(key, app, tx),
+        InstallPhase::ReviewingPkgbuild => handle_review_key(key, app, tx, term_height),
         InstallPhase::PreviewingInstall => handle_preview_key(key, app),
         InstallPhase::Installing => handle_output_key(key, app, term_height),
         InstallPhase::InstallComplete => handle_output_key(key, app, term_height),
@@ -50,6 +52,14 @@ fn handle_searching_key(
     app: &mut App,
     tx: &mpsc::Sender<AppEvent>,
 ) -> bool {
+    match handle_emacs_key(key, &mut app.install.input, &mut app.install.cursor) {
+        EditResult::Changed => {
+            schedule_search(app);
+            return true;
+        }
+        EditResult::Moved => return true,
+        EditResult::Ignored => {}
+    }
     match key.code {
         KeyCode::Esc => {
             app.mode = AppMode::Dashboard;
@@ -70,11 +80,7 @@ fn handle_searching_key(
         KeyCode::Char(' ') => {
             // 多选切换
             if !app.install.results.is_empty() {
-                if app.install.marked.contains(&app.install.selected) {
-                    app.install.marked.remove(&app.install.selected);
-                } else {
-                    app.install.marked.insert(app.install.selected);
-                }
+                app.install.toggle_marked(app.install.selected);
                 // 选中后自动下移
                 let max = app.install.results.len().saturating_sub(1);
                 if app.install.selected < max {
@@ -84,30 +90,39 @@ fn handle_searching_key(
             true
         }
         KeyCode::Enter => {
-            // 收集选中的包，准备安装
-            if !app.install.results.is_empty() {
+            // 收集选中的包，准备安装（结果为空时仍可安装之前标记的包）
+            if !app.install.results.is_empty() || !app.install.marked.is_empty() {
                 let packages = collect_selected_packages(app);
                 if !packages.is_empty() {
-                    // 获取安装预览
-                    if let Some(pm) = app.package_manager.clone() {
-                        let tx_clone = tx.clone();
-                        let pkgs = packages.clone();
-                        tokio::spawn(async move {
-                            let preview = tokio::task::spawn_blocking(move || {
-                                pm.preview_install(&pkgs)
-                            })
-                            .await
-                            .unwrap_or_default();
-                            let _ = tx_clone.send(AppEvent::InstallPreviewReady(preview)).await;
-                        });
-                        app.install.phase = InstallPhase::PreviewingInstall;
-                        app.install.preview = vec!["正在获取安装预览...".to_string()];
-                        app.install.scroll = 0;
-                    }
+                    start_install_flow(app, tx, packages);
                 }
             }
             true
         }
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            import_package_list(app, tx);
+            true
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            super::plan::add_from_install(app);
+            true
+        }
+        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.install.repo_filter = next_repo_filter(&app.install.repo_filter, &app.install.unsorted);
+            app.install.apply_sort();
+            true
+        }
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.install.sort = app.install.sort.next_in(&SortKey::INSTALL);
+            app.install.apply_sort();
+            fetch_missing_sizes(app, tx);
+            true
+        }
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.install.show_sizes = !app.install.show_sizes;
+            fetch_missing_sizes(app, tx);
+            true
+        }
         KeyCode::Backspace => {
             str_delete_back(&mut app.install.input, &mut app.install.cursor);
             schedule_search(app);
@@ -151,11 +166,198 @@ fn handle_searching_key(
     }
 }
 
+/// 开始安装流程：选中了 AUR 包时先审查 PKGBUILD，否则直接进入安装预览
+pub fn start_install_flow(app: &mut App, tx: &mpsc::Sender<AppEvent>, packages: Vec<String>) {
+    let aur: Vec<String> = packages
+        .iter()
+        .map(|spec| parse_version_spec(spec).map(|(n, _)| n).unwrap_or(spec))
+        .filter(|name| app.install.known_aur.contains(*name))
+        .map(str::to_string)
+        .collect();
+    if aur.is_empty() {
+        start_install_preview(app, tx, packages);
+        return;
+    }
+
+    if let Some(pm) = app.package_manager.clone() {
+        let tx_clone = tx.clone();
+        tokio::spawn(async move {
+            let lines = tokio::task::spawn_blocking(move || {
+                let mut lines = Vec::new();
+                for name in &aur {
+                    lines.push(format!("===== {} / PKGBUILD =====", name));
+                    match pm.fetch_pkgbuild(name) {
+                        Ok(text) => lines.extend(text.lines().map(|l| l.replace('\t', "    "))),
+                        Err(e) => lines.push(format!("✗ {}", e)),
+                    }
+                    lines.push(String::new());
+                }
+                lines
+            })
+            .await
+            .unwrap_or_default();
+            let _ = tx_clone.send(AppEvent::InstallPkgbuildReady(lines)).await;
+        });
+        app.install.phase = InstallPhase::ReviewingPkgbuild;
+        app.install.pkgbuild = vec!["正在获取 PKGBUILD...".to_string()];
+        app.install.scroll = 0;
+    }
+}
+
+fn is_fetching_pkgbuild(app: &App) -> bool {
+    app.install.pkgbuild.len() == 1 && app.install.pkgbuild[0].contains("正在获取")
+}
+
+/// PKGBUILD 审查阶段按键处理
+fn handle_review_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    term_height: u16,
+) -> bool {
+    let visible = layout::visible_content_height(term_height);
+    let max_scroll = app.install.pkgbuild.len().saturating_sub(visible);
+    match key.code {
+        KeyCode::Esc => {
+            app.install.phase = InstallPhase::Searching;
+            app.install.pkgbuild.clear();
+            app.install.scroll = 0;
+            true
+        }
+        // 获取完成后才能继续
+        KeyCode::Enter if !is_fetching_pkgbuild(app) => {
+            let packages = collect_selected_packages(app);
+            app.install.pkgbuild.clear();
+            start_install_preview(app, tx, packages);
+            true
+        }
+        KeyCode::Up => {
+            app.install.scroll = app.install.scroll.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            app.install.scroll = (app.install.scroll + 1).min(max_scroll);
+            true
+        }
+        KeyCode::PageUp => {
+            app.install.scroll = app.install.scroll.saturating_sub(visible);
+            true
+        }
+        KeyCode::PageDown => {
+            app.install.scroll = (app.install.scroll + visible).min(max_scroll);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 获取安装预览并进入预览阶段
+pub fn start_install_preview(app: &mut App, tx: &mpsc::Sender<AppEvent>, packages: Vec<String>) {
+    if let Some(pm) = app.package_manager.clone() {
+        let tx_clone = tx.clone();
+        let min_free = app.config.min_free_space_mb.saturating_mul(1024 * 1024);
+        tokio::spawn(async move {
+            let preview = tokio::task::spawn_blocking(move || pm.preview_install(&packages, min_free))
+                .await
+                .unwrap_or_default();
+            let _ = tx_clone.send(AppEvent::InstallPreviewReady(preview)).await;
+        });
+        app.install.phase = InstallPhase::PreviewingInstall;
+        app.install.space_confirmed = false;
+        app.install.preview = vec!["正在获取安装预览...".to_string()];
+        app.install.scroll = 0;
+    }
+}
+
+/// 从文件导入包列表并进入安装预览
+///
+/// 路径取搜索框内容，为空时使用配置中的导出路径；已安装的包会被跳过（同 --needed）。
+fn import_package_list(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let pm = match app.package_manager.clone() {
+        Some(pm) => pm,
+        None => return,
+    };
+    let input = app.install.input.trim().to_string();
+    let path = if input.is_empty() {
+        app.config.explicit_export_path()
+    } else {
+        crate::config::expand_tilde(&input)
+    };
+
+    let names = match crate::package_manager::read_package_list(&path) {
+        Ok(names) => names,
+        Err(e) => {
+            app.install.progress = format!("导入失败: {}", e);
+            return;
+        }
+    };
+    if names.is_empty() {
+        app.install.progress = format!("{} 中没有包名", path.display());
+        return;
+    }
+
+    // pacman -Qq 放到后台执行，结果经 InstallListImported 回到主循环
+    app.install.progress = "正在检查已安装的包...".to_string();
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        let imported = tokio::task::spawn_blocking(move || {
+            let installed = pm.installed_names();
+            names.into_iter().partition(|n| installed.contains(n))
+        })
+        .await;
+        if let Ok((skipped, to_install)) = imported {
+            let _ = tx_clone
+                .send(AppEvent::InstallListImported { path, to_install, skipped })
+                .await;
+        }
+    });
+}
+
+/// 导入列表检查完成：用待安装的包替换搜索结果并进入安装预览
+pub fn apply_imported_list(
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    path: &std::path::Path,
+    to_install: Vec<String>,
+    skipped: Vec<String>,
+) {
+    // 检查期间已离开搜索界面则丢弃
+    if app.mode != AppMode::Install || app.install.phase != InstallPhase::Searching {
+        return;
+    }
+    if to_install.is_empty() {
+        app.install.progress = format!("列表中的 {} 个包均已安装", skipped.len());
+        return;
+    }
+
+    // 取消挂起的搜索，用导入列表替换结果
+    app.install.search_scheduled = None;
+    app.install.searching = false;
+    app.install.search_seq = app.install.search_seq.wrapping_add(1);
+    app.install.marked.clear();
+    app.install.set_results(to_install
+        .iter()
+        .map(|name| crate::package_manager::PackageInfo {
+            repo: "import".to_string(),
+            name: name.clone(),
+            version: String::new(),
+            description: format!("来自 {}", path.display()),
+            installed: false,
+        })
+        .collect());
+    app.install.marked = to_install.iter().cloned().collect();
+    app.install.selected = 0;
+    app.install.skipped = skipped;
+    app.install.progress.clear();
+    start_install_preview(app, tx, to_install);
+}
+
 /// 预览状态按键处理
 fn handle_preview_key(key: KeyEvent, app: &mut App) -> bool {
     match key.code {
         KeyCode::Esc => {
             app.install.phase = InstallPhase::Searching;
+            app.install.space_confirmed = false;
             app.install.preview.clear();
             app.install.scroll = 0;
             true
@@ -168,6 +370,10 @@ fn handle_preview_key(key: KeyEvent, app: &mut App) -> bool {
             app.install.scroll += 1;
             true
         }
+        KeyCode::Char('e') => {
+            app.install.as_explicit = !app.install.as_explicit;
+            true
+        }
         // Enter 在 mod.rs 中处理（需要 sudo）
         _ => false,
     }
@@ -175,12 +381,17 @@ fn handle_preview_key(key: KeyEvent, app: &mut App) -> bool {
 
 /// 输出状态按键处理（Installing/Complete/Analyzing/Error）
 fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
+    let content = app.install.get_content();
+    if app.install.search.handle_key(key, &content, &mut app.install.scroll) {
+        return true;
+    }
     match key.code {
         KeyCode::Esc => {
             match app.install.phase {
                 InstallPhase::Installing | InstallPhase::Analyzing => {
                     // 进行中：取消并返回搜索
                     crate::package_manager::cancel_update();
+                    app.install.search.clear();
                     app.install.phase = InstallPhase::Searching;
                     app.install.scroll = 0;
                 }
@@ -192,14 +403,16 @@ fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
             }
             true
         }
+        KeyCode::Char('f') if app.install.phase == InstallPhase::Installing => {
+            app.install.toggle_follow();
+            true
+        }
         KeyCode::Char('y') => {
-            let content = app.install.get_content();
-            let text = content.join("\n");
-            if layout::copy_to_clipboard(&text) {
-                app.install.progress = "✓ 已复制到剪贴板".to_string();
-            } else {
-                app.install.progress = "复制失败 (请确认已安装 wl-copy/xclip/xsel)".to_string();
-            }
+            let text = layout::plain_text(&app.install.get_content());
+            app.install.progress = match layout::copy_to_clipboard(&text) {
+                Ok(()) => "✓ 已复制到剪贴板".to_string(),
+                Err(e) => format!("复制失败: {}", e),
+            };
             true
         }
         KeyCode::Up => {
@@ -226,6 +439,16 @@ fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
             app.install.scroll = (app.install.scroll + 10).min(max_scroll);
             true
         }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.install.scroll = 0;
+            true
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            let content = app.install.get_content();
+            let visible = layout::visible_content_height(term_height);
+            app.install.scroll = content.len().saturating_sub(visible);
+            true
+        }
         _ => false,
     }
 }
@@ -233,12 +456,15 @@ fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
 /// 完成状态按键处理（可切换 Tab）
 fn handle_complete_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
     match key.code {
-        KeyCode::Tab => {
-            app.install.view_mode = match app.install.view_mode {
-                ViewMode::UpdateLog => ViewMode::AIAnalysis,
-                ViewMode::AIAnalysis => ViewMode::UpdateLog,
-            };
-            app.install.scroll = 0;
+        KeyCode::Tab if !app.install.search.is_editing() => {
+            app.install.toggle_view();
+            app.install.search.clear();
+            true
+        }
+        KeyCode::Char('r')
+            if app.install.view_mode == ViewMode::AIAnalysis && !app.install.search.is_editing() =>
+        {
+            app.markdown_raw = !app.markdown_raw;
             true
         }
         _ => handle_output_key(key, app, term_height),
@@ -246,18 +472,25 @@ fn handle_complete_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
 }
 
 /// 计划异步搜索（防抖）
-fn schedule_search(app: &mut App) {
+pub fn schedule_search(app: &mut App) {
     let keyword = app.install.input.clone();
+    app.install.progress.clear();
+    app.install.skipped.clear();
     if keyword.trim().is_empty() {
         app.install.results.clear();
+        app.install.unsorted.clear();
+        app.install.search_error = None;
         app.install.selected = 0;
-        app.install.marked.clear();
         app.install.searching = false;
         app.install.search_scheduled = None;
         app.install.search_seq = app.install.search_seq.wrapping_add(1);
         return;
     }
 
+    if keyword.contains('=') && parse_version_spec(&keyword).is_none() {
+        app.install.progress = "✗ 版本格式无效，应为 包名=版本，如 firefox=120.0".to_string();
+    }
+
     app.install.search_seq = app.install.search_seq.wrapping_add(1);
     app.install.searching = true;
     app.install.search_scheduled = Some(std::time::Instant::now());
@@ -269,33 +502,79 @@ pub fn execute_pending_search(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
     if keyword.trim().is_empty() {
         return;
     }
+    // name=version 只按包名搜索
+    let keyword = match parse_version_spec(&keyword) {
+        Some((name, _)) => name.to_string(),
+        None => keyword,
+    };
 
     if let Some(pm) = app.package_manager.clone() {
         let seq = app.install.search_seq;
+        let cancel = app.install.search_cancel.clone();
         let tx_clone = tx.clone();
         tokio::spawn(async move {
-            let results = tokio::task::spawn_blocking(move || pm.search_remote(&keyword))
+            let results = tokio::task::spawn_blocking(move || pm.search_remote(&keyword, &cancel))
                 .await
-                .unwrap_or_default();
+                .unwrap_or_else(|e| Err(e.into()));
             let _ = tx_clone.send(AppEvent::InstallSearchResults { results, seq }).await;
         });
     }
 }
 
-/// 收集选中的包名列表
-fn collect_selected_packages(app: &App) -> Vec<String> {
+/// 显示大小列或按大小排序时，后台批量获取尚未查询过的包大小
+///
+/// AUR 包不在同步数据库中，跳过。
+pub fn fetch_missing_sizes(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    if !app.install.show_sizes && app.install.sort != SortKey::Size {
+        return;
+    }
+    let Some(pm) = app.package_manager.clone() else {
+        return;
+    };
+    let names: Vec<String> = app.install.unsorted
+        .iter()
+        .filter(|p| p.repo != "aur" && !app.install.sizes_requested.contains(&p.name))
+        .map(|p| p.name.clone())
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    app.install.sizes_requested.extend(names.iter().cloned());
+
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        let sizes = tokio::task::spawn_blocking(move || pm.package_sizes(&names))
+            .await
+            .unwrap_or_default();
+        let _ = tx_clone.send(AppEvent::InstallSizesLoaded(sizes)).await;
+    });
+}
+
+/// 收集选中的包名列表，搜索框为 name=version 时对应的包带上固定版本
+pub fn collect_selected_packages(app: &App) -> Vec<String> {
+    let pinned = parse_version_spec(&app.install.input);
+    let spec = |pkg: &crate::package_manager::PackageInfo| match pinned {
+        Some((name, version)) if name == pkg.name => format!("{}={}", name, version),
+        _ => pkg.name.clone(),
+    };
+
     if app.install.marked.is_empty() {
         if let Some(pkg) = app.install.results.get(app.install.selected) {
-            vec![pkg.name.clone()]
+            vec![spec(pkg)]
         } else {
             Vec::new()
         }
     } else {
-        app.install.marked
+        // 标记的包可能来自之前的搜索，不一定在当前结果中
+        let mut names: Vec<String> = app.install.marked
             .iter()
-            .filter_map(|&idx| app.install.results.get(idx))
-            .map(|pkg| pkg.name.clone())
-            .collect()
+            .map(|name| match pinned {
+                Some((pinned_name, version)) if pinned_name == name => format!("{}={}", name, version),
+                _ => name.clone(),
+            })
+            .collect();
+        names.sort();
+        names
     }
 }
 
@@ -313,6 +592,8 @@ pub fn spawn_install_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
 
     let tx_clone = tx.clone();
     app.install.phase = InstallPhase::Installing;
+    app.install.search.clear();
+    let flags = app.install.install_flags();
     app.install.lines.clear();
     app.install.lines.push(format!(
         "正在安装: {} ...",
@@ -333,7 +614,7 @@ pub fn spawn_install_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
             }
         });
 
-        let result = pm.install_streaming(&packages, output_tx);
+        let result = pm.install_streaming(&packages, &flags, output_tx);
 
         match result {
             Ok(output) => {
@@ -361,30 +642,23 @@ pub fn handle_install_complete(
             let sys_info = app.system_info.clone();
             let packages = collect_selected_packages(app);
 
-            let prompt_text = format!(
-                "以下是在 {} 系统上使用 {} 安装软件包的日志。\n\
-                 安装的包: {}\n\n\
-                 安装日志:\n{}\n\n\
-                 请简要分析安装结果，说明是否成功，安装了哪些包及其依赖，是否有需要注意的问题。",
-                sys_info.as_ref().map(|i| i.distro.as_str()).unwrap_or("Linux"),
-                pm_name,
-                packages.join(", "),
-                install_log
+            let prompt_text = crate::prompt::generate_install_prompt(
+                &pm_name,
+                &packages,
+                &install_log,
+                sys_info.as_ref(),
+                &app.config.analysis_language,
             );
 
-            let client = crate::deepseek::AiClient::new(
-                api_key.to_string(),
-                app.config.get_api_url().to_string(),
-                app.config.proxy.as_deref(),
-            );
-            let model = app.config.model.clone();
+            let client = crate::deepseek::AiClient::from_config(api_key, &app.config);
+            let model = app.config.get_model().to_string();
             let temperature = app.config.temperature;
             let tx_clone = tx.clone();
 
             tokio::spawn(async move {
                 match client.analyze_update(&prompt_text, &model, temperature).await {
-                    Ok(analysis) => {
-                        let _ = tx_clone.send(AppEvent::InstallAnalysisComplete(analysis)).await;
+                    Ok((analysis, usage)) => {
+                        let _ = tx_clone.send(AppEvent::InstallAnalysisComplete { analysis, usage }).await;
                     }
                     Err(e) => {
                         let _ = tx_clone
@@ -397,6 +671,21 @@ pub fn handle_install_complete(
             let mut new_output = output.clone();
             new_output.stdout.push_str("\n\n[AI 分析已关闭，可在设置中开启]");
             app.install.output = Some(new_output);
+        } else if !output.success {
+            // 固定版本安装失败时，把 pacman 的错误行单独列出
+            let packages = collect_selected_packages(app);
+            if packages.iter().any(|p| parse_version_spec(p).is_some()) {
+                let errors: Vec<String> = output.combined_output()
+                    .lines()
+                    .filter(|l| l.starts_with("error:") || l.starts_with("错误："))
+                    .map(|l| l.to_string())
+                    .collect();
+                app.install.progress = if errors.is_empty() {
+                    "✗ 指定版本安装失败".to_string()
+                } else {
+                    format!("✗ 指定版本不可安装: {}", errors.join("; "))
+                };
+            }
         }
     }
 }
@@ -405,21 +694,24 @@ pub fn handle_install_complete(
 pub fn handle_install_analysis_complete(
     app: &mut App,
     analysis: String,
+    usage: Option<crate::deepseek::TokenUsage>,
     tx: &mpsc::Sender<AppEvent>,
 ) {
     app.install.analysis = Some(analysis.clone());
+    app.install.token_usage = usage;
     app.install.phase = InstallPhase::AnalysisComplete;
-    app.install.view_mode = ViewMode::AIAnalysis;
-    app.install.scroll = 0;
+    app.install.show_analysis();
 
-    let report_dir = app.config.report_dir.clone();
+    let saver = crate::report::ReportSaver::from_config(&app.config);
+    let log = app.install.output.as_ref()
+        .map(|o| o.combined_output())
+        .unwrap_or_default();
     let distro_name = app.system_info.as_ref()
         .map(|info| info.distro.clone())
         .unwrap_or_else(|| "Linux".to_string());
     let tx_clone = tx.clone();
     tokio::spawn(async move {
-        let saver = crate::report::ReportSaver::new(report_dir);
-        match saver.save(&analysis, &distro_name, "S") {
+        match saver.save(&analysis, &log, &distro_name, "S") {
             Ok(path) => {
                 let _ = tx_clone
                     .send(AppEvent::ReportSaved(path.display().to_string()))
@@ -438,6 +730,7 @@ pub fn handle_install_analysis_complete(
 pub fn render_install(f: &mut Frame, app: &App) {
     match app.install.phase {
         InstallPhase::Searching => render_search_view(f, app),
+        InstallPhase::ReviewingPkgbuild => render_review_view(f, app),
         InstallPhase::PreviewingInstall => render_preview_view(f, app),
         _ => render_output_view(f, app),
     }
@@ -448,7 +741,10 @@ fn render_search_view(f: &mut Frame, app: &App) {
     let chunks = layout::main_layout(f.area());
 
     // Header
-    layout::render_header(f, "📦 安装软件包 (-S)", chunks[0]);
+    match &app.install.repo_filter {
+        Some(repo) => layout::render_header(f, &tf("install.title.repo", &[repo]), chunks[0]),
+        None => layout::render_header(f, t("install.title"), chunks[0]),
+    }
 
     // Content: 搜索框 + 结果列表
     let content_block = Block::default()
@@ -491,20 +787,48 @@ fn render_search_view(f: &mut Frame, app: &App) {
     render_result_list(f, app, inner_chunks[2]);
 
     // Footer
-    let footer = if app.install.results.is_empty() {
-        "输入关键词搜索远程仓库包 | Esc 返回"
+    let footer = if app.install.results.is_empty() && app.install.marked.is_empty() {
+        t("install.footer.empty")
+    } else if app.install.results.is_empty() {
+        &tf("install.footer.marked_empty", &[&app.install.marked.len()])
     } else if app.install.marked.is_empty() {
-        "↑↓ 选择 | Space 多选 | Enter 安装选中 | Esc 返回"
+        &tf("install.footer.results", &[&app.install.sort.label()])
     } else {
-        "↑↓ 选择 | Space 多选/取消 | Enter 安装标记项 | Esc 返回"
+        &tf(
+            "install.footer.marked",
+            &[&app.install.marked.len(), &app.install.sort.label()],
+        )
     };
-    layout::render_footer(f, footer, chunks[2]);
+    if let Some(err) = &app.install.search_error {
+        layout::render_footer(f, &format!("✗ {} | {}", err, footer), chunks[2]);
+    } else if app.install.progress.is_empty() {
+        layout::render_footer(f, footer, chunks[2]);
+    } else {
+        layout::render_footer(f, &format!("{} | {}", app.install.progress, footer), chunks[2]);
+    }
+}
+
+/// 大小列文本，未开启或尚未获取时为空
+fn size_text(app: &App, pkg: &crate::package_manager::PackageInfo) -> String {
+    if !app.install.show_sizes {
+        return String::new();
+    }
+    match app.install.sizes.get(&pkg.name) {
+        Some(size) => format!(" [{} / 下载 {}]", size.installed, size.download),
+        None if pkg.repo == "aur" => String::new(),
+        None => " [...]".to_string(),
+    }
 }
 
 /// 渲染搜索结果列表
 fn render_result_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme();
     if app.install.results.is_empty() {
-        if !app.install.input.is_empty() && !app.install.searching {
+        if let Some(err) = &app.install.search_error {
+            let hint = Paragraph::new(format!("  ✗ 搜索失败: {}", err))
+                .style(Style::default().fg(Color::Red));
+            f.render_widget(hint, area);
+        } else if !app.install.input.is_empty() && !app.install.searching {
             let hint = Paragraph::new("  未找到匹配的包")
                 .style(Style::default().fg(Color::DarkGray));
             f.render_widget(hint, area);
@@ -529,32 +853,35 @@ fn render_result_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         .take(visible_height)
         .map(|(idx, pkg)| {
             let is_selected = idx == app.install.selected;
-            let is_marked = app.install.marked.contains(&idx);
+            let is_marked = app.install.is_marked(idx);
 
             let marker = if is_marked { "[✓] " } else { "    " };
             let cursor = if is_selected { ">" } else { " " };
             let installed_tag = if pkg.installed { " [已安装]" } else { "" };
+            let size_tag = size_text(app, pkg);
 
             if is_selected {
                 // 选中行：深色背景 + 多色加粗
-                let bg = Style::default().bg(SEL_BG);
+                let bg = Style::default().bg(theme.sel_bg);
                 Line::from(vec![
-                    Span::styled(format!("{}{}", cursor, marker), bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD)),
-                    Span::styled(format!("{}/", pkg.repo), bg.fg(PINK).add_modifier(Modifier::BOLD)),
-                    Span::styled(pkg.name.clone(), bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD)),
-                    Span::styled(format!(" {}", pkg.version), bg.fg(BLUE)),
-                    Span::styled(installed_tag.to_string(), bg.fg(DIM)),
-                    Span::styled(format!(" - {}", pkg.description), bg.fg(DESC_DIM)),
+                    Span::styled(format!("{}{}", cursor, marker), bg.fg(theme.text).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!("{}/", pkg.repo), bg.fg(theme.primary).add_modifier(Modifier::BOLD)),
+                    Span::styled(pkg.name.clone(), bg.fg(theme.text).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" {}", pkg.version), bg.fg(theme.secondary)),
+                    Span::styled(installed_tag.to_string(), bg.fg(theme.dim)),
+                    Span::styled(size_tag, bg.fg(theme.dim)),
+                    Span::styled(format!(" - {}", pkg.description), bg.fg(theme.desc_dim)),
                 ])
             } else if is_marked {
                 // 标记行：粉色标识
                 Line::from(vec![
-                    Span::styled(format!("{}{}", cursor, marker), Style::default().fg(PINK)),
-                    Span::styled(format!("{}/", pkg.repo), Style::default().fg(PINK)),
-                    Span::styled(pkg.name.clone(), Style::default().fg(PINK)),
+                    Span::styled(format!("{}{}", cursor, marker), Style::default().fg(theme.primary)),
+                    Span::styled(format!("{}/", pkg.repo), Style::default().fg(theme.primary)),
+                    Span::styled(pkg.name.clone(), Style::default().fg(theme.primary)),
                     Span::styled(format!(" {}", pkg.version), Style::default().fg(Color::White)),
-                    Span::styled(installed_tag.to_string(), Style::default().fg(DIM)),
-                    Span::styled(format!(" - {}", pkg.description), Style::default().fg(DIM)),
+                    Span::styled(installed_tag.to_string(), Style::default().fg(theme.dim)),
+                    Span::styled(size_tag, Style::default().fg(theme.dim)),
+                    Span::styled(format!(" - {}", pkg.description), Style::default().fg(theme.dim)),
                 ])
             } else if pkg.installed {
                 // 已安装：暗灰
@@ -564,17 +891,19 @@ fn render_result_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
                     Span::styled(pkg.name.clone(), Style::default().fg(Color::DarkGray)),
                     Span::styled(format!(" {}", pkg.version), Style::default().fg(Color::DarkGray)),
                     Span::styled(installed_tag.to_string(), Style::default().fg(Color::DarkGray)),
+                    Span::styled(size_tag, Style::default().fg(Color::DarkGray)),
                     Span::styled(format!(" - {}", pkg.description), Style::default().fg(Color::DarkGray)),
                 ])
             } else {
                 // 正常行
                 Line::from(vec![
                     Span::styled(format!("{}{}", cursor, marker), Style::default().fg(Color::White)),
-                    Span::styled(format!("{}/", pkg.repo), Style::default().fg(PINK)),
-                    Span::styled(pkg.name.clone(), Style::default().fg(BLUE)),
+                    Span::styled(format!("{}/", pkg.repo), Style::default().fg(theme.primary)),
+                    Span::styled(pkg.name.clone(), Style::default().fg(theme.secondary)),
                     Span::styled(format!(" {}", pkg.version), Style::default().fg(Color::White)),
-                    Span::styled(installed_tag.to_string(), Style::default().fg(DIM)),
-                    Span::styled(format!(" - {}", pkg.description), Style::default().fg(DIM)),
+                    Span::styled(installed_tag.to_string(), Style::default().fg(theme.dim)),
+                    Span::styled(size_tag, Style::default().fg(theme.dim)),
+                    Span::styled(format!(" - {}", pkg.description), Style::default().fg(theme.dim)),
                 ])
             }
         })
@@ -597,32 +926,79 @@ fn render_result_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     }
 }
 
+/// 渲染 PKGBUILD 审查视图
+fn render_review_view(f: &mut Frame, app: &App) {
+    let chunks = layout::main_layout(f.area());
+
+    let packages = collect_selected_packages(app);
+    layout::render_header(
+        f,
+        &tf("install.title.review", &[&packages.join(", ")]),
+        chunks[0],
+    );
+    layout::render_scrollable_content(
+        f,
+        "AUR 包由用户维护，构建前请检查脚本内容",
+        &app.install.pkgbuild,
+        app.install.scroll,
+        chunks[1],
+    );
+
+    let footer = if is_fetching_pkgbuild(app) {
+        t("install.footer.fetching_pkgbuild")
+    } else {
+        t("install.footer.review")
+    };
+    layout::render_footer(f, footer, chunks[2]);
+}
+
 /// 渲染安装预览视图
 fn render_preview_view(f: &mut Frame, app: &App) {
     let chunks = layout::main_layout(f.area());
 
     let packages = collect_selected_packages(app);
-    let header_text = format!(
-        "📦 安装预览 - {} 个包: {}",
-        packages.len(),
-        packages.join(", ")
-    );
+    let header_text = tf("install.title.preview", &[&packages.len(), &packages.join(", ")]);
     layout::render_header(f, &header_text, chunks[0]);
 
+    let mut preview = app.install.preview.clone();
+    if !app.install.skipped.is_empty() {
+        preview.push(format!("已安装，跳过 {} 个包:", app.install.skipped.len()));
+        for name in &app.install.skipped {
+            preview.push(format!("  {}", name));
+        }
+    }
     layout::render_scrollable_content(
         f,
         "将安装以下软件包",
-        &app.install.preview,
+        &preview,
         app.install.scroll,
         chunks[1],
     );
 
+    if app.install.has_space_warning() {
+        let footer = if app.install.space_confirmed {
+            t("install.footer.space_confirm")
+        } else {
+            t("install.footer.space_warning")
+        };
+        layout::render_danger_footer(f, footer, chunks[2]);
+        return;
+    }
+
     let footer = if app.install.preview.len() == 1
         && app.install.preview[0].contains("正在获取")
     {
-        "正在获取安装预览..."
+        t("install.footer.fetching_preview")
     } else {
-        "按 Enter 确认安装 | Esc 返回搜索 | ↑↓ 滚动"
+        &tf(
+            "install.footer.preview",
+            &[&["-S", "--noconfirm"]
+                .iter()
+                .map(|s| s.to_string())
+                .chain(app.install.install_flags())
+                .collect::<Vec<_>>()
+                .join(" ")],
+        )
     };
     layout::render_footer(f, footer, chunks[2]);
 }
@@ -633,16 +1009,16 @@ fn render_output_view(f: &mut Frame, app: &App) {
 
     // Header
     let title = match app.install.phase {
-        InstallPhase::Installing => "⚙️  正在安装...",
-        InstallPhase::InstallComplete => "✅ 安装完成",
-        InstallPhase::Analyzing => "🤖 AI 分析中...",
-        InstallPhase::AnalysisComplete => "✨ 分析完成",
-        InstallPhase::Error => "❌ 错误",
-        _ => "📦 安装",
+        InstallPhase::Installing => t("install.title.installing"),
+        InstallPhase::InstallComplete => t("install.title.complete"),
+        InstallPhase::Analyzing => t("common.analyzing"),
+        InstallPhase::AnalysisComplete => t("common.analysis_done"),
+        InstallPhase::Error => t("common.error"),
+        _ => t("install.title.default"),
     };
 
     let pm_info = if let Some(pm) = &app.package_manager {
-        format!(" | 包管理器: {}", pm.name())
+        tf("common.pm_info", &[&pm.name()])
     } else {
         String::new()
     };
@@ -660,57 +1036,88 @@ fn render_output_view(f: &mut Frame, app: &App) {
     // Content
     let content_title = if app.install.phase == InstallPhase::AnalysisComplete {
         match app.install.view_mode {
-            ViewMode::UpdateLog => "安装日志 [Tab 切换到 AI 分析]",
-            ViewMode::AIAnalysis => "AI 分析报告 [Tab 切换到安装日志]",
+            ViewMode::UpdateLog => t("install.view.log_tab"),
+            ViewMode::AIAnalysis => t("install.view.analysis"),
         }
     } else {
-        "安装日志"
+        t("install.view.log")
     };
 
     let content = app.install.get_content();
-    layout::render_scrollable_content(f, content_title, &content, app.install.scroll, chunks[1]);
+    layout::render_searchable_content(
+        f,
+        content_title,
+        &content,
+        app.install.scroll,
+        chunks[1],
+        Some(&app.install.search),
+        app.install.view_mode == ViewMode::AIAnalysis && !app.markdown_raw,
+    );
 
     // Footer
     let owned_text: String;
     let footer_text = match app.install.phase {
         InstallPhase::Installing => {
             let pt = app.install.progress_info.footer_text();
-            if pt.is_empty() {
-                "安装进行中..."
-            } else {
-                owned_text = pt;
-                &owned_text
-            }
+            let pt = if pt.is_empty() { t("install.footer.running").to_string() } else { pt };
+            owned_text = format!("{} | {}", pt, follow_label(app.install.follow));
+            &owned_text
         }
         InstallPhase::InstallComplete => {
-            if !app.install.progress.is_empty() && (app.install.progress.starts_with('✓') || app.install.progress.starts_with("复制")) {
-                owned_text = format!("{} | y 复制 | ↑↓ 滚动 | Esc 返回主页", app.install.progress);
+            if !app.install.progress.is_empty() && (app.install.progress.starts_with(['✓', '✗']) || app.install.progress.starts_with("复制")) {
+                owned_text = format!("{} | {}", app.install.progress, t("output.footer.done_keys"));
                 &owned_text
             } else {
-                "安装完成 | y 复制 | ↑↓ 滚动 | Esc 返回主页"
+                t("install.footer.done")
             }
         }
-        InstallPhase::Analyzing => "AI 正在分析安装内容...",
+        InstallPhase::Analyzing => t("install.footer.analyzing"),
         InstallPhase::AnalysisComplete => {
             if !app.install.progress.is_empty() && (app.install.progress.starts_with('✓') || app.install.progress.starts_with("复制")) {
-                owned_text = format!("{} | Tab 切换视图 | y 复制 | Esc 返回主页", app.install.progress);
+                owned_text = format!("{} | {}", app.install.progress, t("output.footer.analysis_keys"));
                 &owned_text
             } else if let Some(path) = &app.install.report_path {
-                owned_text = format!("报告已保存: {} | Tab 切换视图 | y 复制 | Esc 返回主页", path);
+                owned_text = format!("{} | {}", tf("common.report_saved", &[path]), t("output.footer.analysis_keys"));
                 &owned_text
             } else {
-                "Tab 切换视图 | y 复制 | ↑↓ 滚动 | Esc 返回主页"
+                t("output.footer.analysis")
             }
         }
         InstallPhase::Error => {
             if let Some(msg) = &app.error_message {
                 msg
             } else {
-                "发生错误 | Esc 返回主页"
+                t("common.error_back")
             }
         }
-        _ => "Esc 返回",
+        _ => t("common.back"),
+    };
+    let with_usage: String;
+    let footer_text = match (&app.install.phase, &app.install.token_usage) {
+        (InstallPhase::AnalysisComplete, Some(usage)) => {
+            with_usage = format!("{} | {}", usage.footer_text(app.config.token_price), footer_text);
+            &with_usage
+        }
+        _ => footer_text,
+    };
+
+    // 搜索输入中只显示输入框；已有查询时作为前缀显示匹配进度
+    let with_search: String;
+    let footer_text = match app.install.search.footer_text() {
+        Some(search) if app.install.search.is_editing() => {
+            with_search = search;
+            &with_search
+        }
+        Some(search) => {
+            with_search = format!("{} | {}", search, footer_text);
+            &with_search
+        }
+        None => footer_text,
     };
 
-    layout::render_footer(f, footer_text, chunks[2]);
+    let percent = match app.install.phase {
+        InstallPhase::Installing if !app.install.search.is_editing() => app.install.progress_info.percent,
+        _ => None,
+    };
+    layout::render_progress_footer(f, footer_text, percent, chunks[2]);
 }
@@ -1,11 +1,39 @@
+use super::search::LogSearch;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
 
+/// 正常渲染所需的最小终端尺寸
+pub const MIN_WIDTH: u16 = 50;
+pub const MIN_HEIGHT: u16 = 15;
+
+/// 终端是否小于最小尺寸（此时只显示提示）
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+/// 终端过小时的居中提示
+pub fn render_too_small(f: &mut Frame) {
+    let area = f.area();
+    let lines = vec![
+        Line::from(Span::styled(
+            "终端太小",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("当前 {}×{}，至少需要 {}×{}", area.width, area.height, MIN_WIDTH, MIN_HEIGHT),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let height = (lines.len() as u16).min(area.height);
+    let rect = Rect::new(area.x, area.y + (area.height - height) / 2, area.width, height);
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), rect);
+}
+
 /// 标准三段式布局：Header(3) + Content(弹性) + Footer(3)
 pub fn main_layout(area: Rect) -> Vec<Rect> {
     Layout::default()
@@ -37,6 +65,82 @@ pub fn render_footer(f: &mut Frame, text: &str, area: Rect) {
     f.render_widget(footer, area);
 }
 
+/// 带进度条的 footer：有百分比时渲染 Gauge（文本作为标签），否则退回纯文本 footer
+pub fn render_progress_footer(f: &mut Frame, text: &str, percent: Option<u8>, area: Rect) {
+    let Some(percent) = percent else {
+        render_footer(f, text, area);
+        return;
+    };
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL))
+        .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
+        .percent(u16::from(percent.min(100)))
+        .label(Span::styled(
+            text.to_string(),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ))
+        .use_unicode(true);
+    f.render_widget(gauge, area);
+}
+
+/// 渲染红色警告 footer（需二次确认的危险操作）
+pub fn render_danger_footer(f: &mut Frame, text: &str, area: Rect) {
+    let footer = Paragraph::new(format!(" {}", text))
+        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL))
+        .alignment(Alignment::Left);
+    f.render_widget(footer, area);
+}
+
+/// 居中的确认弹窗（红色边框）：第一行为提示，第三行为操作说明
+pub fn render_confirm_dialog(f: &mut Frame, text: &str, hint: &str) {
+    let area = f.area();
+    let width = (area.width * 6 / 10).max(20).min(area.width);
+    let height = 5.min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, rect);
+    let dialog = Paragraph::new(vec![
+        Line::from(Span::styled(
+            text.to_string(),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(hint.to_string(), Style::default().fg(Color::DarkGray))),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+    f.render_widget(dialog, rect);
+}
+
+/// area 中按百分比居中的区域
+pub fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
 /// 渲染带滚动条的内容区域
 pub fn render_scrollable_content(
     f: &mut Frame,
@@ -44,6 +148,19 @@ pub fn render_scrollable_content(
     lines: &[String],
     scroll_offset: usize,
     area: Rect,
+) {
+    render_searchable_content(f, title, lines, scroll_offset, area, None, false);
+}
+
+/// 同 render_scrollable_content，并按 search 高亮匹配行；markdown 为 true 时按 markdown 渲染
+pub fn render_searchable_content(
+    f: &mut Frame,
+    title: &str,
+    lines: &[String],
+    scroll_offset: usize,
+    area: Rect,
+    search: Option<&LogSearch>,
+    markdown: bool,
 ) {
     let block = Block::default()
         .title(format!(" {} ", title))
@@ -64,24 +181,21 @@ pub fn render_scrollable_content(
     let max_scroll = total_lines.saturating_sub(visible_height);
     let actual_scroll = scroll_offset.min(max_scroll);
 
+    // markdown 含跨行状态（代码块），整体渲染后再按行号取可见部分
+    let markdown_lines = markdown.then(|| super::markdown::render_lines(lines));
     let visible_content: Vec<Line> = lines
         .iter()
+        .enumerate()
         .skip(actual_scroll)
         .take(visible_height)
-        .map(|line| {
-            if let Some(content) = line.strip_prefix("PROGRESS_LINE:") {
-                // 下载型进度行（含 iB）用青色 ⬇，其他进度行（paru AUR 等）用黄色 ⟳
-                let (icon, color) = if content.contains("iB") {
-                    ("⬇ ", Color::Cyan)
-                } else {
-                    ("⟳ ", Color::Yellow)
-                };
-                Line::from(vec![
-                    Span::styled(icon, Style::default().fg(color)),
-                    Span::styled(content.to_string(), Style::default().fg(color)),
-                ])
-            } else {
-                Line::from(line.clone())
+        .map(|(idx, line)| {
+            let rendered = match &markdown_lines {
+                Some(md) => md[idx].clone(),
+                None => style_line(line),
+            };
+            match search.and_then(|s| s.highlight(idx)) {
+                Some(style) => rendered.patch_style(style),
+                None => rendered,
             }
         })
         .collect();
@@ -110,36 +224,129 @@ pub fn render_scrollable_content(
     }
 }
 
+/// 按行首标记（PROGRESS_LINE: / WARN: / DANGER: / DIFF_*:）着色的单行
+fn style_line(line: &str) -> Line<'static> {
+    if let Some(content) = line.strip_prefix("PROGRESS_LINE:") {
+        // 下载型进度行（含 iB）用青色 ⬇，其他进度行（paru AUR 等）用黄色 ⟳
+        let (icon, color) = if content.contains("iB") {
+            ("⬇ ", Color::Cyan)
+        } else {
+            ("⟳ ", Color::Yellow)
+        };
+        Line::from(vec![
+            Span::styled(icon, Style::default().fg(color)),
+            Span::styled(content.to_string(), Style::default().fg(color)),
+        ])
+    } else if let Some(content) = line.strip_prefix("WARN:") {
+        Line::from(Span::styled(
+            content.to_string(),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))
+    } else if let Some(content) = line.strip_prefix("DANGER:") {
+        Line::from(Span::styled(
+            content.to_string(),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ))
+    } else if let Some(content) = line.strip_prefix("DIFF_ADD:") {
+        Line::from(Span::styled(content.to_string(), Style::default().fg(Color::Green)))
+    } else if let Some(content) = line.strip_prefix("DIFF_DEL:") {
+        Line::from(Span::styled(content.to_string(), Style::default().fg(Color::Red)))
+    } else if let Some(content) = line.strip_prefix("DIFF_CHG:") {
+        Line::from(Span::styled(content.to_string(), Style::default().fg(Color::DarkGray)))
+    } else if let Some(content) = line.strip_prefix("DIM:") {
+        Line::from(Span::styled(content.to_string(), Style::default().fg(Color::DarkGray)))
+    } else {
+        Line::from(line.to_string())
+    }
+}
+
+/// [`style_line`] 识别的行首样式标记
+const STYLE_PREFIXES: &[&str] = &["PROGRESS_LINE:", "WARN:", "DANGER:", "DIFF_ADD:", "DIFF_DEL:", "DIFF_CHG:", "DIM:"];
+
+/// 去掉样式标记后拼接为纯文本（复制到剪贴板用）
+pub fn plain_text(lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            STYLE_PREFIXES
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+                .unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// 估算内容区域可见行数（总高度减去 header/footer/borders）
 pub fn visible_content_height(term_height: u16) -> usize {
     term_height.saturating_sub(8) as usize
 }
 
 /// 将文本复制到系统剪贴板。
-/// 优先尝试 wl-copy（Wayland），然后 xclip，最后 xsel。
-/// 返回 true 表示成功，false 表示找不到可用工具。
-pub fn copy_to_clipboard(text: &str) -> bool {
-    let candidates: &[(&str, &[&str])] = &[
-        ("wl-copy", &[]),
-        ("xclip", &["-selection", "clipboard"]),
-        ("xsel", &["--clipboard", "--input"]),
-    ];
+/// Wayland 会话使用 wl-copy，X11 会话依次尝试 xclip、xsel。
+/// 没有图形会话或找不到可用工具时返回错误说明。
+pub fn copy_to_clipboard(text: &str) -> anyhow::Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+    if !wayland && !x11 {
+        anyhow::bail!("没有图形会话 (未设置 WAYLAND_DISPLAY/DISPLAY)");
+    }
+
+    let mut candidates: Vec<(&str, &[&str])> = Vec::new();
+    if wayland {
+        candidates.push(("wl-copy", &[]));
+    }
+    if x11 {
+        candidates.push(("xclip", &["-selection", "clipboard"]));
+        candidates.push(("xsel", &["--clipboard", "--input"]));
+    }
     for (cmd, args) in candidates {
         if let Ok(mut child) = std::process::Command::new(cmd)
-            .args(*args)
+            .args(args)
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
             .spawn()
         {
             use std::io::Write;
-            if let Some(stdin) = child.stdin.as_mut() {
+            if let Some(mut stdin) = child.stdin.take() {
                 let _ = stdin.write_all(text.as_bytes());
             }
             if child.wait().map(|s| s.success()).unwrap_or(false) {
-                return true;
+                return Ok(());
+            }
+        }
+    }
+    anyhow::bail!("请确认已安装 wl-copy/xclip/xsel")
+}
+
+/// 读取系统剪贴板文本（优先 wl-paste，其次 xclip / xsel）
+pub fn paste_from_clipboard() -> anyhow::Result<String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+    if !wayland && !x11 {
+        anyhow::bail!("没有图形会话 (未设置 WAYLAND_DISPLAY/DISPLAY)");
+    }
+
+    let mut candidates: Vec<(&str, &[&str])> = Vec::new();
+    if wayland {
+        candidates.push(("wl-paste", &["--no-newline"]));
+    }
+    if x11 {
+        candidates.push(("xclip", &["-selection", "clipboard", "-o"]));
+        candidates.push(("xsel", &["--clipboard", "--output"]));
+    }
+    for (cmd, args) in candidates {
+        if let Ok(output) = std::process::Command::new(cmd)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output()
+        {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).to_string());
             }
         }
     }
-    false
+    anyhow::bail!("请确认已安装 wl-paste/xclip/xsel")
 }
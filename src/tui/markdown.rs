@@ -0,0 +1,138 @@
+//! AI 分析报告的轻量 markdown 渲染：标题、列表、引用、分隔线、代码块与行内 **粗体** / *斜体* / `代码`。
+//! 逐行转换且行数不变，滚动、搜索与原文共用同一套行号。
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// 代码块 / 行内代码的样式
+fn code_style() -> Style {
+    Style::default().fg(Color::LightGreen).bg(Color::Rgb(40, 40, 40))
+}
+
+/// 将 markdown 文本逐行渲染为带样式的 Line
+pub fn render_lines(lines: &[String]) -> Vec<Line<'static>> {
+    let mut in_code = false;
+    lines
+        .iter()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if let Some(lang) = trimmed.strip_prefix("```") {
+                in_code = !in_code;
+                // 围栏行显示为分隔线，开头的围栏附带语言名
+                let label = if in_code && !lang.trim().is_empty() {
+                    format!("──── {} ────", lang.trim())
+                } else {
+                    "────".to_string()
+                };
+                return Line::from(Span::styled(label, Style::default().fg(Color::DarkGray)));
+            }
+            if in_code {
+                return Line::from(Span::styled(line.clone(), code_style()));
+            }
+            render_block_line(line)
+        })
+        .collect()
+}
+
+/// 非代码块中的一行：先识别行首结构，再解析行内样式
+fn render_block_line(line: &str) -> Line<'static> {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indent_len);
+
+    // 标题
+    let hashes = rest.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) && rest[hashes..].starts_with(' ') {
+        let style = match hashes {
+            1 => Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            2 => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            _ => Style::default().add_modifier(Modifier::BOLD),
+        };
+        return Line::from(inline_spans(rest[hashes..].trim(), style));
+    }
+
+    // 分隔线
+    let mut marks = rest.chars().filter(|c| !c.is_whitespace());
+    let first = marks.next();
+    let rule_len = rest.chars().filter(|c| !c.is_whitespace()).count();
+    if matches!(first, Some('-' | '*' | '_')) && rule_len >= 3 && marks.all(|c| Some(c) == first) {
+        return Line::from(Span::styled("─".repeat(40), Style::default().fg(Color::DarkGray)));
+    }
+
+    // 引用
+    if let Some(quote) = rest.strip_prefix('>') {
+        let style = Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC);
+        let mut spans = vec![Span::styled(format!("{}│ ", indent), Style::default().fg(Color::DarkGray))];
+        spans.extend(inline_spans(quote.trim_start(), style));
+        return Line::from(spans);
+    }
+
+    // 无序列表
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = rest.strip_prefix(marker) {
+            let mut spans = vec![Span::styled(format!("{}• ", indent), Style::default().fg(Color::Cyan))];
+            spans.extend(inline_spans(item, Style::default()));
+            return Line::from(spans);
+        }
+    }
+
+    // 有序列表：序号着色
+    let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && rest[digits..].starts_with(". ") {
+        let mut spans = vec![Span::styled(
+            format!("{}{} ", indent, &rest[..digits + 1]),
+            Style::default().fg(Color::Cyan),
+        )];
+        spans.extend(inline_spans(&rest[digits + 2..], Style::default()));
+        return Line::from(spans);
+    }
+
+    let mut spans = vec![Span::raw(indent.to_string())];
+    spans.extend(inline_spans(rest, Style::default()));
+    Line::from(spans)
+}
+
+/// 解析行内 **粗体**、*斜体* / _斜体_、`代码`；未闭合的标记按原文输出
+fn inline_spans(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let (marker, style) = if rest.starts_with("**") || rest.starts_with("__") {
+            (&rest[..2], base.add_modifier(Modifier::BOLD))
+        } else if c == '`' {
+            ("`", code_style())
+        } else if c == '*' || c == '_' {
+            (&rest[..1], base.add_modifier(Modifier::ITALIC))
+        } else {
+            plain.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+
+        let inner = &rest[marker.len()..];
+        // 下划线仅在词边界生效，避免 snake_case 被误识别为斜体
+        let word_boundary = !marker.starts_with('_') || plain.chars().last().is_none_or(|p| !p.is_alphanumeric());
+        match inner.find(marker).filter(|&end| end > 0 && word_boundary) {
+            Some(end) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut plain), base));
+                }
+                spans.push(Span::styled(inner[..end].to_string(), style));
+                rest = &inner[end + marker.len()..];
+            }
+            None => {
+                plain.push_str(marker);
+                rest = inner;
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
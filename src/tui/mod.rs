@@ -1,9 +1,16 @@
+mod completion;
 mod dashboard;
+mod help;
+mod i18n;
 pub mod input;
 mod install;
 mod layout;
+mod markdown;
+mod pacnew;
+mod plan;
 mod query;
 mod remove;
+mod search;
 mod settings;
 pub mod state;
 mod shell;
@@ -11,11 +18,14 @@ mod theme;
 mod update;
 
 use crate::config::Config;
-use crate::package_manager::PackageManager;
+use crate::package_manager::{self, PackageManager, SudoKeepalive};
 use crate::sysinfo::SystemInfo;
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyModifiers, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -28,10 +38,11 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
     // 终端初始化
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    i18n::set_language(&config.language);
     let mut app = App::new(config);
 
     let (tx, mut rx) = mpsc::channel(32);
@@ -62,6 +73,14 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
 
     // 主循环
     loop {
+        // 操作进行中保持 sudo 凭证有效，结束后停止刷新
+        if app.is_busy() {
+            if app.sudo_keepalive.is_none() {
+                app.sudo_keepalive = SudoKeepalive::start(app.config.sudo_keepalive_secs);
+            }
+        } else {
+            app.sudo_keepalive = None;
+        }
         // 更新模式下 clamp scroll
         if app.mode == AppMode::Update {
             let content = app.update.get_content();
@@ -97,6 +116,12 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                     let max_scroll = app.install.preview.len().saturating_sub(visible);
                     app.install.scroll = app.install.scroll.min(max_scroll);
                 }
+                state::InstallPhase::ReviewingPkgbuild => {
+                    let term_size = terminal.size()?;
+                    let visible = layout::visible_content_height(term_size.height);
+                    let max_scroll = app.install.pkgbuild.len().saturating_sub(visible);
+                    app.install.scroll = app.install.scroll.min(max_scroll);
+                }
                 _ => {}
             }
         }
@@ -160,41 +185,141 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
 
         // 处理事件
         if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+            let ev = event::read()?;
+            if let Event::Mouse(mouse) = ev {
+                let term_size = terminal.size()?;
+                handle_mouse_scroll(mouse, &mut app, &tx, &api_key, term_size.height);
+            }
+            if let Event::Paste(text) = &ev {
+                if app.is_text_input() && !app.show_help && !app.quit_confirm {
+                    paste_into_input(&mut app, text);
+                }
+            }
+            if let Event::Key(key) = ev {
                 let term_size = terminal.size()?;
 
                 // 全局按键
                 match key.code {
+                    // 退出确认中：Ctrl+C / y 确认退出，其他按键取消
+                    _ if app.quit_confirm => {
+                        let confirmed = key.code == KeyCode::Char('y')
+                            || (key.code == KeyCode::Char('c')
+                                && key.modifiers.contains(KeyModifiers::CONTROL));
+                        app.quit_confirm = false;
+                        if confirmed {
+                            crate::package_manager::cancel_update();
+                            app.should_quit = true;
+                        }
+                    }
+                    // 帮助浮层打开时：Esc / ? / F1 关闭，其余按键（Ctrl+C 除外）忽略
+                    KeyCode::Esc | KeyCode::Char('?') | KeyCode::F(1) if app.show_help => {
+                        app.show_help = false;
+                    }
+                    _ if app.show_help && !key.modifiers.contains(KeyModifiers::CONTROL) => {}
+                    KeyCode::F(1) => {
+                        app.show_help = true;
+                    }
+                    KeyCode::Char('?') if !app.is_text_input() => {
+                        app.show_help = true;
+                    }
                     // q 仅在 Dashboard 退出
                     KeyCode::Char('q') if app.mode == AppMode::Dashboard => {
                         app.should_quit = true;
                     }
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        if app.mode == AppMode::Update
-                            || app.mode == AppMode::Install
-                            || app.mode == AppMode::Remove
-                            || app.mode == AppMode::Shell
-                        {
-                            crate::package_manager::cancel_update();
+                        if app.is_busy() {
+                            // 事务进行中直接退出可能留下 db.lck 或半完成的事务，先确认
+                            app.quit_confirm = true;
+                        } else {
+                            app.should_quit = true;
+                        }
+                    }
+                    // Ctrl+V 从系统剪贴板粘贴到当前输入框（终端自身的粘贴走 Event::Paste）
+                    KeyCode::Char('v')
+                        if key.modifiers.contains(KeyModifiers::CONTROL) && app.is_text_input() =>
+                    {
+                        match layout::paste_from_clipboard() {
+                            Ok(text) => paste_into_input(&mut app, &text),
+                            Err(e) => log::warn!("读取剪贴板失败: {}", e),
                         }
-                        app.should_quit = true;
+                    }
+                    // o / c 仅在 Dashboard：进入孤立包清理 / 包缓存清理
+                    KeyCode::Char('o') if app.mode == AppMode::Dashboard => {
+                        if let Some(pm) = app.package_manager.clone() {
+                            app.mode = AppMode::Remove;
+                            app.reset_remove_state();
+                            app.remove.orphans = true;
+                            app.remove.loading = true;
+                            let tx_clone = tx.clone();
+                            tokio::spawn(async move {
+                                let packages = tokio::task::spawn_blocking(move || pm.get_orphan_packages())
+                                    .await
+                                    .unwrap_or_default();
+                                let _ = tx_clone.send(AppEvent::RemovePackagesLoaded(packages)).await;
+                            });
+                        }
+                    }
+                    // l 仅在 Dashboard：按占用空间列出最大的包，选中后走正常卸载流程
+                    KeyCode::Char('l') if app.mode == AppMode::Dashboard => {
+                        if let Some(pm) = app.package_manager.clone() {
+                            app.mode = AppMode::Remove;
+                            app.reset_remove_state();
+                            app.remove.largest = true;
+                            app.remove.sort = state::SortKey::Size;
+                            app.remove.loading = true;
+                            let tx_clone = tx.clone();
+                            tokio::spawn(async move {
+                                let packages = tokio::task::spawn_blocking(move || {
+                                    pm.largest_packages(dashboard::LARGEST_LIMIT)
+                                })
+                                .await
+                                .unwrap_or_default();
+                                let _ = tx_clone.send(AppEvent::RemovePackagesLoaded(packages)).await;
+                            });
+                        }
+                    }
+                    // p 仅在 Dashboard：扫描并合并 .pacnew / .pacsave
+                    KeyCode::Char('p') if app.mode == AppMode::Dashboard => {
+                        let files = crate::package_manager::find_pacnew_files();
+                        pacnew::open(&mut app, files, AppMode::Dashboard);
+                    }
+                    KeyCode::Char('c') if app.mode == AppMode::Dashboard => {
+                        app.mode = AppMode::Shell;
+                        app.reset_shell_state();
+                        completion::refresh_in_background();
+                        app.shell.input = crate::package_manager::cache_clean_command();
+                        app.shell.cursor = app.shell.input.chars().count();
                     }
                     KeyCode::Esc => {
                         match app.mode {
                             AppMode::Dashboard => {}
+                            AppMode::Update if app.update.chat_input.is_some() => {
+                                // 退出追问输入
+                                app.update.chat_input = None;
+                            }
+                            AppMode::Update if app.update.search.is_editing() => {
+                                // 退出搜索输入
+                                app.update.search.input = None;
+                            }
                             AppMode::Update => {
                                 // 按当前阶段决定是否需要取消子进程
                                 match app.update.phase {
-                                    UpdatePhase::Updating | UpdatePhase::Analyzing => {
+                                    UpdatePhase::Analyzing => {
+                                        // 中断流式分析，停留在当前页查看已生成的部分
+                                        update::cancel_analysis(&mut app);
+                                    }
+                                    UpdatePhase::Updating => {
                                         // 正在运行中：发送取消信号，后表1秒内进程组将自行退出
                                         crate::package_manager::cancel_update();
+                                        app.mode = AppMode::Dashboard;
+                                        app.update.reset_scroll();
                                     }
                                     _ => {
                                         // 无运行中的子进程，直接返回，不错误地置位 SHOULD_CANCEL
+                                        app.mode = AppMode::Dashboard;
+                                        app.update.reset_scroll();
                                     }
                                 }
-                                app.mode = AppMode::Dashboard;
-                                app.update.reset_scroll();
                             }
                             AppMode::Query => {
                                 query::handle_query_key(
@@ -224,6 +349,8 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                                 settings::handle_settings_key(
                                     crossterm::event::KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
                                     &mut app,
+                                    &tx,
+                                    &api_key,
                                 );
                             }
                             AppMode::Shell => {
@@ -234,21 +361,40 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                                     term_size.height,
                                 );
                             }
+                            AppMode::Pacnew => {
+                                pacnew::handle_pacnew_key(
+                                    crossterm::event::KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+                                    &mut app,
+                                    term_size.height,
+                                );
+                            }
+                            AppMode::Plan => {
+                                plan::handle_plan_key(
+                                    crossterm::event::KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+                                    &mut app,
+                                    &tx,
+                                    term_size.height,
+                                );
+                            }
                         }
                     }
                     // 模式切换快捷键 (Shift + 字母)
                     // 当处于文本输入状态时（Shell Input、Install 搜索、Remove 浏览、Query）不触发
-                    KeyCode::Char('U' | 'S' | 'R' | 'Q' | 'C' | 'X')
+                    KeyCode::Char('U' | 'S' | 'R' | 'Q' | 'C' | 'X' | 'P')
                         if matches!(
                             app.mode,
                             AppMode::Shell
                                 | AppMode::Install
                                 | AppMode::Remove
                                 | AppMode::Query
-                        ) =>
+                        ) || (app.mode == AppMode::Update
+                            && (app.update.chat_input.is_some() || app.update.search.is_editing())) =>
                     {
                         // 转发给当前模式处理（作为普通字符输入）
                         match app.mode {
+                            AppMode::Update => {
+                                update::handle_update_key(key, &mut app, &tx, &api_key, term_size.height);
+                            }
                             AppMode::Shell => {
                                 shell::handle_shell_key(key, &mut app, &tx, term_size.height);
                             }
@@ -268,16 +414,13 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                         if app.mode != AppMode::Update {
                             app.mode = AppMode::Update;
                             app.reset_update_state();
-                            // 如果 PM 已检测到，直接检查可用更新
-                            if let Some(pm) = app.package_manager.clone() {
+                            update::spawn_news_task(&app, &tx);
+                            // 优先复用未过期的检查结果；否则等待进行中的检查或重新检查
+                            if let Some(cached) = update::cached_updates(&app) {
+                                update::apply_update_preview(&mut app, Ok(cached));
+                            } else if app.package_manager.is_some() {
                                 app.update.lines.push("正在检查可用更新...".to_string());
-                                let tx_clone = tx.clone();
-                                tokio::spawn(async move {
-                                    let updates = tokio::task::spawn_blocking(move || pm.check_updates())
-                                        .await
-                                        .unwrap_or_default();
-                                    let _ = tx_clone.send(AppEvent::UpdatePreviewReady(updates)).await;
-                                });
+                                update::spawn_update_check(&mut app, &tx);
                             }
                         }
                     }
@@ -320,31 +463,52 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                         if app.mode != AppMode::Shell {
                             app.mode = AppMode::Shell;
                             app.reset_shell_state();
+                            completion::refresh_in_background();
+                        }
+                    }
+                    KeyCode::Char('P') => {
+                        if app.mode != AppMode::Plan {
+                            plan::open(&mut app);
                         }
                     }
                     // 委托给当前模式处理
                     _ => {
                         match app.mode {
                             AppMode::Update => {
-                                if key.code == KeyCode::Enter && app.update.phase == UpdatePhase::PreviewingUpdates {
+                                // 签名校验失败后按 k：先更新密钥环再重新完整更新
+                                let keyring_retry = key.code == KeyCode::Char('k')
+                                    && app.update.phase == UpdatePhase::UpdateComplete
+                                    && app.update.has_signature_error();
+                                // 出错或更新失败后按 r：保留之前的日志重新执行
+                                let retry = key.code == KeyCode::Char('r') && update::can_retry(&app);
+                                let start = key.code == KeyCode::Enter
+                                    && app.update.phase == UpdatePhase::PreviewingUpdates;
+                                if (start || keyring_retry || retry) && !app.update.search.is_editing() {
+                                    if keyring_retry {
+                                        app.update.keyring_first = true;
+                                        app.update.marked.clear();
+                                    }
                                     // Enter：sudo 鉴权 + 开始更新
-                                    if !app.update.preview.is_empty() {
+                                    if !app.update.preview.is_empty() || keyring_retry || retry {
                                         match validate_sudo_tui(&mut terminal) {
-                                            Ok(true) => {
+                                            Ok(true) if start => {
                                                 update::spawn_update_task(&mut app, &tx);
                                             }
+                                            Ok(true) => {
+                                                update::retry_update_task(&mut app, &tx);
+                                            }
                                             Ok(false) => {
-                                                app.error_message = Some("sudo 验证失败，请确保你有 sudo 权限".to_string());
+                                                app.error_message = Some("权限验证失败，请确保你有管理员权限".to_string());
                                                 app.update.phase = UpdatePhase::Error;
                                             }
                                             Err(e) => {
-                                                app.error_message = Some(format!("sudo 验证出错: {}", e));
+                                                app.error_message = Some(format!("权限验证出错: {}", e));
                                                 app.update.phase = UpdatePhase::Error;
                                             }
                                         }
                                     }
                                 } else {
-                                    update::handle_update_key(key, &mut app, term_size.height);
+                                    update::handle_update_key(key, &mut app, &tx, &api_key, term_size.height);
                                 }
                             }
                             AppMode::Install => {
@@ -352,18 +516,23 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                                     && app.install.phase == state::InstallPhase::PreviewingInstall
                                     && app.install.preview.len() > 1
                                 {
-                                    // Enter in preview: sudo → install
-                                    match validate_sudo_tui(&mut terminal) {
-                                        Ok(true) => {
-                                            install::spawn_install_task(&mut app, &tx);
-                                        }
-                                        Ok(false) => {
-                                            app.error_message = Some("sudo 验证失败，请确保你有 sudo 权限".to_string());
-                                            app.install.phase = state::InstallPhase::Error;
-                                        }
-                                        Err(e) => {
-                                            app.error_message = Some(format!("sudo 验证出错: {}", e));
-                                            app.install.phase = state::InstallPhase::Error;
+                                    if app.install.has_space_warning() && !app.install.space_confirmed {
+                                        // 空间不足：第一次 Enter 仅确认，第二次才执行
+                                        app.install.space_confirmed = true;
+                                    } else {
+                                        // Enter in preview: sudo → install
+                                        match validate_sudo_tui(&mut terminal) {
+                                            Ok(true) => {
+                                                install::spawn_install_task(&mut app, &tx);
+                                            }
+                                            Ok(false) => {
+                                                app.error_message = Some("权限验证失败，请确保你有管理员权限".to_string());
+                                                app.install.phase = state::InstallPhase::Error;
+                                            }
+                                            Err(e) => {
+                                                app.error_message = Some(format!("权限验证出错: {}", e));
+                                                app.install.phase = state::InstallPhase::Error;
+                                            }
                                         }
                                     }
                                 } else {
@@ -375,18 +544,23 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                                     && app.remove.phase == state::RemovePhase::PreviewingRemove
                                     && app.remove.preview.len() > 1
                                 {
-                                    // Enter in preview: sudo → remove
-                                    match validate_sudo_tui(&mut terminal) {
-                                        Ok(true) => {
-                                            remove::spawn_remove_task(&mut app, &tx);
-                                        }
-                                        Ok(false) => {
-                                            app.error_message = Some("sudo 验证失败，请确保你有 sudo 权限".to_string());
-                                            app.remove.phase = state::RemovePhase::Error;
-                                        }
-                                        Err(e) => {
-                                            app.error_message = Some(format!("sudo 验证出错: {}", e));
-                                            app.remove.phase = state::RemovePhase::Error;
+                                    if app.remove.has_protected() && !app.remove.protected_confirmed {
+                                        // 含受保护包：第一次 Enter 仅确认，第二次才执行
+                                        app.remove.protected_confirmed = true;
+                                    } else {
+                                        // Enter in preview: sudo → remove
+                                        match validate_sudo_tui(&mut terminal) {
+                                            Ok(true) => {
+                                                remove::spawn_remove_task(&mut app, &tx);
+                                            }
+                                            Ok(false) => {
+                                                app.error_message = Some("权限验证失败，请确保你有管理员权限".to_string());
+                                                app.remove.phase = state::RemovePhase::Error;
+                                            }
+                                            Err(e) => {
+                                                app.error_message = Some(format!("权限验证出错: {}", e));
+                                                app.remove.phase = state::RemovePhase::Error;
+                                            }
                                         }
                                     }
                                 } else {
@@ -397,10 +571,90 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                                 query::handle_query_key(key, &mut app, &tx, term_size.height);
                             }
                             AppMode::Settings => {
-                                settings::handle_settings_key(key, &mut app);
+                                settings::handle_settings_key(key, &mut app, &tx, &api_key);
                             }
                             AppMode::Shell => {
-                                shell::handle_shell_key(key, &mut app, &tx, term_size.height);
+                                if key.code == KeyCode::Enter
+                                    && app.shell.phase == state::ShellPhase::PickingPackage
+                                {
+                                    // Enter in picker: 校验文件 → sudo → pacman -U
+                                    match shell::selected_local_package(&app) {
+                                        Some(path) => match validate_sudo_tui(&mut terminal) {
+                                            Ok(true) => {
+                                                shell::spawn_local_install(&mut app, &tx, path);
+                                            }
+                                            Ok(false) => {
+                                                app.shell.progress = "✗ 权限验证失败，请确保你有管理员权限".to_string();
+                                            }
+                                            Err(e) => {
+                                                app.shell.progress = format!("✗ 权限验证出错: {}", e);
+                                            }
+                                        },
+                                        None if app.shell.local_packages.is_empty() => {}
+                                        None => {
+                                            app.shell.progress = "✗ 文件已不存在".to_string();
+                                        }
+                                    }
+                                } else if key.code == KeyCode::Enter
+                                    && app.shell.phase == state::ShellPhase::Input
+                                    && app.shell.input.trim_start().starts_with(&format!("{} ", package_manager::privilege::name()))
+                                {
+                                    // sudo 等提权命令：先在 TUI 外完成鉴权，避免子进程等待密码输入
+                                    match validate_sudo_tui(&mut terminal) {
+                                        Ok(true) => {
+                                            shell::handle_shell_key(key, &mut app, &tx, term_size.height);
+                                        }
+                                        Ok(false) => {
+                                            app.shell.progress = "✗ 权限验证失败，请确保你有管理员权限".to_string();
+                                        }
+                                        Err(e) => {
+                                            app.shell.progress = format!("✗ 权限验证出错: {}", e);
+                                        }
+                                    }
+                                } else {
+                                    shell::handle_shell_key(key, &mut app, &tx, term_size.height);
+                                }
+                            }
+                            AppMode::Plan => {
+                                if key.code == KeyCode::Enter
+                                    && app.plan.phase == state::PlanPhase::Previewing
+                                    && !app.plan.fetching
+                                {
+                                    if app.plan.has_danger() && !app.plan.danger_confirmed {
+                                        // 含受保护包或空间不足：第一次 Enter 仅确认，第二次才执行
+                                        app.plan.danger_confirmed = true;
+                                    } else {
+                                        match validate_sudo_tui(&mut terminal) {
+                                            Ok(true) => plan::spawn_plan_task(&mut app, &tx),
+                                            Ok(false) => {
+                                                app.plan.message = Some("✗ 权限验证失败，请确保你有管理员权限".to_string());
+                                            }
+                                            Err(e) => {
+                                                app.plan.message = Some(format!("✗ 权限验证出错: {}", e));
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    plan::handle_plan_key(key, &mut app, &tx, term_size.height);
+                                }
+                            }
+                            AppMode::Pacnew => {
+                                if key.code == KeyCode::Char('y') && app.pacnew.pending.is_some() {
+                                    // 覆盖 / 删除 /etc 下的文件需要 root
+                                    match validate_sudo_tui(&mut terminal) {
+                                        Ok(true) => pacnew::apply_pending(&mut app),
+                                        Ok(false) => {
+                                            app.pacnew.pending = None;
+                                            app.pacnew.message = Some("✗ 权限验证失败".to_string());
+                                        }
+                                        Err(e) => {
+                                            app.pacnew.pending = None;
+                                            app.pacnew.message = Some(format!("✗ 权限验证出错: {}", e));
+                                        }
+                                    }
+                                } else {
+                                    pacnew::handle_pacnew_key(key, &mut app, term_size.height);
+                                }
                             }
                             AppMode::Dashboard => {}
                         }
@@ -414,21 +668,14 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
             match event {
                 AppEvent::PackageManagerDetected(pm) => {
                     app.package_manager = Some(pm);
-                    // 如果当前在更新模式且还在检测状态，自动检查更新
+                    // 后台检查可用更新；当前在更新模式且还在检测状态时，结果直接用于预览
                     if app.mode == AppMode::Update
                         && app.update.phase == UpdatePhase::PackageManagerCheck
                     {
-                        if let Some(pm) = app.package_manager.clone() {
-                            app.update.lines.push("正在检查可用更新...".to_string());
-                            let tx_clone = tx.clone();
-                            tokio::spawn(async move {
-                                let updates = tokio::task::spawn_blocking(move || pm.check_updates())
-                                    .await
-                                    .unwrap_or_default();
-                                let _ = tx_clone.send(AppEvent::UpdatePreviewReady(updates)).await;
-                            });
-                        }
+                        app.update.lines.push("正在检查可用更新...".to_string());
                     }
+                    update::spawn_update_check(&mut app, &tx);
+                    dashboard::spawn_maintenance_stats(&app, &tx);
                     // 检测到 PM 后，获取已安装包数量
                     if let Some(pm) = &app.package_manager {
                         let count = pm.count_installed();
@@ -444,6 +691,21 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                 AppEvent::InstalledCount(count) => {
                     app.installed_count = Some(count);
                 }
+                AppEvent::PackageCounts(counts) => {
+                    app.package_counts = Some(counts);
+                }
+                AppEvent::OrphanCount(count) => {
+                    app.orphan_count = Some(count);
+                }
+                AppEvent::CacheSize(size) => {
+                    app.cache_size = Some(size);
+                }
+                AppEvent::LargestPackages(packages) => {
+                    app.largest_packages = Some(packages);
+                }
+                AppEvent::RecentChanges(entries) => {
+                    app.recent_changes = Some(entries);
+                }
                 AppEvent::UpdateLine(line) => {
                     app.update.add_line(line);
                 }
@@ -452,27 +714,58 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                     packages_before,
                     packages_after,
                 } => {
+                    // 完整更新成功后缓存清零；部分更新或失败则重新检查，保持仪表盘计数准确
+                    if output.success && !app.update.is_partial() {
+                        app.available_updates = Some(Vec::new());
+                        app.available_updates_at = Some(std::time::Instant::now());
+                    } else {
+                        update::invalidate_update_cache(&mut app, &tx);
+                    }
+                    dashboard::spawn_maintenance_stats(&app, &tx);
+                    app.notify_finished("系统更新", app.update.target_preview().len(), output.failure_reason());
                     app.update.output = Some(output);
                     app.update.packages_before = packages_before;
                     app.update.packages_after = packages_after;
+                    app.update.build_package_diff();
                     app.update.phase = UpdatePhase::UpdateComplete;
                     app.update.add_line("--- 更新完成 ---".to_string());
 
                     // 启动 AI 分析
                     update::handle_update_complete(&mut app, &tx, &api_key);
                 }
-                AppEvent::AnalysisComplete(analysis) => {
-                    update::handle_analysis_complete(&mut app, analysis, &tx);
+                AppEvent::RebootCheck(required) => {
+                    update::handle_reboot_check(&mut app, required);
+                }
+                AppEvent::PacnewFound(files) => {
+                    app.update.pacnew_files = files;
+                }
+                AppEvent::ChatReply(reply) => {
+                    update::handle_chat_reply(&mut app, reply);
+                }
+                AppEvent::ChatFailed(msg) => {
+                    update::handle_chat_failed(&mut app, msg);
+                }
+                AppEvent::AnalysisChunk(chunk) => {
+                    update::handle_analysis_chunk(&mut app, chunk);
+                }
+                AppEvent::AnalysisComplete { analysis, usage } => {
+                    update::handle_analysis_complete(&mut app, analysis, usage, &tx);
                 }
                 AppEvent::ReportSaved(path) => {
                     // 根据当前模式分配报告路径
                     match app.mode {
                         AppMode::Install => { app.install.report_path = Some(path); }
                         AppMode::Remove => { app.remove.report_path = Some(path); }
+                        AppMode::Plan => { app.plan.report_path = Some(path); }
                         _ => { app.update.report_path = Some(path); }
                     }
                 }
                 AppEvent::Error(msg) => {
+                    // 更新 / 安装 / 卸载执行中出错
+                    if app.is_busy() && app.mode != AppMode::Shell {
+                        let (operation, count) = operation_summary(&app);
+                        app.notify_finished(operation, count, Some(&msg));
+                    }
                     app.error_message = Some(msg.clone());
                     // 根据当前模式设置对应错误状态
                     match app.mode {
@@ -481,7 +774,7 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                         AppMode::Shell => {
                             app.shell.lines.push(format!("错误: {}", msg));
                             app.shell.lines.push("─── 命令失败 ───".to_string());
-                            app.shell.phase = state::ShellPhase::Done;
+                            app.shell.phase = state::ShellPhase::Error;
                             app.shell.scroll = app.shell.lines.len().saturating_sub(1);
                         }
                         _ => { app.update.phase = UpdatePhase::Error; }
@@ -498,47 +791,85 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                 }
                 AppEvent::QueryRemoteResults { results, seq } => {
                     if seq == app.query.search_seq {
-                        app.query.remote_results = results;
+                        app.query.remote_error = results.as_ref().err().map(|e| e.to_string());
+                        app.query.remote_all = results.unwrap_or_default();
                         app.query.remote_selected = 0;
+                        app.query.apply_repo_filter();
                         if app.query.search_scheduled.is_none() {
                             app.query.searching = false;
                         }
                     }
                 }
-                AppEvent::QueryDetailLoaded { detail, files, dirs } => {
+                AppEvent::QueryDetailLoaded { detail, files, dirs, optdeps } => {
                     app.query.detail = Some(detail);
                     app.query.files = files;
                     app.query.dirs = dirs;
+                    app.query.optdeps = optdeps;
+                    app.query.optdep_selected = 0;
                     app.query.file_mode = state::FileListMode::Files;
                     app.query.detail_scroll = 0;
+                    app.query.file_mode_scroll = 0;
                     app.query.view = state::QueryView::Detail;
                 }
+                AppEvent::UpdateDownloadSize(total) => {
+                    app.update.download_total = total;
+                }
                 AppEvent::UpdatePreviewReady(updates) => {
-                    app.update.preview = updates;
-                    app.update.lines.clear();
-                    if app.update.preview.is_empty() {
-                        app.update.lines.push("系统已是最新，没有可用更新。".to_string());
-                    } else {
-                        app.update.lines.push(format!("找到 {} 个可用更新：", app.update.preview.len()));
-                        app.update.lines.push(String::new());
-                        for pkg in &app.update.preview {
-                            app.update.lines.push(format!("  {}", pkg));
+                    app.checking_updates = false;
+                    match &updates {
+                        Ok(list) => {
+                            app.available_updates = Some(list.clone());
+                            app.available_updates_at = Some(std::time::Instant::now());
+                            app.update_check_error = None;
+                        }
+                        Err(e) => {
+                            app.available_updates = None;
+                            app.available_updates_at = None;
+                            app.update_check_error = Some(e.to_string());
                         }
                     }
-                    app.update.phase = UpdatePhase::PreviewingUpdates;
-                    app.update.reset_scroll();
+                    // 仅在更新模式等待检查结果时刷新预览，其余情况只更新缓存
+                    if app.mode == AppMode::Update
+                        && app.update.phase == UpdatePhase::PackageManagerCheck
+                    {
+                        update::apply_update_preview(&mut app, updates);
+                    }
+                }
+                AppEvent::NewsLoaded(news) => {
+                    app.update.news = news;
+                    if app.mode == AppMode::Update
+                        && app.update.phase == UpdatePhase::PreviewingUpdates
+                    {
+                        app.update.push_news_lines();
+                    }
                 }
                 // ===== Install 事件 =====
                 AppEvent::InstallSearchResults { results, seq } => {
                     if seq == app.install.search_seq {
-                        app.install.results = results;
+                        app.install.search_error = results.as_ref().err().map(|e| e.to_string());
+                        app.install.set_results(results.unwrap_or_default());
                         app.install.selected = 0;
-                        app.install.marked.clear();
+                        install::fetch_missing_sizes(&mut app, &tx);
                         if app.install.search_scheduled.is_none() {
                             app.install.searching = false;
                         }
                     }
                 }
+                AppEvent::InstallPkgbuildReady(lines) => {
+                    if app.install.phase == state::InstallPhase::ReviewingPkgbuild {
+                        app.install.pkgbuild = lines;
+                        app.install.scroll = 0;
+                    }
+                }
+                AppEvent::InstallSizesLoaded(sizes) => {
+                    app.install.sizes.extend(sizes);
+                    if app.install.sort == state::SortKey::Size {
+                        app.install.apply_sort();
+                    }
+                }
+                AppEvent::InstallListImported { path, to_install, skipped } => {
+                    install::apply_imported_list(&mut app, &tx, &path, to_install, skipped);
+                }
                 AppEvent::InstallPreviewReady(preview) => {
                     app.install.preview = preview;
                     app.install.scroll = 0;
@@ -547,24 +878,31 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                     app.install.add_line(line);
                 }
                 AppEvent::InstallComplete { output } => {
+                    let count = install::collect_selected_packages(&app).len();
+                    app.notify_finished("安装", count, output.failure_reason());
                     app.install.output = Some(output);
                     app.install.phase = state::InstallPhase::InstallComplete;
                     app.install.add_line("--- 安装完成 ---".to_string());
                     install::handle_install_complete(&mut app, &tx, &api_key);
+                    update::invalidate_update_cache(&mut app, &tx);
                     // 刷新已安装包数量
                     if let Some(pm) = &app.package_manager {
                         let count = pm.count_installed();
                         app.installed_count = Some(count);
                     }
+                    dashboard::spawn_maintenance_stats(&app, &tx);
                 }
-                AppEvent::InstallAnalysisComplete(analysis) => {
-                    install::handle_install_analysis_complete(&mut app, analysis, &tx);
+                AppEvent::InstallAnalysisComplete { analysis, usage } => {
+                    install::handle_install_analysis_complete(&mut app, analysis, usage, &tx);
                 }
                 // ===== Remove 事件 =====
                 AppEvent::RemovePackagesLoaded(packages) => {
                     app.remove.packages = packages;
                     app.remove.loading = false;
                     app.remove.apply_filter();
+                    if app.remove.orphans {
+                        app.remove.marked = (0..app.remove.packages.len()).collect();
+                    }
                 }
                 AppEvent::RemovePreviewReady(preview) => {
                     app.remove.preview = preview;
@@ -574,24 +912,52 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                     app.remove.add_line(line);
                 }
                 AppEvent::RemoveComplete { output } => {
+                    let count = remove::collect_selected_packages(&app).len();
+                    app.notify_finished("卸载", count, output.failure_reason());
                     app.remove.output = Some(output);
                     app.remove.phase = state::RemovePhase::RemoveComplete;
                     app.remove.add_line("--- 卸载完成 ---".to_string());
                     remove::handle_remove_complete(&mut app, &tx, &api_key);
+                    update::invalidate_update_cache(&mut app, &tx);
                     // 刷新已安装包数量
                     if let Some(pm) = &app.package_manager {
                         let count = pm.count_installed();
                         app.installed_count = Some(count);
                     }
+                    dashboard::spawn_maintenance_stats(&app, &tx);
+                }
+                AppEvent::RemoveAnalysisComplete { analysis, usage } => {
+                    remove::handle_remove_analysis_complete(&mut app, analysis, usage, &tx);
+                }
+                AppEvent::PlanPreviewReady { preview, seq } => {
+                    // 已按 Esc 返回或切换卸载方式重新获取时，丢弃过期的预览
+                    if app.plan.fetching && seq == app.plan.preview_seq {
+                        app.plan.fetching = false;
+                        app.plan.preview = preview;
+                        app.plan.scroll = 0;
+                    }
+                }
+                AppEvent::PlanAnalysisComplete { result, log } => {
+                    plan::handle_plan_analysis_complete(&mut app, result, log, &tx);
                 }
-                AppEvent::RemoveAnalysisComplete(analysis) => {
-                    remove::handle_remove_analysis_complete(&mut app, analysis, &tx);
+                AppEvent::PlanLine(line) => {
+                    app.plan.add_line(line);
+                }
+                AppEvent::PlanComplete { removed, installed } => {
+                    plan::handle_plan_complete(&mut app, removed, installed, &tx, &api_key);
+                    update::invalidate_update_cache(&mut app, &tx);
+                    if let Some(pm) = &app.package_manager {
+                        app.installed_count = Some(pm.count_installed());
+                    }
+                    dashboard::spawn_maintenance_stats(&app, &tx);
                 }
                 AppEvent::ShellLine(line) => {
                     app.shell.add_line(line);
                 }
                 AppEvent::ShellComplete { output } => {
                     let success = output.success;
+                    // 命令可能安装或卸载了包，重新收集补全列表
+                    completion::refresh_in_background();
                     // 把 stderr 中有内容的行追加到 lines（stdout 已经通过 ShellLine 流式写入）
                     let stderr = output.stderr.clone();
                     app.shell.output = Some(output);
@@ -609,8 +975,17 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
                         "─── 命令失败 ───".to_string()
                     });
                     app.shell.phase = state::ShellPhase::Done;
-                    // scroll 已由 add_line 自动推进，这里确保它指向最后一行
-                    app.shell.scroll = app.shell.lines.len().saturating_sub(1);
+                    // scroll 已由 add_line 自动推进，这里确保它指向最后一行（关闭跟随时保持原位）
+                    if app.shell.follow {
+                        app.shell.scroll = app.shell.lines.len().saturating_sub(1);
+                    }
+                }
+                AppEvent::SettingsApiTested(result) => {
+                    app.settings.api_testing = false;
+                    app.settings.message = Some(match result {
+                        Ok(elapsed) => format!("✓ API 连接正常，耗时 {} ms", elapsed.as_millis()),
+                        Err(e) => format!("✗ API 连接失败: {}", e),
+                    });
                 }
             }
         }
@@ -628,13 +1003,131 @@ pub async fn run(api_key: String, config: Config) -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
     Ok(())
 }
 
+/// 滚轮每格滚动的行数
+const MOUSE_SCROLL_LINES: usize = 3;
+
+/// 鼠标滚轮：在输出/详情视图中转换为 ↑↓ 按键，复用各模式的滚动与 clamp 逻辑。
+/// 输入框视图中 ↑↓ 用于历史记录或选择，不转发
+fn handle_mouse_scroll(
+    mouse: MouseEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    api_key: &str,
+    term_height: u16,
+) {
+    let code = match mouse.kind {
+        MouseEventKind::ScrollUp => KeyCode::Up,
+        MouseEventKind::ScrollDown => KeyCode::Down,
+        _ => return,
+    };
+    let scrollable = match app.mode {
+        AppMode::Update => app.update.chat_input.is_none(),
+        AppMode::Install => app.install.phase != state::InstallPhase::Searching,
+        AppMode::Remove => app.remove.phase != state::RemovePhase::Browsing,
+        AppMode::Shell => !matches!(
+            app.shell.phase,
+            state::ShellPhase::Input | state::ShellPhase::PickingPackage
+        ),
+        AppMode::Query => app.query.view == state::QueryView::Detail,
+        AppMode::Pacnew => app.pacnew.view == state::PacnewView::Diff,
+        AppMode::Plan => app.plan.phase != state::PlanPhase::Editing,
+        AppMode::Dashboard | AppMode::Settings => false,
+    };
+    if !scrollable {
+        return;
+    }
+    let key = crossterm::event::KeyEvent::new(code, KeyModifiers::NONE);
+    for _ in 0..MOUSE_SCROLL_LINES {
+        match app.mode {
+            AppMode::Update => {
+                update::handle_update_key(key, app, tx, api_key, term_height);
+            }
+            AppMode::Install => {
+                install::handle_install_key(key, app, tx, term_height);
+            }
+            AppMode::Remove => {
+                remove::handle_remove_key(key, app, tx, term_height);
+            }
+            AppMode::Shell => {
+                shell::handle_shell_key(key, app, tx, term_height);
+            }
+            AppMode::Query => query::handle_query_key(key, app, tx, term_height),
+            AppMode::Pacnew => {
+                pacnew::handle_pacnew_key(key, app, term_height);
+            }
+            AppMode::Plan => {
+                plan::handle_plan_key(key, app, tx, term_height);
+            }
+            AppMode::Dashboard | AppMode::Settings => {}
+        }
+    }
+}
+
+/// 将粘贴的文本插入当前输入框，并像逐字输入一样刷新搜索 / 筛选
+fn paste_into_input(app: &mut App, text: &str) {
+    use input::str_insert_str;
+    match app.mode {
+        AppMode::Update => {
+            let single_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            if let Some(input) = app.update.search.input.as_mut() {
+                input.push_str(single_line);
+            } else if let Some(input) = app.update.chat_input.as_mut() {
+                input.push_str(single_line);
+            }
+        }
+        AppMode::Install => {
+            str_insert_str(&mut app.install.input, &mut app.install.cursor, text, false);
+            install::schedule_search(app);
+        }
+        AppMode::Remove => {
+            str_insert_str(&mut app.remove.input, &mut app.remove.cursor, text, false);
+            app.remove.apply_filter();
+        }
+        AppMode::Query => {
+            str_insert_str(&mut app.query.input, &mut app.query.cursor, text, false);
+            query::schedule_search(app);
+        }
+        AppMode::Settings if app.settings.filter_editing => {
+            str_insert_str(&mut app.settings.filter, &mut app.settings.filter_cursor, text, false);
+            app.apply_settings_filter();
+        }
+        AppMode::Settings => {
+            let multiline = matches!(
+                app.selected_settings_item(),
+                Some(state::SettingsItem::TextEdit { multiline: true, .. })
+            );
+            str_insert_str(&mut app.settings.edit_buffer, &mut app.settings.edit_cursor, text, multiline);
+        }
+        AppMode::Shell => {
+            if let Some(input) = app.shell.search.input.as_mut() {
+                input.push_str(text.lines().next().unwrap_or(""));
+            } else {
+                str_insert_str(&mut app.shell.input, &mut app.shell.cursor, text, false);
+                app.shell.progress.clear();
+            }
+        }
+        AppMode::Dashboard | AppMode::Pacnew | AppMode::Plan => {}
+    }
+}
+
+/// 当前操作的名称与涉及的包数量，用于桌面通知
+fn operation_summary(app: &App) -> (&'static str, usize) {
+    match app.mode {
+        AppMode::Install => ("安装", install::collect_selected_packages(app).len()),
+        AppMode::Remove => ("卸载", remove::collect_selected_packages(app).len()),
+        AppMode::Plan => ("事务计划", app.plan.len()),
+        _ => ("系统更新", app.update.target_preview().len()),
+    }
+}
+
 /// 临时退出 TUI 执行 sudo 鉴权，成功后恢复 TUI
 fn validate_sudo_tui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
@@ -644,26 +1137,28 @@ fn validate_sudo_tui(
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
-    // 提示并执行 sudo -v
-    println!("🔐 需要 sudo 权限来执行此操作");
+    // 提示并执行 sudo -v（或配置的提权命令）
+    let privilege = package_manager::privilege::name();
+    println!("🔐 需要 {} 权限来执行此操作", privilege);
     println!();
 
-    let status = std::process::Command::new("sudo")
-        .arg("-v")
+    let status = package_manager::privilege::command(false)
+        .args(package_manager::privilege::validate_args())
         .status()?;
 
     let success = status.success();
 
     if success {
         println!();
-        println!("✅ sudo 验证成功！");
+        println!("✅ {} 验证成功！", privilege);
     } else {
         println!();
-        println!("❌ sudo 验证失败");
+        println!("❌ {} 验证失败", privilege);
     }
 
     std::thread::sleep(std::time::Duration::from_millis(500));
@@ -673,7 +1168,8 @@ fn validate_sudo_tui(
     execute!(
         terminal.backend_mut(),
         EnterAlternateScreen,
-        EnableMouseCapture
+        EnableMouseCapture,
+        EnableBracketedPaste
     )?;
     terminal.hide_cursor()?;
     terminal.clear()?;
@@ -682,13 +1178,30 @@ fn validate_sudo_tui(
 }
 
 fn ui(f: &mut Frame, app: &App) {
-    match app.mode {
-        AppMode::Dashboard => dashboard::render_dashboard(f, app),
-        AppMode::Update => update::render_update(f, app),
-        AppMode::Install => install::render_install(f, app),
-        AppMode::Remove => remove::render_remove(f, app),
-        AppMode::Query => query::render_query(f, app),
-        AppMode::Settings => settings::render_settings(f, app),
-        AppMode::Shell => shell::render_shell(f, app),
+    if layout::is_too_small(f.area()) {
+        // 尺寸不足时布局计算会得到零大小区域，只显示提示
+        layout::render_too_small(f);
+    } else {
+        match app.mode {
+            AppMode::Dashboard => dashboard::render_dashboard(f, app),
+            AppMode::Update => update::render_update(f, app),
+            AppMode::Install => install::render_install(f, app),
+            AppMode::Remove => remove::render_remove(f, app),
+            AppMode::Query => query::render_query(f, app),
+            AppMode::Settings => settings::render_settings(f, app),
+            AppMode::Shell => shell::render_shell(f, app),
+            AppMode::Pacnew => pacnew::render_pacnew(f, app),
+            AppMode::Plan => plan::render_plan(f, app),
+        }
+        if app.show_help {
+            help::render_help_overlay(f);
+        }
+    }
+    if app.quit_confirm {
+        layout::render_confirm_dialog(
+            f,
+            "操作进行中，确认退出？",
+            "Ctrl+C / y 确认退出（将中止当前操作） | 其他键取消",
+        );
     }
 }
@@ -0,0 +1,356 @@
+//! .pacnew / .pacsave 合并：列出待处理文件，查看与在用配置的统一 diff，sudo 接受新文件或删除
+//!
+//! .pacsave 是 pacman 保存下来的旧配置，不能"接受"，只能对比后删除；需要其中的改动请手动合并
+
+use super::i18n::{t, tf};
+use super::layout;
+use super::state::{App, AppMode, PacnewAction, PacnewModeState, PacnewView};
+use crate::package_manager::privilege;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use similar::{ChangeTag, TextDiff};
+use std::path::{Path, PathBuf};
+
+/// diff 中每处改动前后保留的上下文行数
+const CONTEXT_LINES: usize = 3;
+
+/// 进入合并视图，Esc 从列表返回 return_mode
+pub fn open(app: &mut App, files: Vec<PathBuf>, return_mode: AppMode) {
+    app.pacnew = PacnewModeState::new();
+    app.pacnew.files = files;
+    app.pacnew.return_mode = return_mode;
+    app.mode = AppMode::Pacnew;
+}
+
+/// .pacnew / .pacsave 对应的在用配置路径
+fn live_path(path: &Path) -> PathBuf {
+    crate::package_manager::pacnew_target(path)
+        .map(|(live, _)| live)
+        .unwrap_or_else(|| path.with_extension(""))
+}
+
+/// 是否为 pacman 保存的旧配置（.pacsave / .pacsave.N）
+fn is_pacsave(path: &Path) -> bool {
+    crate::package_manager::pacnew_target(path).is_some_and(|(_, pacsave)| pacsave)
+}
+
+/// 选中项是否可以接受（仅 .pacnew）
+fn can_accept(app: &App) -> bool {
+    selected_file(app).is_some_and(|p| !is_pacsave(p))
+}
+
+fn selected_file(app: &App) -> Option<&PathBuf> {
+    app.pacnew.files.get(app.pacnew.selected)
+}
+
+/// 读取配置文件；/etc 下部分文件普通用户不可读，此时借助已缓存的 sudo 凭证读取
+fn read_config(path: &Path) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let output = privilege::command(true)
+                .arg("cat")
+                .arg(path)
+                .output()
+                .map_err(|e| e.to_string())?;
+            if output.status.success() {
+                Ok(String::from_utf8_lossy(&output.stdout).to_string())
+            } else {
+                Err(format!(
+                    "{}（无权限，可先执行一次 {} 操作后重试）",
+                    e,
+                    crate::package_manager::privilege::name()
+                ))
+            }
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 在用配置 → 新文件的统一 diff，按 DIFF_* 前缀着色
+pub fn diff_lines(live: &str, new: &str) -> Vec<String> {
+    let diff = TextDiff::from_lines(live, new);
+    let mut lines = Vec::new();
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let old_start = first.old_range().start;
+        let new_start = first.new_range().start;
+        lines.push(format!(
+            "DIFF_CHG:@@ -{},{} +{},{} @@",
+            old_start + 1,
+            last.old_range().end - old_start,
+            new_start + 1,
+            last.new_range().end - new_start
+        ));
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                // 制表符在终端中宽度不定，统一展开
+                let text = change.value().trim_end_matches(['\n', '\r']).replace('\t', "    ");
+                lines.push(match change.tag() {
+                    ChangeTag::Equal => format!("  {}", text),
+                    ChangeTag::Delete => format!("DIFF_DEL:- {}", text),
+                    ChangeTag::Insert => format!("DIFF_ADD:+ {}", text),
+                });
+            }
+        }
+    }
+    lines
+}
+
+/// 载入选中文件的 diff 并切换到 diff 视图
+///
+/// .pacnew 显示 在用 → 新文件；.pacsave 显示 旧配置 → 在用，即旧配置被替换后改了什么
+fn open_diff(app: &mut App) {
+    let Some(path) = selected_file(app).cloned() else {
+        return;
+    };
+    let live = live_path(&path);
+    let pacsave = is_pacsave(&path);
+    let mut lines = if pacsave {
+        vec![
+            format!("DIFF_DEL:--- {} (旧配置)", path.display()),
+            format!("DIFF_ADD:+++ {} (在用)", live.display()),
+            "WARN:.pacsave 是 pacman 保存的旧配置，只能删除 (d)；需要其中的改动请手动合并".to_string(),
+            String::new(),
+        ]
+    } else {
+        vec![
+            format!("DIFF_DEL:--- {} (在用)", live.display()),
+            format!("DIFF_ADD:+++ {}", path.display()),
+            String::new(),
+        ]
+    };
+    let live_content = if live.exists() {
+        read_config(&live)
+    } else if pacsave {
+        lines.push("WARN:在用配置不存在（对应的软件包可能已卸载）".to_string());
+        Ok(String::new())
+    } else {
+        lines.push("WARN:在用配置不存在，接受将直接恢复此文件".to_string());
+        Ok(String::new())
+    };
+    match (live_content, read_config(&path)) {
+        (Ok(live_text), Ok(file_text)) => {
+            let diff = if pacsave {
+                diff_lines(&file_text, &live_text)
+            } else {
+                diff_lines(&live_text, &file_text)
+            };
+            if diff.is_empty() {
+                lines.push("两个文件内容相同，可直接删除 (d)".to_string());
+            }
+            lines.extend(diff);
+        }
+        (Err(e), _) => lines.push(format!("DANGER:无法读取 {}: {}", live.display(), e)),
+        (_, Err(e)) => lines.push(format!("DANGER:无法读取 {}: {}", path.display(), e)),
+    }
+    app.pacnew.diff = lines;
+    app.pacnew.scroll = 0;
+    app.pacnew.view = PacnewView::Diff;
+}
+
+/// 处理合并视图按键（y 确认由主循环处理，需先完成 sudo 鉴权）
+pub fn handle_pacnew_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
+    // 确认弹窗中：除 y 外的任意键取消
+    if app.pacnew.pending.is_some() {
+        app.pacnew.pending = None;
+        return true;
+    }
+
+    match key.code {
+        KeyCode::Char('a') if can_accept(app) => {
+            app.pacnew.pending = Some(PacnewAction::Accept);
+            true
+        }
+        KeyCode::Char('d') if selected_file(app).is_some() => {
+            app.pacnew.pending = Some(PacnewAction::Delete);
+            true
+        }
+        _ => match app.pacnew.view {
+            PacnewView::List => handle_list_key(key, app),
+            PacnewView::Diff => handle_diff_key(key, app, term_height),
+        },
+    }
+}
+
+fn handle_list_key(key: KeyEvent, app: &mut App) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.mode = app.pacnew.return_mode.clone();
+            true
+        }
+        KeyCode::Up => {
+            app.pacnew.selected = app.pacnew.selected.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            if app.pacnew.selected + 1 < app.pacnew.files.len() {
+                app.pacnew.selected += 1;
+            }
+            true
+        }
+        KeyCode::Enter => {
+            open_diff(app);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn handle_diff_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
+    let max_scroll = app
+        .pacnew
+        .diff
+        .len()
+        .saturating_sub(layout::visible_content_height(term_height));
+    match key.code {
+        KeyCode::Esc => {
+            app.pacnew.view = PacnewView::List;
+            true
+        }
+        KeyCode::Up => {
+            app.pacnew.scroll = app.pacnew.scroll.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            app.pacnew.scroll = (app.pacnew.scroll + 1).min(max_scroll);
+            true
+        }
+        KeyCode::PageUp => {
+            app.pacnew.scroll = app.pacnew.scroll.saturating_sub(10);
+            true
+        }
+        KeyCode::PageDown => {
+            app.pacnew.scroll = (app.pacnew.scroll + 10).min(max_scroll);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 执行已确认的操作（调用前需完成 sudo 验证），成功后从列表移除该文件
+pub fn apply_pending(app: &mut App) {
+    let (Some(action), Some(path)) = (app.pacnew.pending.take(), selected_file(app).cloned())
+    else {
+        return;
+    };
+    let live = live_path(&path);
+    if action == PacnewAction::Accept && is_pacsave(&path) {
+        return;
+    }
+    let mut cmd = privilege::command(true);
+    match action {
+        PacnewAction::Accept => cmd.arg("mv").arg("-f").arg(&path).arg(&live),
+        PacnewAction::Delete => cmd.arg("rm").arg("-f").arg(&path),
+    };
+
+    match cmd.output() {
+        Ok(output) if output.status.success() => {
+            app.pacnew.message = Some(match action {
+                PacnewAction::Accept => format!("✓ 已用新文件替换 {}", live.display()),
+                PacnewAction::Delete => format!("✓ 已删除 {}", path.display()),
+            });
+            app.pacnew.files.retain(|p| p != &path);
+            app.update.pacnew_files.retain(|p| p != &path);
+            app.pacnew.selected = app.pacnew.selected.min(app.pacnew.files.len().saturating_sub(1));
+            app.pacnew.view = PacnewView::List;
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            app.pacnew.message = Some(format!("操作失败: {}", stderr.trim()));
+        }
+        Err(e) => app.pacnew.message = Some(format!("操作失败: {}", e)),
+    }
+}
+
+/// 渲染合并视图
+pub fn render_pacnew(f: &mut Frame, app: &App) {
+    let chunks = layout::main_layout(f.area());
+    let title = tf("pacnew.title", &[&app.pacnew.files.len()]);
+    layout::render_header(f, &title, chunks[0]);
+
+    match app.pacnew.view {
+        PacnewView::List => render_list(f, app, chunks[1]),
+        PacnewView::Diff => {
+            let name = selected_file(app).map(|p| p.display().to_string()).unwrap_or_default();
+            layout::render_scrollable_content(f, &name, &app.pacnew.diff, app.pacnew.scroll, chunks[1]);
+        }
+    }
+
+    let pacsave = !can_accept(app);
+    let keys = match app.pacnew.view {
+        PacnewView::List if app.pacnew.files.is_empty() => t("common.back"),
+        PacnewView::List if pacsave => t("pacnew.footer.list_pacsave"),
+        PacnewView::List => t("pacnew.footer.list"),
+        PacnewView::Diff if pacsave => t("pacnew.footer.diff_pacsave"),
+        PacnewView::Diff => t("pacnew.footer.diff"),
+    };
+    let owned_footer: String;
+    let footer = match &app.pacnew.message {
+        Some(msg) => {
+            owned_footer = format!("{} | {}", msg, keys);
+            &owned_footer
+        }
+        None => keys,
+    };
+    layout::render_footer(f, footer, chunks[2]);
+
+    if let (Some(action), Some(path)) = (app.pacnew.pending, selected_file(app)) {
+        let text = match action {
+            PacnewAction::Accept => format!(
+                "用 {} 覆盖 {}？",
+                path.display(),
+                live_path(path).display()
+            ),
+            PacnewAction::Delete if is_pacsave(path) => format!("删除旧配置 {}（保留在用配置）？", path.display()),
+            PacnewAction::Delete => format!("删除 {}（保留在用配置）？", path.display()),
+        };
+        layout::render_confirm_dialog(f, &text, "y 确认 (sudo) | 其他键取消");
+    }
+}
+
+fn render_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme();
+    let block = Block::default()
+        .title(" .pacnew / .pacsave ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.pacnew.files.is_empty() {
+        let hint = Paragraph::new("没有待合并的配置文件").style(Style::default().fg(theme.dim));
+        f.render_widget(hint, inner);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let start = app.pacnew.selected.saturating_sub(visible.saturating_sub(1));
+    let lines: Vec<Line> = app
+        .pacnew
+        .files
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .map(|(i, path)| {
+            let name = path.display().to_string();
+            if i == app.pacnew.selected {
+                Line::from(Span::styled(
+                    format!("▸ {}", name),
+                    Style::default().fg(theme.primary).add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(Span::styled(format!("  {}", name), Style::default().fg(theme.text)))
+            }
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
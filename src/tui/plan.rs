@@ -0,0 +1,546 @@
+//! 事务计划：在安装 / 卸载模式中用 Ctrl+P 收集包，合并预览后先卸载再安装
+//!
+//! 先卸载可以让互相冲突的包完成替换；卸载失败时不再继续安装。AUR 包可能需要
+//! paru / yay 单独构建，因此两步分别调用包管理器，而不是合成一条命令。
+//! 执行结束后两步的日志合在一起做一次 AI 分析，保存为一份报告。
+
+use super::i18n::{t, tf};
+use super::layout;
+use super::state::{follow_label, App, AppEvent, AppMode, PlanPhase};
+use crate::package_manager::UpdateOutput;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tokio::sync::mpsc;
+
+/// 把安装模式中选中的包加入计划
+pub fn add_from_install(app: &mut App) {
+    let names = super::install::collect_selected_packages(app);
+    if names.is_empty() {
+        return;
+    }
+    app.plan
+        .aur
+        .extend(names.iter().filter(|n| app.install.known_aur.contains(*n)).cloned());
+    app.plan.add_install(&names);
+    app.install.marked.clear();
+    app.install.progress = tf("plan.added", &[&app.plan.remove.len(), &app.plan.install.len()]);
+}
+
+/// 把卸载模式中选中的包加入计划
+pub fn add_from_remove(app: &mut App) {
+    let names = super::remove::collect_selected_packages(app);
+    if names.is_empty() {
+        return;
+    }
+    app.plan.add_remove(&names);
+    app.remove.marked.clear();
+    app.remove.progress = tf("plan.added", &[&app.plan.remove.len(), &app.plan.install.len()]);
+}
+
+/// 进入计划视图；执行中或已完成时保留当前输出
+pub fn open(app: &mut App) {
+    app.mode = AppMode::Plan;
+    if app.plan.phase == PlanPhase::Previewing {
+        app.plan.phase = PlanPhase::Editing;
+        app.plan.fetching = false;
+    }
+    app.plan.message = None;
+}
+
+/// 处理计划视图按键（预览中的 Enter 由主循环处理，需先完成 sudo 鉴权）
+pub fn handle_plan_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    term_height: u16,
+) -> bool {
+    match app.plan.phase {
+        PlanPhase::Editing => handle_edit_key(key, app, tx),
+        PlanPhase::Previewing => handle_preview_key(key, app, tx, term_height),
+        PlanPhase::Running | PlanPhase::Complete => handle_output_key(key, app, term_height),
+    }
+}
+
+fn handle_edit_key(key: KeyEvent, app: &mut App, tx: &mpsc::Sender<AppEvent>) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.mode = AppMode::Dashboard;
+            true
+        }
+        KeyCode::Up => {
+            app.plan.selected = app.plan.selected.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            if app.plan.selected + 1 < app.plan.len() {
+                app.plan.selected += 1;
+            }
+            true
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            app.plan.remove_selected();
+            true
+        }
+        KeyCode::Char('c') => {
+            app.plan.reset();
+            true
+        }
+        KeyCode::Char('m') => {
+            app.plan.variant = app.plan.variant.next();
+            true
+        }
+        KeyCode::Enter if !app.plan.is_empty() => {
+            start_preview(app, tx);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn handle_preview_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    term_height: u16,
+) -> bool {
+    let max_scroll = app.plan.preview.len().saturating_sub(layout::visible_content_height(term_height));
+    match key.code {
+        KeyCode::Esc => {
+            app.plan.phase = PlanPhase::Editing;
+            app.plan.fetching = false;
+            app.plan.preview.clear();
+            app.plan.scroll = 0;
+            true
+        }
+        KeyCode::Char('m') => {
+            // 切换卸载方式并重新获取预览
+            app.plan.variant = app.plan.variant.next();
+            start_preview(app, tx);
+            true
+        }
+        KeyCode::Up => {
+            app.plan.scroll = app.plan.scroll.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            app.plan.scroll = (app.plan.scroll + 1).min(max_scroll);
+            true
+        }
+        KeyCode::PageUp => {
+            app.plan.scroll = app.plan.scroll.saturating_sub(10);
+            true
+        }
+        KeyCode::PageDown => {
+            app.plan.scroll = (app.plan.scroll + 10).min(max_scroll);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
+    let max_scroll = app.plan.lines.len().saturating_sub(layout::visible_content_height(term_height));
+    match key.code {
+        KeyCode::Esc if app.plan.phase == PlanPhase::Running => {
+            // 中止当前步骤，后续步骤不再执行，结果仍由 PlanComplete 汇总
+            crate::package_manager::cancel_update();
+            true
+        }
+        KeyCode::Esc => {
+            // 全部成功后清空计划；否则保留列表以便调整后重试
+            if app.plan.succeeded {
+                app.plan.reset();
+                app.mode = AppMode::Dashboard;
+            } else {
+                app.plan.phase = PlanPhase::Editing;
+                app.plan.lines.clear();
+                app.plan.scroll = 0;
+                app.plan.analyzing = false;
+            }
+            true
+        }
+        KeyCode::Char('f') if app.plan.phase == PlanPhase::Running => {
+            app.plan.toggle_follow();
+            true
+        }
+        KeyCode::Char('y') => {
+            app.plan.message = Some(match layout::copy_to_clipboard(&layout::plain_text(&app.plan.lines)) {
+                Ok(()) => "✓ 已复制到剪贴板".to_string(),
+                Err(e) => format!("复制失败: {}", e),
+            });
+            true
+        }
+        KeyCode::Up => {
+            app.plan.scroll = app.plan.scroll.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            app.plan.scroll = (app.plan.scroll + 1).min(max_scroll);
+            true
+        }
+        KeyCode::PageUp => {
+            app.plan.scroll = app.plan.scroll.saturating_sub(10);
+            true
+        }
+        KeyCode::PageDown => {
+            app.plan.scroll = (app.plan.scroll + 10).min(max_scroll);
+            true
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.plan.scroll = 0;
+            true
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.plan.scroll = max_scroll;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 分别获取卸载与安装预览，合并后进入预览阶段
+fn start_preview(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let Some(pm) = app.package_manager.clone() else {
+        return;
+    };
+    let remove = app.plan.remove.clone();
+    let install = app.plan.install.clone();
+    let mut aur: Vec<String> = install.iter().filter(|n| app.plan.aur.contains(*n)).cloned().collect();
+    aur.sort();
+    let variant = app.plan.variant;
+    let min_free = app.config.min_free_space_mb.saturating_mul(1024 * 1024);
+    app.plan.preview_seq += 1;
+    let seq = app.plan.preview_seq;
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        let preview = tokio::task::spawn_blocking(move || {
+            let mut lines = Vec::new();
+            if !remove.is_empty() {
+                lines.push(format!("===== 1. 卸载 ({}) =====", variant.flag()));
+                lines.extend(pm.preview_remove(&remove, variant));
+                lines.push(String::new());
+            }
+            if !install.is_empty() {
+                let step = if remove.is_empty() { 1 } else { 2 };
+                lines.push(format!("===== {}. 安装 =====", step));
+                if !aur.is_empty() {
+                    lines.push(format!(
+                        "WARN:AUR 包 {} 将直接构建，未经 PKGBUILD 审查（需要审查请在安装模式中单独安装）",
+                        aur.join(", ")
+                    ));
+                }
+                lines.extend(pm.preview_install(&install, min_free));
+            }
+            lines
+        })
+        .await
+        .unwrap_or_default();
+        let _ = tx_clone.send(AppEvent::PlanPreviewReady { preview, seq }).await;
+    });
+    app.plan.phase = PlanPhase::Previewing;
+    app.plan.fetching = true;
+    app.plan.danger_confirmed = false;
+    app.plan.preview = vec!["正在获取预览...".to_string()];
+    app.plan.scroll = 0;
+}
+
+/// 启动计划执行：先卸载后安装，前一步失败或被取消时跳过后续步骤
+pub fn spawn_plan_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let Some(pm) = app.package_manager.clone() else {
+        return;
+    };
+    let remove = app.plan.remove.clone();
+    let install = app.plan.install.clone();
+    let variant = app.plan.variant;
+    let tx_clone = tx.clone();
+    app.plan.phase = PlanPhase::Running;
+    app.plan.lines.clear();
+    app.plan.scroll = 0;
+    app.plan.message = None;
+    app.plan.analyzing = false;
+    app.plan.token_usage = None;
+    app.plan.report_path = None;
+
+    // 在 UI 线程提前重置取消标志
+    crate::package_manager::reset_cancel();
+
+    std::thread::spawn(move || {
+        let (output_tx, mut output_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let tx_for_lines = tx_clone.clone();
+        std::thread::spawn(move || {
+            while let Some(line) = output_rx.blocking_recv() {
+                let _ = tx_for_lines.blocking_send(AppEvent::PlanLine(line));
+            }
+        });
+
+        // 启动失败也作为失败的输出记录，便于统一汇总
+        let failed = |e: anyhow::Error| UpdateOutput {
+            stdout: String::new(),
+            stderr: e.to_string(),
+            success: false,
+        };
+
+        let removed = (!remove.is_empty()).then(|| {
+            let _ = output_tx.send(format!("===== 卸载: {} =====", remove.join(", ")));
+            pm.remove_streaming(&remove, variant, output_tx.clone()).unwrap_or_else(failed)
+        });
+        let installed = (!install.is_empty() && removed.as_ref().is_none_or(|o| o.success)).then(|| {
+            let _ = output_tx.send(format!("===== 安装: {} =====", install.join(", ")));
+            pm.install_streaming(&install, &[], output_tx.clone()).unwrap_or_else(failed)
+        });
+        drop(output_tx);
+
+        let _ = tx_clone.blocking_send(AppEvent::PlanComplete { removed, installed });
+    });
+}
+
+/// 处理计划执行结束：追加合并的结果摘要，发送通知并启动合并的 AI 分析
+pub fn handle_plan_complete(
+    app: &mut App,
+    removed: Option<UpdateOutput>,
+    installed: Option<UpdateOutput>,
+    tx: &mpsc::Sender<AppEvent>,
+    api_key: &str,
+) {
+    let mut steps = Vec::new();
+    if !app.plan.remove.is_empty() {
+        steps.push((format!("卸载 ({})", app.plan.variant.flag()), app.plan.remove.len(), removed));
+    }
+    if !app.plan.install.is_empty() {
+        steps.push(("安装".to_string(), app.plan.install.len(), installed));
+    }
+
+    let plan = &mut app.plan;
+    plan.phase = PlanPhase::Complete;
+    plan.add_line(String::new());
+    plan.add_line("===== 计划执行结果 =====".to_string());
+    let mut error: Option<String> = None;
+    for (label, count, output) in &steps {
+        let line = match output {
+            Some(o) if o.success => format!("✓ {} {} 个包", label, count),
+            Some(o) => {
+                let reason = o.failure_reason().unwrap_or_default();
+                error.get_or_insert_with(|| reason.to_string());
+                format!("DANGER:✗ {} 失败: {}", label, reason)
+            }
+            None => {
+                error.get_or_insert_with(|| "前一步未成功".to_string());
+                format!("WARN:- {} 已跳过（前一步未成功）", label)
+            }
+        };
+        plan.add_line(line);
+    }
+    plan.succeeded = error.is_none();
+    let count = plan.len();
+    app.notify_finished("事务计划", count, error.as_deref());
+
+    // 至少有一步执行过才分析；卸载和安装的日志合在一起，最终只保存一份报告
+    if steps.iter().all(|(_, _, output)| output.is_none()) {
+        return;
+    }
+    let enabled = (!app.plan.remove.is_empty() && app.config.ai_enabled_for("remove"))
+        || (!app.plan.install.is_empty() && app.config.ai_enabled_for("install"));
+    if !enabled {
+        app.plan.add_line(String::new());
+        app.plan.add_line("[AI 分析已关闭，可在设置中开启]".to_string());
+        return;
+    }
+    spawn_plan_analysis(app, tx, api_key);
+}
+
+/// 以执行输出（含结果摘要）为日志启动 AI 分析
+fn spawn_plan_analysis(app: &mut App, tx: &mpsc::Sender<AppEvent>, api_key: &str) {
+    let Some(pm) = &app.package_manager else {
+        return;
+    };
+    let log = layout::plain_text(&app.plan.lines);
+    let prompt_text = crate::prompt::generate_plan_prompt(
+        pm.name(),
+        &app.plan.remove,
+        app.plan.variant.flag(),
+        &app.plan.install,
+        &log,
+        app.system_info.as_ref(),
+        &app.config.analysis_language,
+    );
+    let client = crate::deepseek::AiClient::from_config(api_key, &app.config);
+    let model = app.config.get_model().to_string();
+    let temperature = app.config.temperature;
+    let tx_clone = tx.clone();
+    app.plan.analyzing = true;
+
+    tokio::spawn(async move {
+        let result = client.analyze_update(&prompt_text, &model, temperature).await;
+        let _ = tx_clone.send(AppEvent::PlanAnalysisComplete { result, log }).await;
+    });
+}
+
+/// 处理计划 AI 分析结束：追加到输出末尾，并把分析与执行日志保存为一份报告
+///
+/// 分析期间计划已被清空（Esc 返回主页）时不再追加，但报告照常保存
+pub fn handle_plan_analysis_complete(
+    app: &mut App,
+    result: anyhow::Result<(String, Option<crate::deepseek::TokenUsage>)>,
+    log: String,
+    tx: &mpsc::Sender<AppEvent>,
+) {
+    let show = std::mem::take(&mut app.plan.analyzing);
+    let (analysis, usage) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            if show {
+                app.plan.add_line(String::new());
+                app.plan.add_line(format!("DANGER:AI 分析失败: {}", e));
+            }
+            return;
+        }
+    };
+    if show {
+        app.plan.token_usage = usage;
+        app.plan.add_line(String::new());
+        app.plan.add_line("===== AI 分析 =====".to_string());
+        for line in analysis.lines() {
+            app.plan.add_line(line.to_string());
+        }
+    }
+
+    let saver = crate::report::ReportSaver::from_config(&app.config);
+    let distro_name = app.system_info.as_ref()
+        .map(|info| info.distro.clone())
+        .unwrap_or_else(|| "Linux".to_string());
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        match saver.save(&analysis, &log, &distro_name, "plan") {
+            Ok(path) => {
+                let _ = tx_clone
+                    .send(AppEvent::ReportSaved(path.display().to_string()))
+                    .await;
+            }
+            Err(e) => {
+                log::error!("保存报告失败: {}", e);
+            }
+        }
+    });
+}
+
+// ===== 渲染 =====
+
+pub fn render_plan(f: &mut Frame, app: &App) {
+    let chunks = layout::main_layout(f.area());
+    let title = tf("plan.title", &[&app.plan.remove.len(), &app.plan.install.len()]);
+    layout::render_header(f, &title, chunks[0]);
+
+    match app.plan.phase {
+        PlanPhase::Editing => render_entries(f, app, chunks[1]),
+        PlanPhase::Previewing => {
+            layout::render_scrollable_content(f, t("plan.view.preview"), &app.plan.preview, app.plan.scroll, chunks[1]);
+        }
+        PlanPhase::Running | PlanPhase::Complete => {
+            layout::render_scrollable_content(f, t("plan.view.output"), &app.plan.lines, app.plan.scroll, chunks[1]);
+        }
+    }
+
+    let owned_footer: String;
+    let keys = match app.plan.phase {
+        PlanPhase::Editing if app.plan.is_empty() => t("plan.footer.empty"),
+        PlanPhase::Editing => {
+            owned_footer = tf("plan.footer.edit", &[&app.plan.variant.flag()]);
+            &owned_footer
+        }
+        PlanPhase::Previewing if app.plan.fetching => t("common.loading"),
+        PlanPhase::Previewing if app.plan.has_danger() && app.plan.danger_confirmed => {
+            t("plan.footer.danger_confirm")
+        }
+        PlanPhase::Previewing if app.plan.has_danger() => t("plan.footer.danger"),
+        PlanPhase::Previewing => {
+            owned_footer = tf("plan.footer.preview", &[&app.plan.variant.flag()]);
+            &owned_footer
+        }
+        PlanPhase::Running => {
+            let pt = app.plan.progress_info.footer_text();
+            let pt = if pt.is_empty() { t("plan.footer.running").to_string() } else { pt };
+            owned_footer = format!("{} | {}", pt, follow_label(app.plan.follow));
+            &owned_footer
+        }
+        PlanPhase::Complete if app.plan.analyzing => {
+            owned_footer = format!("{} | {}", t("common.analyzing"), t("plan.footer.done"));
+            &owned_footer
+        }
+        PlanPhase::Complete => {
+            let mut parts = Vec::new();
+            if let Some(path) = &app.plan.report_path {
+                parts.push(tf("common.report_saved", &[path]));
+            }
+            if let Some(usage) = &app.plan.token_usage {
+                parts.push(usage.footer_text(app.config.token_price));
+            }
+            parts.push(t("plan.footer.done").to_string());
+            owned_footer = parts.join(" | ");
+            &owned_footer
+        }
+    };
+    let with_message: String;
+    let footer = match &app.plan.message {
+        Some(msg) => {
+            with_message = format!("{} | {}", msg, keys);
+            &with_message
+        }
+        None => keys,
+    };
+    let percent = match app.plan.phase {
+        PlanPhase::Running => app.plan.progress_info.percent,
+        _ => None,
+    };
+    layout::render_progress_footer(f, footer, percent, chunks[2]);
+}
+
+/// 编辑列表：卸载项在前（红色 -），安装项在后（绿色 +），与执行顺序一致
+fn render_entries(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme();
+    let block = Block::default()
+        .title(format!(" {} ", t("plan.view.entries")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ratatui::style::Color::Yellow));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.plan.is_empty() {
+        let hint = Paragraph::new(t("plan.empty")).style(Style::default().fg(theme.dim));
+        f.render_widget(hint, inner);
+        return;
+    }
+
+    let entries = app
+        .plan
+        .remove
+        .iter()
+        .map(|name| ("-", name, ratatui::style::Color::Red))
+        .chain(app.plan.install.iter().map(|name| ("+", name, ratatui::style::Color::Green)));
+    let visible = inner.height as usize;
+    let start = app.plan.selected.saturating_sub(visible.saturating_sub(1));
+    let lines: Vec<Line> = entries
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .map(|(i, (sign, name, color))| {
+            let aur = if app.plan.aur.contains(name) { " (AUR)" } else { "" };
+            let style = if i == app.plan.selected {
+                Style::default().bg(theme.sel_bg).fg(color).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            let cursor = if i == app.plan.selected { "▸" } else { " " };
+            Line::from(vec![
+                Span::styled(format!("{} {} {}", cursor, sign, name), style),
+                Span::styled(aur, Style::default().fg(theme.dim)),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -1,8 +1,8 @@
-use super::input::{self, InputBox, str_insert_char, str_delete_back, str_delete_forward};
+use super::input::{self, handle_emacs_key, InputBox, str_insert_char, str_delete_back, str_delete_forward, EditResult};
+use super::i18n::{t, tf};
 use super::layout;
-use super::state::{App, AppEvent, FileListMode, QueryPanel, QueryView};
-use super::theme::{BLUE, BRIGHT_WHITE, DESC_DIM, DIM, PINK, SEL_BG};
-use crate::package_manager::PackageInfo;
+use super::state::{next_repo_filter, App, AppEvent, AppMode, FileListMode, QueryPanel, QueryView};
+use crate::package_manager::{InstalledPackage, PackageInfo};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
@@ -27,9 +27,31 @@ fn input_box_from_app(app: &App) -> InputBox {
     ib
 }
 
+/// 可选依赖字段名（英文及中文语言环境）
+const OPTDEP_KEYS: [&str; 2] = ["Optional Deps", "可选依赖"];
+
+/// 选中的可选依赖在详情内容中的行号
+fn optdep_line(app: &App) -> Option<usize> {
+    let detail = app.query.detail.as_ref()?;
+    let mut line = 0;
+    for (key, _) in &detail.fields {
+        if OPTDEP_KEYS.contains(&key.as_str()) {
+            return Some(line + app.query.optdep_selected);
+        }
+        line += detail.get_list(&[key.as_str()]).len().max(1);
+    }
+    None
+}
+
 /// 计算详情视图总行数（用于滚动边界）
 pub fn detail_total_lines(app: &App) -> usize {
-    let field_count = app.query.detail.as_ref().map(|d| d.fields.len()).unwrap_or(0);
+    // 多值字段每条占一行
+    let field_count = app.query.detail.as_ref().map(|d| {
+        d.fields
+            .iter()
+            .map(|(key, _)| d.get_list(&[key.as_str()]).len().max(1))
+            .sum()
+    }).unwrap_or(0);
     let list_items = match app.query.file_mode {
         FileListMode::Files => &app.query.files,
         FileListMode::Directories => &app.query.dirs,
@@ -53,7 +75,7 @@ pub fn handle_query_key(
 ) {
     match app.query.view {
         QueryView::List => handle_list_key(key, app, tx),
-        QueryView::Detail => handle_detail_key(key, app, term_height),
+        QueryView::Detail => handle_detail_key(key, app, tx, term_height),
     }
 }
 
@@ -63,10 +85,24 @@ fn handle_list_key(
     app: &mut App,
     tx: &mpsc::Sender<AppEvent>,
 ) {
+    // 除 ↑↓ 外的任何按键都结束历史浏览
+    if !matches!(key.code, KeyCode::Up | KeyCode::Down) {
+        app.query.history_idx = None;
+    }
+
+    match handle_emacs_key(key, &mut app.query.input, &mut app.query.cursor) {
+        EditResult::Changed => {
+            schedule_search(app);
+            return;
+        }
+        EditResult::Moved => return,
+        EditResult::Ignored => {}
+    }
+
     match key.code {
         // Esc 返回 Dashboard
         KeyCode::Esc => {
-            app.mode = super::state::AppMode::Dashboard;
+            app.mode = AppMode::Dashboard;
             app.reset_query_state();
         }
         // Tab 切换面板
@@ -76,6 +112,14 @@ fn handle_list_key(
                 QueryPanel::Remote => QueryPanel::Local,
             };
         }
+        // 输入为空或正在浏览历史时，↑ 调出更早的搜索
+        KeyCode::Up if app.query.input.is_empty() || app.query.history_idx.is_some() => {
+            recall_history(app, true);
+        }
+        // 浏览历史时 ↓ 调出较新的搜索，越过最新一条后回到空输入
+        KeyCode::Down if app.query.history_idx.is_some() => {
+            recall_history(app, false);
+        }
         // 上下选择
         KeyCode::Up => {
             match app.query.panel {
@@ -87,32 +131,40 @@ fn handle_list_key(
                 }
             }
         }
-        KeyCode::Down => {
-            match app.query.panel {
-                QueryPanel::Local => {
-                    let max = app.query.local_results.len().saturating_sub(1);
-                    if app.query.local_selected < max {
-                        app.query.local_selected += 1;
-                    }
-                }
-                QueryPanel::Remote => {
-                    let max = app.query.remote_results.len().saturating_sub(1);
-                    if app.query.remote_selected < max {
-                        app.query.remote_selected += 1;
-                    }
-                }
-            }
-        }
+        KeyCode::Down => move_selection_down(app),
         // Enter 查看详情
         KeyCode::Enter => {
-            let selected_pkg = match app.query.panel {
-                QueryPanel::Local => app.query.local_results.get(app.query.local_selected).cloned(),
-                QueryPanel::Remote => app.query.remote_results.get(app.query.remote_selected).cloned(),
-            };
-            if let Some(pkg) = selected_pkg {
+            if let Some(pkg) = selected_package(app) {
+                let keyword = app.query.input.clone();
+                if crate::history::push(&mut app.query.history, &keyword) {
+                    crate::history::save(crate::history::QUERY_HISTORY_FILE, &app.query.history);
+                }
                 load_package_detail(app, &pkg, tx);
             }
         }
+        // Ctrl+F 按仓库筛选远程结果
+        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.query.repo_filter = next_repo_filter(&app.query.repo_filter, &app.query.remote_all);
+            app.query.apply_repo_filter();
+        }
+        // Space 多选标记，标记后自动下移
+        KeyCode::Char(' ') if app.query.selected_package().is_some() => {
+            app.query.toggle_marked();
+            move_selection_down(app);
+        }
+        // Ctrl+S 安装标记中未安装的包，Ctrl+R 卸载标记中已安装的包
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            let pkgs = app.query.marked_by_installed(false);
+            if !pkgs.is_empty() {
+                jump_to_install(app, tx, pkgs);
+            }
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            let pkgs = app.query.marked_by_installed(true);
+            if !pkgs.is_empty() {
+                jump_to_remove(app, tx, pkgs);
+            }
+        }
         // 文本输入
         KeyCode::Char(c) => {
             // 忽略带 Ctrl/Alt 修饰的字符
@@ -132,10 +184,8 @@ fn handle_list_key(
             str_delete_forward(&mut app.query.input, &mut app.query.cursor);
             schedule_search(app);
         }
-        KeyCode::Left => {
-            if app.query.cursor > 0 {
-                app.query.cursor -= 1;
-            }
+        KeyCode::Left if app.query.cursor > 0 => {
+            app.query.cursor -= 1;
         }
         KeyCode::Right => {
             let char_count = app.query.input.chars().count();
@@ -153,12 +203,58 @@ fn handle_list_key(
     }
 }
 
+/// 当前面板的选中项下移一行
+fn move_selection_down(app: &mut App) {
+    match app.query.panel {
+        QueryPanel::Local => {
+            let max = app.query.local_results.len().saturating_sub(1);
+            if app.query.local_selected < max {
+                app.query.local_selected += 1;
+            }
+        }
+        QueryPanel::Remote => {
+            let max = app.query.remote_results.len().saturating_sub(1);
+            if app.query.remote_selected < max {
+                app.query.remote_selected += 1;
+            }
+        }
+    }
+}
+
+/// 调出上一条（older）或下一条搜索历史并重新搜索
+fn recall_history(app: &mut App, older: bool) {
+    if app.query.history.is_empty() {
+        return;
+    }
+    let idx = match (app.query.history_idx, older) {
+        (None, true) => app.query.history.len() - 1,
+        (None, false) => return,
+        (Some(i), true) => i.saturating_sub(1),
+        (Some(i), false) if i + 1 >= app.query.history.len() => {
+            app.query.history_idx = None;
+            app.query.input.clear();
+            app.query.cursor = 0;
+            schedule_search(app);
+            return;
+        }
+        (Some(i), false) => i + 1,
+    };
+    app.query.history_idx = Some(idx);
+    app.query.input = app.query.history[idx].clone();
+    app.query.cursor = app.query.input.chars().count();
+    app.query.local_selected = 0;
+    app.query.remote_selected = 0;
+    schedule_search(app);
+}
+
 /// 计划异步搜索（防抖）
-fn schedule_search(app: &mut App) {
+pub fn schedule_search(app: &mut App) {
     let keyword = app.query.input.clone();
     if keyword.trim().is_empty() {
         app.query.local_results.clear();
         app.query.remote_results.clear();
+        app.query.remote_all.clear();
+        app.query.remote_error = None;
         app.query.local_selected = 0;
         app.query.remote_selected = 0;
         app.query.searching = false;
@@ -200,10 +296,11 @@ pub fn execute_pending_search(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
     // 远程搜索
     let tx_remote = tx.clone();
     let kw_remote = keyword;
+    let cancel = app.query.search_cancel.clone();
     tokio::spawn(async move {
-        let results = tokio::task::spawn_blocking(move || pm.search_remote(&kw_remote))
+        let results = tokio::task::spawn_blocking(move || pm.search_remote(&kw_remote, &cancel))
             .await
-            .unwrap_or_default();
+            .unwrap_or_else(|e| Err(e.into()));
         let _ = tx_remote.send(AppEvent::QueryRemoteResults { results, seq }).await;
     });
 }
@@ -254,8 +351,18 @@ fn load_package_detail(app: &App, pkg: &PackageInfo, tx: &mpsc::Sender<AppEvent>
 
         match detail_result {
             Ok(Ok(detail)) => {
+                // -Si 不标注可选依赖是否已安装，用本地包名补充
+                let mut optdeps = crate::package_manager::parse_optional_deps(&detail);
+                if optdeps.iter().any(|d| !d.installed) {
+                    let installed = tokio::task::spawn_blocking(move || pm.installed_names())
+                        .await
+                        .unwrap_or_default();
+                    for dep in &mut optdeps {
+                        dep.installed |= installed.contains(&dep.name);
+                    }
+                }
                 let _ = tx_clone
-                    .send(AppEvent::QueryDetailLoaded { detail, files, dirs })
+                    .send(AppEvent::QueryDetailLoaded { detail, files, dirs, optdeps })
                     .await;
             }
             Ok(Err(e)) => {
@@ -273,35 +380,41 @@ fn load_package_detail(app: &App, pkg: &PackageInfo, tx: &mpsc::Sender<AppEvent>
 }
 
 /// 详情视图按键处理
-fn handle_detail_key(key: KeyEvent, app: &mut App, term_height: u16) {
+fn handle_detail_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    term_height: u16,
+) {
     let total = detail_total_lines(app);
     let visible = term_height.saturating_sub(8) as usize;
     let max_scroll = total.saturating_sub(visible);
 
+    app.query.message = None;
     match key.code {
         KeyCode::Esc => {
             app.query.view = QueryView::List;
             app.query.detail = None;
             app.query.files.clear();
             app.query.dirs.clear();
+            app.query.optdeps.clear();
             app.query.file_mode = FileListMode::Files;
             app.query.detail_scroll = 0;
+            app.query.file_mode_scroll = 0;
         }
         KeyCode::Tab => {
-            // 切换文件/目录视图
+            // 切换文件/目录视图，两个列表各自保留滚动位置
             app.query.file_mode = match app.query.file_mode {
                 FileListMode::Files => FileListMode::Directories,
                 FileListMode::Directories => FileListMode::Files,
             };
-            app.query.detail_scroll = 0;
+            std::mem::swap(&mut app.query.detail_scroll, &mut app.query.file_mode_scroll);
         }
         KeyCode::Up => {
             app.query.detail_scroll = app.query.detail_scroll.saturating_sub(1);
         }
-        KeyCode::Down => {
-            if app.query.detail_scroll < max_scroll {
-                app.query.detail_scroll += 1;
-            }
+        KeyCode::Down if app.query.detail_scroll < max_scroll => {
+            app.query.detail_scroll += 1;
         }
         KeyCode::PageUp => {
             app.query.detail_scroll = app.query.detail_scroll.saturating_sub(10);
@@ -309,10 +422,139 @@ fn handle_detail_key(key: KeyEvent, app: &mut App, term_height: u16) {
         KeyCode::PageDown => {
             app.query.detail_scroll = (app.query.detail_scroll + 10).min(max_scroll);
         }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.query.detail_scroll = 0;
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.query.detail_scroll = max_scroll;
+        }
+        KeyCode::Char('o') => {
+            app.query.message = Some(match open_homepage(app) {
+                Ok(url) => format!("✓ 已在浏览器中打开 {}", url),
+                Err(e) => format!("✗ {}", e),
+            });
+        }
+        // [ ] 选择可选依赖，Enter 安装选中的可选依赖
+        KeyCode::Char('[') | KeyCode::Char(']') if !app.query.optdeps.is_empty() => {
+            let last = app.query.optdeps.len() - 1;
+            app.query.optdep_selected = if key.code == KeyCode::Char('[') {
+                app.query.optdep_selected.saturating_sub(1)
+            } else {
+                (app.query.optdep_selected + 1).min(last)
+            };
+            // 保证选中行可见
+            if let Some(line) = optdep_line(app) {
+                if line < app.query.detail_scroll {
+                    app.query.detail_scroll = line;
+                } else if line >= app.query.detail_scroll + visible {
+                    app.query.detail_scroll = (line + 1).saturating_sub(visible).min(max_scroll);
+                }
+            }
+        }
+        KeyCode::Enter => match app.query.optdeps.get(app.query.optdep_selected) {
+            Some(dep) if dep.installed => {
+                app.query.message = Some(format!("✗ {} 已安装", dep.name));
+            }
+            Some(dep) => {
+                let pkg = PackageInfo {
+                    repo: String::new(),
+                    name: dep.name.clone(),
+                    version: String::new(),
+                    description: dep.description.clone(),
+                    installed: false,
+                };
+                jump_to_install(app, tx, vec![pkg]);
+            }
+            None => {}
+        },
+        // i 安装未安装的包，r 卸载已安装的包，直接进入预览
+        KeyCode::Char('i') => match selected_package(app) {
+            Some(pkg) if !pkg.installed => jump_to_install(app, tx, vec![pkg]),
+            Some(_) => app.query.message = Some("✗ 该包已安装".to_string()),
+            None => {}
+        },
+        KeyCode::Char('r') => match selected_package(app) {
+            Some(pkg) if pkg.installed => jump_to_remove(app, tx, vec![pkg]),
+            Some(_) => app.query.message = Some("✗ 该包未安装".to_string()),
+            None => {}
+        },
         _ => {}
     }
 }
 
+/// 当前面板中选中的包
+fn selected_package(app: &App) -> Option<PackageInfo> {
+    app.query.selected_package().cloned()
+}
+
+/// 切换到安装模式并直接获取这些包的安装预览
+fn jump_to_install(app: &mut App, tx: &mpsc::Sender<AppEvent>, pkgs: Vec<PackageInfo>) {
+    let names: Vec<String> = pkgs.iter().map(|p| p.name.clone()).collect();
+    app.mode = AppMode::Install;
+    app.reset_install_state();
+    if let [name] = names.as_slice() {
+        app.install.input = name.clone();
+        app.install.cursor = name.chars().count();
+    }
+    app.install.set_results(pkgs);
+    app.install.marked.extend(names.iter().cloned());
+    super::install::start_install_flow(app, tx, names);
+}
+
+/// 切换到卸载模式并直接获取这些包的卸载预览
+fn jump_to_remove(app: &mut App, tx: &mpsc::Sender<AppEvent>, pkgs: Vec<PackageInfo>) {
+    // 详情视图中只有一个包，可从详情字段取安装大小
+    let size = match app.query.view {
+        QueryView::Detail => app.query.detail.as_ref()
+            .and_then(|d| d.get(&["Installed Size", "安装大小", "安装后大小"]))
+            .unwrap_or_default()
+            .to_string(),
+        QueryView::List => String::new(),
+    };
+    let names: Vec<String> = pkgs.iter().map(|p| p.name.clone()).collect();
+    app.mode = AppMode::Remove;
+    app.reset_remove_state();
+    if let [name] = names.as_slice() {
+        app.remove.input = name.clone();
+        app.remove.cursor = name.chars().count();
+    }
+    app.remove.packages = pkgs
+        .into_iter()
+        .map(|pkg| InstalledPackage {
+            name: pkg.name,
+            version: pkg.version,
+            size: size.clone(),
+            description: pkg.description,
+            install_date: None,
+        })
+        .collect();
+    if names.len() > 1 {
+        app.remove.marked = (0..names.len()).collect();
+    }
+    app.remove.apply_filter();
+    super::remove::start_remove_preview(app, tx, names);
+}
+
+/// 用 xdg-open 打开当前包的主页
+fn open_homepage(app: &App) -> anyhow::Result<String> {
+    let url = app.query.detail.as_ref()
+        .and_then(|d| d.get(&["URL", "网址"]))
+        .map(str::trim)
+        .filter(|u| !u.is_empty() && *u != "None")
+        .ok_or_else(|| anyhow::anyhow!("该包没有 URL 字段"))?;
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        anyhow::bail!("不是 http(s) 地址: {}", url);
+    }
+    std::process::Command::new("xdg-open")
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("无法启动 xdg-open: {}", e))?;
+    Ok(url.to_string())
+}
+
 // ===== 渲染 =====
 
 /// 渲染查询视图
@@ -337,7 +579,10 @@ fn render_list_view(f: &mut Frame, app: &App) {
         .split(area);
 
     // Header
-    layout::render_header(f, "🔍 查询软件包 (Shift+Q)", chunks[0]);
+    match &app.query.repo_filter {
+        Some(repo) => layout::render_header(f, &tf("query.title.repo", &[repo]), chunks[0]),
+        None => layout::render_header(f, t("query.title"), chunks[0]),
+    }
 
     // 输入框
     let input = input_box_from_app(app);
@@ -357,6 +602,7 @@ fn render_list_view(f: &mut Frame, app: &App) {
         app.query.local_selected,
         app.query.panel == QueryPanel::Local,
         panels[0],
+        app,
     );
 
     // 远程面板
@@ -367,15 +613,35 @@ fn render_list_view(f: &mut Frame, app: &App) {
         app.query.remote_selected,
         app.query.panel == QueryPanel::Remote,
         panels[1],
+        app,
     );
 
     // Footer
-    let footer_text = if app.query.searching {
-        "搜索中... | Tab 切换面板 | ↑↓ 选择 | Enter 查看详情 | Esc 返回"
+    let owned_footer: String;
+    let footer_text = if !app.query.marked.is_empty() {
+        let not_installed = app.query.marked.values().filter(|p| !p.installed).count();
+        owned_footer = tf(
+            "query.footer.marked",
+            &[
+                &app.query.marked.len(),
+                &not_installed,
+                &(app.query.marked.len() - not_installed),
+            ],
+        );
+        &owned_footer
+    } else if app.query.searching {
+        t("query.footer.searching")
+    } else if app.query.history_idx.is_some() {
+        t("query.footer.history")
+    } else if app.query.input.is_empty() && !app.query.history.is_empty() {
+        t("query.footer.empty")
     } else {
-        "输入关键词搜索 | Tab 切换面板 | ↑↓ 选择 | Enter 查看详情 | Esc 返回"
+        t("query.footer.list")
     };
-    layout::render_footer(f, footer_text, chunks[3]);
+    match &app.query.remote_error {
+        Some(err) => layout::render_footer(f, &tf("query.footer.remote_error", &[err, &footer_text]), chunks[3]),
+        None => layout::render_footer(f, footer_text, chunks[3]),
+    }
 }
 
 /// 渲染搜索结果面板
@@ -386,7 +652,10 @@ fn render_result_panel(
     selected: usize,
     focused: bool,
     area: Rect,
+    app: &App,
 ) {
+    let theme = app.theme();
+    let marked = &app.query.marked;
     let border_color = if focused { Color::Yellow } else { Color::DarkGray };
     let block = Block::default()
         .title(title)
@@ -425,25 +694,36 @@ fn render_result_panel(
     for (i, pkg) in results.iter().enumerate().skip(scroll).take(visible_items) {
         let is_selected = i == selected && focused;
         let marker = if is_selected { "► " } else { "  " };
+        let is_marked = marked.contains_key(&pkg.name);
+        // 有标记时未标记的行留出等宽空白以对齐
+        let check = match (is_marked, marked.is_empty()) {
+            (true, _) => "[✓] ",
+            (false, false) => "    ",
+            (false, true) => "",
+        };
+        let marker = format!("{}{}", marker, check);
         let installed_mark = if pkg.installed { " [已安装]" } else { "" };
 
         // 第一行：包名 + 版本
         if is_selected {
-            let bg = Style::default().bg(SEL_BG);
+            let bg = Style::default().bg(theme.sel_bg);
             lines.push(Line::from(vec![
-                Span::styled(marker.to_string(), bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD)),
-                Span::styled(format!("{}/", pkg.repo), bg.fg(PINK).add_modifier(Modifier::BOLD)),
-                Span::styled(pkg.name.clone(), bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD)),
-                Span::styled(format!(" {}", pkg.version), bg.fg(BLUE)),
-                Span::styled(installed_mark.to_string(), bg.fg(DIM)),
+                Span::styled(marker, bg.fg(theme.text).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{}/", pkg.repo), bg.fg(theme.primary).add_modifier(Modifier::BOLD)),
+                Span::styled(pkg.name.clone(), bg.fg(theme.text).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" {}", pkg.version), bg.fg(theme.secondary)),
+                Span::styled(installed_mark.to_string(), bg.fg(theme.dim)),
             ]));
         } else {
             lines.push(Line::from(vec![
-                Span::styled(marker.to_string(), Style::default().fg(Color::White)),
-                Span::styled(format!("{}/", pkg.repo), Style::default().fg(PINK)),
-                Span::styled(pkg.name.clone(), Style::default().fg(BLUE)),
+                Span::styled(marker, Style::default().fg(if is_marked { theme.primary } else { Color::White })),
+                Span::styled(format!("{}/", pkg.repo), Style::default().fg(theme.primary)),
+                Span::styled(
+                    pkg.name.clone(),
+                    Style::default().fg(if is_marked { theme.primary } else { theme.secondary }),
+                ),
                 Span::styled(format!(" {}", pkg.version), Style::default().fg(Color::White)),
-                Span::styled(installed_mark.to_string(), Style::default().fg(DIM)),
+                Span::styled(installed_mark.to_string(), Style::default().fg(theme.dim)),
             ]));
         }
 
@@ -456,7 +736,7 @@ fn render_result_panel(
         if is_selected {
             lines.push(Line::from(Span::styled(
                 format!("    {}", desc),
-                Style::default().bg(SEL_BG).fg(DESC_DIM),
+                Style::default().bg(theme.sel_bg).fg(theme.desc_dim),
             )));
         } else {
             lines.push(Line::from(Span::styled(
@@ -503,21 +783,35 @@ fn render_detail_view(f: &mut Frame, app: &App) {
         .as_ref()
         .and_then(|d| d.fields.first().map(|(_, v): &(String, String)| v.as_str()))
         .unwrap_or("未知");
-    layout::render_header(f, &format!("📦 包信息 - {}", pkg_name), chunks[0]);
+    layout::render_header(f, &tf("query.title.detail", &[&pkg_name]), chunks[0]);
 
     // 详情内容
     render_detail_content(f, app, chunks[1]);
 
     // Footer
-    let footer_text = if app.query.files.is_empty() && app.query.dirs.is_empty() {
-        "↑↓ 滚动 | PgUp/PgDn 翻页 | Esc 返回列表"
+    // i / r 提示与按键处理一致，按包是否已安装决定（文件列表可能为空或获取失败）
+    let installed = app.query.selected_package().is_some_and(|p| p.installed);
+    let footer_text = if !installed {
+        t("query.footer.detail")
+    } else if app.query.files.is_empty() && app.query.dirs.is_empty() {
+        t("query.footer.detail_installed")
     } else {
         match app.query.file_mode {
-            FileListMode::Files => "↑↓ 滚动 | PgUp/PgDn 翻页 | Tab 切换目录视图 | Esc 返回列表",
-            FileListMode::Directories => "↑↓ 滚动 | PgUp/PgDn 翻页 | Tab 切换文件视图 | Esc 返回列表",
+            FileListMode::Files => t("query.footer.detail_files"),
+            FileListMode::Directories => t("query.footer.detail_dirs"),
         }
     };
-    layout::render_footer(f, footer_text, chunks[2]);
+    let owned_footer: String;
+    let footer_text = if app.query.optdeps.iter().any(|d| !d.installed) {
+        owned_footer = tf("query.footer.optdeps", &[&footer_text]);
+        &owned_footer
+    } else {
+        footer_text
+    };
+    match &app.query.message {
+        Some(msg) => layout::render_footer(f, &format!("{} | {}", msg, footer_text), chunks[2]),
+        None => layout::render_footer(f, footer_text, chunks[2]),
+    }
 }
 
 /// 渲染详情内容区域
@@ -538,20 +832,45 @@ fn render_detail_content(f: &mut Frame, app: &App, area: Rect) {
 
     // 包信息字段（CJK 对齐）
     if let Some(detail) = &app.query.detail {
+        let theme = app.theme();
         for (key, value) in &detail.fields {
             let key_width = UnicodeWidthStr::width(key.as_str());
             let target_width: usize = 18;
             let pad = target_width.saturating_sub(key_width);
             let padded_key = format!("{}{} ", key, " ".repeat(pad));
-            all_lines.push(Line::from(vec![
-                Span::styled(
-                    padded_key,
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(value.clone(), Style::default().fg(Color::White)),
-            ]));
+            // 多值字段（依赖、可选依赖等）每行一条
+            let items = detail.get_list(&[key.as_str()]);
+            let is_optdeps = OPTDEP_KEYS.contains(&key.as_str());
+            let item_spans = |i: usize, text: &str| -> Vec<Span<'static>> {
+                match app.query.optdeps.get(i).filter(|_| is_optdeps) {
+                    Some(dep) => {
+                        let mark = if dep.installed { "✓ " } else { "○ " };
+                        let style = if i == app.query.optdep_selected {
+                            Style::default().bg(theme.sel_bg).fg(theme.text).add_modifier(Modifier::BOLD)
+                        } else if dep.installed {
+                            Style::default().fg(theme.dim)
+                        } else {
+                            Style::default().fg(Color::White)
+                        };
+                        vec![Span::styled(format!("{}{}", mark, text), style)]
+                    }
+                    None => vec![Span::styled(text.to_string(), Style::default().fg(Color::White))],
+                }
+            };
+            let first = items.first().unwrap_or(value);
+            let mut spans = vec![Span::styled(
+                padded_key,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )];
+            spans.extend(item_spans(0, first));
+            all_lines.push(Line::from(spans));
+            for (i, item) in items.iter().enumerate().skip(1) {
+                let mut spans = vec![Span::raw(" ".repeat(target_width + 1))];
+                spans.extend(item_spans(i, item));
+                all_lines.push(Line::from(spans));
+            }
         }
     }
 
@@ -1,8 +1,8 @@
 use super::input::InputBox;
+use super::i18n::{t, tf};
 use super::layout;
-use super::state::{App, AppEvent, AppMode, RemovePhase, ViewMode};
-use super::theme::{BLUE, BRIGHT_WHITE, DESC_DIM, DIM, PINK, SEL_BG};
-use crate::tui::input::{str_insert_char, str_delete_back, str_delete_forward};
+use super::state::{follow_label, App, AppEvent, AppMode, RemovePhase, SortKey, ViewMode};
+use crate::tui::input::{handle_emacs_key, str_insert_char, str_delete_back, str_delete_forward, EditResult};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Margin},
@@ -36,7 +36,7 @@ pub fn handle_remove_key(
 ) -> bool {
     match app.remove.phase {
         RemovePhase::Browsing => handle_browsing_key(key, app, tx),
-        RemovePhase::PreviewingRemove => handle_preview_key(key, app),
+        RemovePhase::PreviewingRemove => handle_preview_key(key, app, tx),
         RemovePhase::Removing => handle_output_key(key, app, term_height),
         RemovePhase::RemoveComplete => handle_output_key(key, app, term_height),
         RemovePhase::Analyzing => handle_output_key(key, app, term_height),
@@ -51,6 +51,14 @@ fn handle_browsing_key(
     app: &mut App,
     tx: &mpsc::Sender<AppEvent>,
 ) -> bool {
+    match handle_emacs_key(key, &mut app.remove.input, &mut app.remove.cursor) {
+        EditResult::Changed => {
+            app.remove.apply_filter();
+            return true;
+        }
+        EditResult::Moved => return true,
+        EditResult::Ignored => {}
+    }
     match key.code {
         KeyCode::Esc => {
             app.mode = AppMode::Dashboard;
@@ -91,25 +99,20 @@ fn handle_browsing_key(
             if !app.remove.filtered.is_empty() {
                 let packages = collect_selected_packages(app);
                 if !packages.is_empty() {
-                    if let Some(pm) = app.package_manager.clone() {
-                        let tx_clone = tx.clone();
-                        let pkgs = packages.clone();
-                        tokio::spawn(async move {
-                            let preview = tokio::task::spawn_blocking(move || {
-                                pm.preview_remove(&pkgs)
-                            })
-                            .await
-                            .unwrap_or_default();
-                            let _ = tx_clone.send(AppEvent::RemovePreviewReady(preview)).await;
-                        });
-                        app.remove.phase = RemovePhase::PreviewingRemove;
-                        app.remove.preview = vec!["正在获取卸载预览...".to_string()];
-                        app.remove.scroll = 0;
-                    }
+                    start_remove_preview(app, tx, packages);
                 }
             }
             true
         }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            super::plan::add_from_remove(app);
+            true
+        }
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.remove.sort = app.remove.sort.next_in(&SortKey::REMOVE);
+            app.remove.apply_filter();
+            true
+        }
         KeyCode::Backspace => {
             str_delete_back(&mut app.remove.input, &mut app.remove.cursor);
             app.remove.apply_filter();
@@ -153,11 +156,37 @@ fn handle_browsing_key(
     }
 }
 
+/// 获取卸载预览并进入预览阶段
+pub fn start_remove_preview(app: &mut App, tx: &mpsc::Sender<AppEvent>, packages: Vec<String>) {
+    if let Some(pm) = app.package_manager.clone() {
+        let tx_clone = tx.clone();
+        let variant = app.remove.variant;
+        tokio::spawn(async move {
+            let preview = tokio::task::spawn_blocking(move || pm.preview_remove(&packages, variant))
+                .await
+                .unwrap_or_default();
+            let _ = tx_clone.send(AppEvent::RemovePreviewReady(preview)).await;
+        });
+        app.remove.phase = RemovePhase::PreviewingRemove;
+        app.remove.protected_confirmed = false;
+        app.remove.preview = vec!["正在获取卸载预览...".to_string()];
+        app.remove.scroll = 0;
+    }
+}
+
 /// 预览状态按键处理
-fn handle_preview_key(key: KeyEvent, app: &mut App) -> bool {
+fn handle_preview_key(key: KeyEvent, app: &mut App, tx: &mpsc::Sender<AppEvent>) -> bool {
     match key.code {
+        KeyCode::Char('m') => {
+            // 切换卸载方式并重新获取预览
+            app.remove.variant = app.remove.variant.next();
+            let packages = collect_selected_packages(app);
+            start_remove_preview(app, tx, packages);
+            true
+        }
         KeyCode::Esc => {
             app.remove.phase = RemovePhase::Browsing;
+            app.remove.protected_confirmed = false;
             app.remove.preview.clear();
             app.remove.scroll = 0;
             true
@@ -177,12 +206,17 @@ fn handle_preview_key(key: KeyEvent, app: &mut App) -> bool {
 
 /// 输出状态按键处理
 fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
+    let content = app.remove.get_content();
+    if app.remove.search.handle_key(key, &content, &mut app.remove.scroll) {
+        return true;
+    }
     match key.code {
         KeyCode::Esc => {
             match app.remove.phase {
                 RemovePhase::Removing | RemovePhase::Analyzing => {
                     // 进行中：取消并返回浏览
                     crate::package_manager::cancel_update();
+                    app.remove.search.clear();
                     app.remove.phase = RemovePhase::Browsing;
                     app.remove.scroll = 0;
                 }
@@ -194,14 +228,16 @@ fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
             }
             true
         }
+        KeyCode::Char('f') if app.remove.phase == RemovePhase::Removing => {
+            app.remove.toggle_follow();
+            true
+        }
         KeyCode::Char('y') => {
-            let content = app.remove.get_content();
-            let text = content.join("\n");
-            if layout::copy_to_clipboard(&text) {
-                app.remove.progress = "✓ 已复制到剪贴板".to_string();
-            } else {
-                app.remove.progress = "复制失败 (请确认已安装 wl-copy/xclip/xsel)".to_string();
-            }
+            let text = layout::plain_text(&app.remove.get_content());
+            app.remove.progress = match layout::copy_to_clipboard(&text) {
+                Ok(()) => "✓ 已复制到剪贴板".to_string(),
+                Err(e) => format!("复制失败: {}", e),
+            };
             true
         }
         KeyCode::Up => {
@@ -228,6 +264,16 @@ fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
             app.remove.scroll = (app.remove.scroll + 10).min(max_scroll);
             true
         }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.remove.scroll = 0;
+            true
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            let content = app.remove.get_content();
+            let visible = layout::visible_content_height(term_height);
+            app.remove.scroll = content.len().saturating_sub(visible);
+            true
+        }
         _ => false,
     }
 }
@@ -235,12 +281,15 @@ fn handle_output_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
 /// 完成状态按键处理
 fn handle_complete_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
     match key.code {
-        KeyCode::Tab => {
-            app.remove.view_mode = match app.remove.view_mode {
-                ViewMode::UpdateLog => ViewMode::AIAnalysis,
-                ViewMode::AIAnalysis => ViewMode::UpdateLog,
-            };
-            app.remove.scroll = 0;
+        KeyCode::Tab if !app.remove.search.is_editing() => {
+            app.remove.toggle_view();
+            app.remove.search.clear();
+            true
+        }
+        KeyCode::Char('r')
+            if app.remove.view_mode == ViewMode::AIAnalysis && !app.remove.search.is_editing() =>
+        {
+            app.markdown_raw = !app.markdown_raw;
             true
         }
         _ => handle_output_key(key, app, term_height),
@@ -248,7 +297,7 @@ fn handle_complete_key(key: KeyEvent, app: &mut App, term_height: u16) -> bool {
 }
 
 /// 收集选中的包名列表
-fn collect_selected_packages(app: &App) -> Vec<String> {
+pub fn collect_selected_packages(app: &App) -> Vec<String> {
     if app.remove.marked.is_empty() {
         // 没有多选标记，使用当前高亮项对应的原始索引
         if let Some(&real_idx) = app.remove.filtered.get(app.remove.selected) {
@@ -281,8 +330,10 @@ pub fn spawn_remove_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
         return;
     }
 
+    let variant = app.remove.variant;
     let tx_clone = tx.clone();
     app.remove.phase = RemovePhase::Removing;
+    app.remove.search.clear();
     app.remove.lines.clear();
     app.remove.lines.push(format!(
         "正在卸载: {} ...",
@@ -303,7 +354,7 @@ pub fn spawn_remove_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
             }
         });
 
-        let result = pm.remove_streaming(&packages, output_tx);
+        let result = pm.remove_streaming(&packages, variant, output_tx);
 
         match result {
             Ok(output) => {
@@ -331,30 +382,24 @@ pub fn handle_remove_complete(
             let sys_info = app.system_info.clone();
             let packages = collect_selected_packages(app);
 
-            let prompt_text = format!(
-                "以下是在 {} 系统上使用 {} -Rns 卸载软件包的日志。\n\
-                 卸载的包: {}\n\n\
-                 卸载日志:\n{}\n\n\
-                 请简要分析卸载结果，说明是否成功，移除了哪些包及其依赖和配置，是否有需要注意的问题。",
-                sys_info.as_ref().map(|i| i.distro.as_str()).unwrap_or("Linux"),
-                pm_name,
-                packages.join(", "),
-                remove_log
+            let prompt_text = crate::prompt::generate_remove_prompt(
+                &pm_name,
+                &packages,
+                app.remove.variant.flag(),
+                &remove_log,
+                sys_info.as_ref(),
+                &app.config.analysis_language,
             );
 
-            let client = crate::deepseek::AiClient::new(
-                api_key.to_string(),
-                app.config.get_api_url().to_string(),
-                app.config.proxy.as_deref(),
-            );
-            let model = app.config.model.clone();
+            let client = crate::deepseek::AiClient::from_config(api_key, &app.config);
+            let model = app.config.get_model().to_string();
             let temperature = app.config.temperature;
             let tx_clone = tx.clone();
 
             tokio::spawn(async move {
                 match client.analyze_update(&prompt_text, &model, temperature).await {
-                    Ok(analysis) => {
-                        let _ = tx_clone.send(AppEvent::RemoveAnalysisComplete(analysis)).await;
+                    Ok((analysis, usage)) => {
+                        let _ = tx_clone.send(AppEvent::RemoveAnalysisComplete { analysis, usage }).await;
                     }
                     Err(e) => {
                         let _ = tx_clone
@@ -375,21 +420,25 @@ pub fn handle_remove_complete(
 pub fn handle_remove_analysis_complete(
     app: &mut App,
     analysis: String,
+    usage: Option<crate::deepseek::TokenUsage>,
     tx: &mpsc::Sender<AppEvent>,
 ) {
     app.remove.analysis = Some(analysis.clone());
+    app.remove.token_usage = usage;
     app.remove.phase = RemovePhase::AnalysisComplete;
-    app.remove.view_mode = ViewMode::AIAnalysis;
-    app.remove.scroll = 0;
+    app.remove.show_analysis();
 
-    let report_dir = app.config.report_dir.clone();
+    let saver = crate::report::ReportSaver::from_config(&app.config);
+    let log = app.remove.output.as_ref()
+        .map(|o| o.combined_output())
+        .unwrap_or_default();
     let distro_name = app.system_info.as_ref()
         .map(|info| info.distro.clone())
         .unwrap_or_else(|| "Linux".to_string());
+    let operation = app.remove.variant.operation();
     let tx_clone = tx.clone();
     tokio::spawn(async move {
-        let saver = crate::report::ReportSaver::new(report_dir);
-        match saver.save(&analysis, &distro_name, "Rns") {
+        match saver.save(&analysis, &log, &distro_name, operation) {
             Ok(path) => {
                 let _ = tx_clone
                     .send(AppEvent::ReportSaved(path.display().to_string()))
@@ -418,7 +467,15 @@ fn render_browse_view(f: &mut Frame, app: &App) {
     let chunks = layout::main_layout(f.area());
 
     // Header
-    layout::render_header(f, "🗑️  卸载软件包 (-Rns)", chunks[0]);
+    let title = if app.remove.orphans {
+        t("remove.title.orphans")
+    } else if app.remove.largest {
+        t("remove.title.largest")
+    } else {
+        t("remove.title.remove")
+    };
+    let header_text = format!("🗑️  {} ({})", title, app.remove.variant.flag());
+    layout::render_header(f, &header_text, chunks[0]);
 
     // Content
     let content_block = Block::default()
@@ -437,10 +494,17 @@ fn render_browse_view(f: &mut Frame, app: &App) {
     }
 
     if app.remove.loading {
-        let loading = Paragraph::new("正在加载已安装包列表...")
+        let loading_text = if app.remove.orphans {
+            "正在查找孤立包..."
+        } else if app.remove.largest {
+            "正在统计各包占用空间..."
+        } else {
+            "正在加载已安装包列表..."
+        };
+        let loading = Paragraph::new(loading_text)
             .style(Style::default().fg(Color::Yellow));
         f.render_widget(loading, padded);
-        layout::render_footer(f, "加载中...", chunks[2]);
+        layout::render_footer(f, t("common.loading"), chunks[2]);
         return;
     }
 
@@ -463,8 +527,15 @@ fn render_browse_view(f: &mut Frame, app: &App) {
 
     // 统计行
     let stat_text = format!(
-        "共 {} 个匹配 / 已安装 {} 个",
+        "共 {} 个匹配 / {} {} 个",
         app.remove.filtered.len(),
+        if app.remove.orphans {
+            "孤立包"
+        } else if app.remove.largest {
+            "最大的"
+        } else {
+            "已安装"
+        },
         app.remove.packages.len()
     );
     let stat_line = Paragraph::new(stat_text)
@@ -475,18 +546,25 @@ fn render_browse_view(f: &mut Frame, app: &App) {
     render_package_list(f, app, inner_chunks[2]);
 
     // Footer
-    let footer = if app.remove.filtered.is_empty() {
-        "输入关键词筛选已安装包 | Esc 返回"
+    let footer = if app.remove.orphans && app.remove.packages.is_empty() {
+        t("remove.footer.no_orphans")
+    } else if app.remove.filtered.is_empty() {
+        t("remove.footer.empty")
     } else if app.remove.marked.is_empty() {
-        "↑↓ 选择 | Space 多选 | Enter 卸载选中 | Esc 返回"
+        &tf("remove.footer.list", &[&app.remove.sort.label()])
     } else {
-        "↑↓ 选择 | Space 多选/取消 | Enter 卸载标记项 | Esc 返回"
+        &tf("remove.footer.marked", &[&app.remove.sort.label()])
     };
-    layout::render_footer(f, footer, chunks[2]);
+    if app.remove.progress.is_empty() {
+        layout::render_footer(f, footer, chunks[2]);
+    } else {
+        layout::render_footer(f, &format!("{} | {}", app.remove.progress, footer), chunks[2]);
+    }
 }
 
 /// 渲染已安装包列表
 fn render_package_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme();
     if app.remove.filtered.is_empty() {
         if !app.remove.input.is_empty() {
             let hint = Paragraph::new("  未找到匹配的包")
@@ -500,7 +578,7 @@ fn render_package_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let total = app.remove.filtered.len();
 
     let scroll = if app.remove.selected >= visible_height {
-        app.remove.selected.saturating_sub(visible_height - 1)
+        app.remove.selected.saturating_sub(visible_height.saturating_sub(1))
     } else {
         0
     };
@@ -517,6 +595,14 @@ fn render_package_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         })
         .max()
         .unwrap_or(20);
+    let max_size_width = app.remove.filtered
+        .iter()
+        .skip(scroll)
+        .take(visible_height)
+        .filter_map(|&idx| app.remove.packages.get(idx))
+        .map(|pkg| UnicodeWidthStr::width(pkg.size.as_str()))
+        .max()
+        .unwrap_or(0);
 
     let lines: Vec<Line> = app.remove.filtered
         .iter()
@@ -535,33 +621,41 @@ fn render_package_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             let ver_width = UnicodeWidthStr::width(pkg.version.as_str());
             let name_ver_width = name_width + 1 + ver_width; // +1 for space
             let padding = max_name_width.saturating_sub(name_ver_width) + 2;
+            // 大小右对齐，后接安装日期列
+            let size_date = format!(
+                "{}{:>width$}  {}",
+                " ".repeat(padding),
+                pkg.size,
+                pkg.install_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                width = max_size_width,
+            );
 
             // MTF flag colors from theme
 
             if is_selected {
                 // 选中行：深色背景 + 多色加粗
-                let bg = Style::default().bg(SEL_BG);
+                let bg = Style::default().bg(theme.sel_bg);
                 Line::from(vec![
-                    Span::styled(format!("{}{}", cursor, marker), bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD)),
-                    Span::styled(pkg.name.clone(), bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD)),
-                    Span::styled(format!(" {}", pkg.version), bg.fg(BLUE)),
-                    Span::styled(format!("{}{}", " ".repeat(padding), pkg.size), bg.fg(DESC_DIM)),
+                    Span::styled(format!("{}{}", cursor, marker), bg.fg(theme.text).add_modifier(Modifier::BOLD)),
+                    Span::styled(pkg.name.clone(), bg.fg(theme.text).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" {}", pkg.version), bg.fg(theme.secondary)),
+                    Span::styled(size_date, bg.fg(theme.desc_dim)),
                 ])
             } else if is_marked {
                 // 标记行：粉色标识
                 Line::from(vec![
-                    Span::styled(format!("{}{}", cursor, marker), Style::default().fg(PINK)),
-                    Span::styled(pkg.name.clone(), Style::default().fg(PINK)),
+                    Span::styled(format!("{}{}", cursor, marker), Style::default().fg(theme.primary)),
+                    Span::styled(pkg.name.clone(), Style::default().fg(theme.primary)),
                     Span::styled(format!(" {}", pkg.version), Style::default().fg(Color::White)),
-                    Span::styled(format!("{}{}", " ".repeat(padding), pkg.size), Style::default().fg(DIM)),
+                    Span::styled(size_date, Style::default().fg(theme.dim)),
                 ])
             } else {
                 // 正常行：名称蓝色，版本白色，大小灰色
                 Line::from(vec![
                     Span::styled(format!("{}{}", cursor, marker), Style::default().fg(Color::White)),
-                    Span::styled(pkg.name.clone(), Style::default().fg(BLUE)),
+                    Span::styled(pkg.name.clone(), Style::default().fg(theme.secondary)),
                     Span::styled(format!(" {}", pkg.version), Style::default().fg(Color::White)),
-                    Span::styled(format!("{}{}", " ".repeat(padding), pkg.size), Style::default().fg(DIM)),
+                    Span::styled(size_date, Style::default().fg(theme.dim)),
                 ])
             }
         })
@@ -589,11 +683,7 @@ fn render_preview_view(f: &mut Frame, app: &App) {
     let chunks = layout::main_layout(f.area());
 
     let packages = collect_selected_packages(app);
-    let header_text = format!(
-        "🗑️  卸载预览 - {} 个包: {}",
-        packages.len(),
-        packages.join(", ")
-    );
+    let header_text = tf("remove.title.preview", &[&packages.len(), &packages.join(", ")]);
     layout::render_header(f, &header_text, chunks[0]);
 
     layout::render_scrollable_content(
@@ -604,12 +694,22 @@ fn render_preview_view(f: &mut Frame, app: &App) {
         chunks[1],
     );
 
+    let owned_footer: String;
     let footer = if app.remove.preview.len() == 1
         && app.remove.preview[0].contains("正在获取")
     {
-        "正在获取卸载预览..."
+        t("remove.footer.fetching_preview")
+    } else if app.remove.has_protected() && app.remove.protected_confirmed {
+        t("remove.footer.protected_confirm")
+    } else if app.remove.has_protected() {
+        owned_footer = tf("remove.footer.protected", &[&app.remove.variant.flag()]);
+        &owned_footer
     } else {
-        "按 Enter 确认卸载 | Esc 返回列表 | ↑↓ 滚动"
+        owned_footer = tf(
+            "remove.footer.preview",
+            &[&app.remove.variant.flag(), &app.remove.variant.label()],
+        );
+        &owned_footer
     };
     layout::render_footer(f, footer, chunks[2]);
 }
@@ -619,16 +719,16 @@ fn render_output_view(f: &mut Frame, app: &App) {
     let chunks = layout::main_layout(f.area());
 
     let title = match app.remove.phase {
-        RemovePhase::Removing => "⚙️  正在卸载...",
-        RemovePhase::RemoveComplete => "✅ 卸载完成",
-        RemovePhase::Analyzing => "🤖 AI 分析中...",
-        RemovePhase::AnalysisComplete => "✨ 分析完成",
-        RemovePhase::Error => "❌ 错误",
-        _ => "🗑️  卸载",
+        RemovePhase::Removing => t("remove.title.removing"),
+        RemovePhase::RemoveComplete => t("remove.title.complete"),
+        RemovePhase::Analyzing => t("common.analyzing"),
+        RemovePhase::AnalysisComplete => t("common.analysis_done"),
+        RemovePhase::Error => t("common.error"),
+        _ => t("remove.title.default"),
     };
 
     let pm_info = if let Some(pm) = &app.package_manager {
-        format!(" | 包管理器: {}", pm.name())
+        tf("common.pm_info", &[&pm.name()])
     } else {
         String::new()
     };
@@ -645,56 +745,87 @@ fn render_output_view(f: &mut Frame, app: &App) {
 
     let content_title = if app.remove.phase == RemovePhase::AnalysisComplete {
         match app.remove.view_mode {
-            ViewMode::UpdateLog => "卸载日志 [Tab 切换到 AI 分析]",
-            ViewMode::AIAnalysis => "AI 分析报告 [Tab 切换到卸载日志]",
+            ViewMode::UpdateLog => t("remove.view.log_tab"),
+            ViewMode::AIAnalysis => t("remove.view.analysis"),
         }
     } else {
-        "卸载日志"
+        t("remove.view.log")
     };
 
     let content = app.remove.get_content();
-    layout::render_scrollable_content(f, content_title, &content, app.remove.scroll, chunks[1]);
+    layout::render_searchable_content(
+        f,
+        content_title,
+        &content,
+        app.remove.scroll,
+        chunks[1],
+        Some(&app.remove.search),
+        app.remove.view_mode == ViewMode::AIAnalysis && !app.markdown_raw,
+    );
 
     let owned_text: String;
     let footer_text = match app.remove.phase {
         RemovePhase::Removing => {
             let pt = app.remove.progress_info.footer_text();
-            if pt.is_empty() {
-                "卸载进行中..."
-            } else {
-                owned_text = pt;
-                &owned_text
-            }
+            let pt = if pt.is_empty() { t("remove.footer.running").to_string() } else { pt };
+            owned_text = format!("{} | {}", pt, follow_label(app.remove.follow));
+            &owned_text
         }
         RemovePhase::RemoveComplete => {
             if !app.remove.progress.is_empty() && (app.remove.progress.starts_with('✓') || app.remove.progress.starts_with("复制")) {
-                owned_text = format!("{} | y 复制 | ↑↓ 滚动 | Esc 返回主页", app.remove.progress);
+                owned_text = format!("{} | {}", app.remove.progress, t("output.footer.done_keys"));
                 &owned_text
             } else {
-                "卸载完成 | y 复制 | ↑↓ 滚动 | Esc 返回主页"
+                t("remove.footer.done")
             }
         }
-        RemovePhase::Analyzing => "AI 正在分析卸载内容...",
+        RemovePhase::Analyzing => t("remove.footer.analyzing"),
         RemovePhase::AnalysisComplete => {
             if !app.remove.progress.is_empty() && (app.remove.progress.starts_with('✓') || app.remove.progress.starts_with("复制")) {
-                owned_text = format!("{} | Tab 切换视图 | y 复制 | Esc 返回主页", app.remove.progress);
+                owned_text = format!("{} | {}", app.remove.progress, t("output.footer.analysis_keys"));
                 &owned_text
             } else if let Some(path) = &app.remove.report_path {
-                owned_text = format!("报告已保存: {} | Tab 切换视图 | y 复制 | Esc 返回主页", path);
+                owned_text = format!("{} | {}", tf("common.report_saved", &[path]), t("output.footer.analysis_keys"));
                 &owned_text
             } else {
-                "Tab 切换视图 | y 复制 | ↑↓ 滚动 | Esc 返回主页"
+                t("output.footer.analysis")
             }
         }
         RemovePhase::Error => {
             if let Some(msg) = &app.error_message {
                 msg
             } else {
-                "发生错误 | Esc 返回主页"
+                t("common.error_back")
             }
         }
-        _ => "Esc 返回",
+        _ => t("common.back"),
+    };
+    let with_usage: String;
+    let footer_text = match (&app.remove.phase, &app.remove.token_usage) {
+        (RemovePhase::AnalysisComplete, Some(usage)) => {
+            with_usage = format!("{} | {}", usage.footer_text(app.config.token_price), footer_text);
+            &with_usage
+        }
+        _ => footer_text,
+    };
+
+    // 搜索输入中只显示输入框；已有查询时作为前缀显示匹配进度
+    let with_search: String;
+    let footer_text = match app.remove.search.footer_text() {
+        Some(search) if app.remove.search.is_editing() => {
+            with_search = search;
+            &with_search
+        }
+        Some(search) => {
+            with_search = format!("{} | {}", search, footer_text);
+            &with_search
+        }
+        None => footer_text,
     };
 
-    layout::render_footer(f, footer_text, chunks[2]);
+    let percent = match app.remove.phase {
+        RemovePhase::Removing if !app.remove.search.is_editing() => app.remove.progress_info.percent,
+        _ => None,
+    };
+    layout::render_progress_footer(f, footer_text, percent, chunks[2]);
 }
@@ -0,0 +1,120 @@
+//! 输出视图中的 "/" 搜索：输入查询、高亮匹配行、n/N 在匹配间跳转（不区分大小写）
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::{Color, Modifier, Style};
+
+/// 跳转时匹配行上方保留的上下文行数
+const CONTEXT_LINES: usize = 2;
+
+#[derive(Default)]
+pub struct LogSearch {
+    /// 正在输入的查询，Some 时页脚显示输入框
+    pub input: Option<String>,
+    /// 最近一次提交的查询
+    pub query: String,
+    /// 匹配行的行号（升序）
+    pub matches: Vec<usize>,
+    /// 当前匹配在 matches 中的位置
+    pub current: usize,
+}
+
+impl LogSearch {
+    pub fn is_editing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// 处理搜索相关按键，返回 true 表示已消费；lines 为当前视图内容，scroll 随匹配跳转
+    pub fn handle_key(&mut self, key: KeyEvent, lines: &[String], scroll: &mut usize) -> bool {
+        if let Some(input) = &mut self.input {
+            match key.code {
+                KeyCode::Esc => self.input = None,
+                KeyCode::Enter => {
+                    self.query = self.input.take().unwrap_or_default();
+                    self.refresh(lines);
+                    // 从当前可见位置向下找第一个匹配，找不到则回到开头
+                    self.current = self.matches.iter().position(|&l| l >= *scroll).unwrap_or(0);
+                    self.jump(scroll);
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Char('/') => {
+                self.input = Some(String::new());
+                true
+            }
+            KeyCode::Char(c @ ('n' | 'N')) if !self.query.is_empty() => {
+                // 输出可能仍在增长，跳转前重新匹配
+                self.refresh(lines);
+                if !self.matches.is_empty() {
+                    let len = self.matches.len();
+                    self.current = if c == 'n' {
+                        (self.current + 1) % len
+                    } else {
+                        (self.current + len - 1) % len
+                    };
+                    self.jump(scroll);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 清除查询与匹配（视图内容整体切换时调用）
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn refresh(&mut self, lines: &[String]) {
+        let query = self.query.to_lowercase();
+        self.matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            lines
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.to_lowercase().contains(&query))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        self.current = self.current.min(self.matches.len().saturating_sub(1));
+    }
+
+    fn jump(&self, scroll: &mut usize) {
+        if let Some(&line) = self.matches.get(self.current) {
+            *scroll = line.saturating_sub(CONTEXT_LINES);
+        }
+    }
+
+    /// 第 idx 行的高亮样式：当前匹配反色，其余匹配加深背景
+    pub fn highlight(&self, idx: usize) -> Option<Style> {
+        if self.matches.get(self.current) == Some(&idx) {
+            Some(Style::default().add_modifier(Modifier::REVERSED))
+        } else if self.matches.binary_search(&idx).is_ok() {
+            Some(Style::default().bg(Color::DarkGray))
+        } else {
+            None
+        }
+    }
+
+    /// 页脚前缀：输入中为 "/查询▏"，已提交为 "🔍 查询 3/12"，无搜索时为 None
+    pub fn footer_text(&self) -> Option<String> {
+        if let Some(input) = &self.input {
+            return Some(format!("/{}▏ | Enter 查找 | Esc 取消", input));
+        }
+        if self.query.is_empty() {
+            return None;
+        }
+        Some(if self.matches.is_empty() {
+            format!("🔍 {} 无匹配", self.query)
+        } else {
+            format!("🔍 {} {}/{} | n/N 跳转", self.query, self.current + 1, self.matches.len())
+        })
+    }
+}
@@ -1,25 +1,99 @@
+use super::input::{handle_emacs_key, str_delete_back, str_delete_forward, str_insert_char, EditResult};
+use super::i18n::{t, tf};
 use super::layout;
-use super::state::{App, AppMode, SettingsItem};
-use super::theme::{BLUE, BRIGHT_WHITE, DIM, PINK, SEL_BG};
+use super::state::{App, AppEvent, AppMode, SettingsItem};
+use crate::deepseek::{AiClient, Message};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::{Margin, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
 use ratatui::Frame;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use unicode_width::UnicodeWidthStr;
 
-/// 处理设置模式按键
-pub fn handle_settings_key(key: KeyEvent, app: &mut App) -> bool {
+/// API 连接测试的超时时间
+const API_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 处理设置模式按键（api_key 为启动时解析的 Key，设置中未填写时用于连接测试）
+pub fn handle_settings_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    api_key: &str,
+) -> bool {
+    if app.settings.reset_confirm {
+        // y 确认恢复默认，其他键取消
+        app.settings.reset_confirm = false;
+        if key.code == KeyCode::Char('y') {
+            app.reset_settings();
+        }
+        return true;
+    }
     if app.settings.editing {
         handle_editing_key(key, app)
+    } else if app.settings.filter_editing {
+        handle_filter_key(key, app)
     } else {
-        handle_browsing_key(key, app)
+        handle_browsing_key(key, app, tx, api_key)
     }
 }
 
+/// 筛选输入按键处理：输入即时筛选，Enter 确认，Esc 清除筛选
+fn handle_filter_key(key: KeyEvent, app: &mut App) -> bool {
+    match handle_emacs_key(key, &mut app.settings.filter, &mut app.settings.filter_cursor) {
+        EditResult::Changed => {
+            app.apply_settings_filter();
+            return true;
+        }
+        EditResult::Moved => return true,
+        EditResult::Ignored => {}
+    }
+    match key.code {
+        KeyCode::Esc => {
+            app.settings.filter.clear();
+            app.settings.filter_cursor = 0;
+            app.settings.filter_editing = false;
+            app.apply_settings_filter();
+        }
+        KeyCode::Enter => {
+            app.settings.filter_editing = false;
+        }
+        KeyCode::Up | KeyCode::Down => {
+            app.move_settings_selection(key.code == KeyCode::Down);
+        }
+        KeyCode::Backspace => {
+            str_delete_back(&mut app.settings.filter, &mut app.settings.filter_cursor);
+            app.apply_settings_filter();
+        }
+        KeyCode::Delete => {
+            str_delete_forward(&mut app.settings.filter, &mut app.settings.filter_cursor);
+            app.apply_settings_filter();
+        }
+        KeyCode::Left => {
+            app.settings.filter_cursor = app.settings.filter_cursor.saturating_sub(1);
+        }
+        KeyCode::Right => {
+            app.settings.filter_cursor =
+                (app.settings.filter_cursor + 1).min(app.settings.filter.chars().count());
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            str_insert_char(&mut app.settings.filter, &mut app.settings.filter_cursor, c);
+            app.apply_settings_filter();
+        }
+        _ => return false,
+    }
+    true
+}
+
 /// 浏览模式按键处理
-fn handle_browsing_key(key: KeyEvent, app: &mut App) -> bool {
+fn handle_browsing_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    api_key: &str,
+) -> bool {
     let total = app.settings_focusable_count();
     if total == 0 {
         if key.code == KeyCode::Esc {
@@ -28,23 +102,31 @@ fn handle_browsing_key(key: KeyEvent, app: &mut App) -> bool {
         return true;
     }
 
+    // 筛选后没有匹配项时，选中项不可见，不响应操作
+    let visible = app.settings.filtered.contains(&app.settings.selected);
     match key.code {
+        KeyCode::Esc if !app.settings.filter.is_empty() => {
+            app.settings.filter.clear();
+            app.settings.filter_cursor = 0;
+            app.apply_settings_filter();
+            true
+        }
         KeyCode::Esc => {
             app.mode = AppMode::Dashboard;
             true
         }
-        KeyCode::Up => {
-            app.settings.selected = app.settings.selected.saturating_sub(1);
+        KeyCode::Char('/') => {
+            app.settings.filter_editing = true;
+            app.settings.filter_cursor = app.settings.filter.chars().count();
             app.settings.message = None;
             true
         }
-        KeyCode::Down => {
-            if app.settings.selected + 1 < total {
-                app.settings.selected += 1;
-            }
+        KeyCode::Up | KeyCode::Down => {
+            app.move_settings_selection(key.code == KeyCode::Down);
             app.settings.message = None;
             true
         }
+        KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right if !visible => true,
         KeyCode::Enter | KeyCode::Char(' ') => {
             // 获取当前选中项的实际索引
             let focusable: Vec<usize> = app.settings.items.iter().enumerate()
@@ -60,22 +142,99 @@ fn handle_browsing_key(key: KeyEvent, app: &mut App) -> bool {
                     SettingsItem::TextEdit { .. } => {
                         app.start_settings_edit();
                     }
+                    SettingsItem::Select { .. } => {
+                        app.cycle_settings_select(true);
+                    }
                     _ => {}
                 }
             }
             app.settings.message = None;
             true
         }
+        KeyCode::Left | KeyCode::Right => {
+            app.cycle_settings_select(key.code == KeyCode::Right);
+            app.settings.message = None;
+            true
+        }
         KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             app.save_settings();
             true
         }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.secure_config_permissions();
+            true
+        }
+        KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.move_api_key_to_keyring();
+            true
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.reload_settings();
+            true
+        }
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.settings.reset_confirm = true;
+            app.settings.message = None;
+            true
+        }
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            test_api_connection(app, tx, api_key);
+            true
+        }
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.export_package_list(false);
+            true
+        }
+        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.export_package_list(true);
+            true
+        }
         _ => false,
     }
 }
 
+/// 用当前（可能未保存的）地址、模型和 Key 发送一条极短的请求，结果通过事件返回
+fn test_api_connection(app: &mut App, tx: &mpsc::Sender<AppEvent>, api_key: &str) {
+    if app.settings.api_testing {
+        return;
+    }
+    let api_key = app.config.api_key.clone().unwrap_or_else(|| api_key.to_string());
+    if api_key.is_empty() && app.config.provider.requires_api_key() {
+        app.settings.message = Some("✗ 未设置 API Key".to_string());
+        return;
+    }
+    // 不读写缓存，确保真正发出请求
+    let mut config = app.config.clone();
+    config.ai.cache = false;
+    let client = AiClient::from_config(&api_key, &config);
+    let model = config.get_model().to_string();
+    let temperature = config.temperature;
+    app.settings.api_testing = true;
+    app.settings.message = Some(format!("… 正在测试 {} ({})", config.get_api_url(), model));
+
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(
+            API_TEST_TIMEOUT,
+            client.chat(vec![Message::user("ping")], &model, temperature),
+        )
+        .await
+        {
+            Ok(Ok(_)) => Ok(started.elapsed()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err(format!("{} 秒内未响应", API_TEST_TIMEOUT.as_secs())),
+        };
+        let _ = tx.send(AppEvent::SettingsApiTested(result)).await;
+    });
+}
+
 /// 编辑模式按键处理
 fn handle_editing_key(key: KeyEvent, app: &mut App) -> bool {
+    let edit = handle_emacs_key(key, &mut app.settings.edit_buffer, &mut app.settings.edit_cursor);
+    if edit != EditResult::Ignored {
+        return true;
+    }
     match key.code {
         KeyCode::Esc => {
             // 取消编辑
@@ -87,6 +246,22 @@ fn handle_editing_key(key: KeyEvent, app: &mut App) -> bool {
             app.confirm_settings_edit();
             true
         }
+        KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            // 多行文本插入换行
+            if matches!(
+                app.selected_settings_item(),
+                Some(SettingsItem::TextEdit { multiline: true, .. })
+            ) {
+                let byte_pos = app.settings.edit_buffer
+                    .char_indices()
+                    .nth(app.settings.edit_cursor)
+                    .map(|(i, _)| i)
+                    .unwrap_or(app.settings.edit_buffer.len());
+                app.settings.edit_buffer.insert(byte_pos, '\n');
+                app.settings.edit_cursor += 1;
+            }
+            true
+        }
         KeyCode::Backspace => {
             if app.settings.edit_cursor > 0 {
                 // UTF-8 安全删除
@@ -169,7 +344,7 @@ pub fn render_settings(f: &mut Frame, app: &App) {
     let chunks = layout::main_layout(f.area());
 
     // Header
-    layout::render_header(f, "⚙  设置", chunks[0]);
+    layout::render_header(f, t("settings.title"), chunks[0]);
 
     // Content
     let content_block = Block::default()
@@ -190,16 +365,35 @@ pub fn render_settings(f: &mut Frame, app: &App) {
     render_items(f, app, padded);
 
     // Footer
+    let owned_footer: String;
     let footer_text = if app.settings.editing {
-        "输入新值 | Enter 确认 | Esc 取消"
+        if matches!(
+            app.selected_settings_item(),
+            Some(SettingsItem::TextEdit { multiline: true, .. })
+        ) {
+            t("settings.footer.edit_multiline")
+        } else {
+            t("settings.footer.edit")
+        }
+    } else if app.settings.filter_editing {
+        owned_footer = tf("settings.footer.filter_editing", &[&app.settings.filter]);
+        &owned_footer
+    } else if !app.settings.filter.is_empty() {
+        owned_footer = tf(
+            "settings.footer.filtered",
+            &[&app.settings.filter, &app.settings.filtered.len()],
+        );
+        &owned_footer
     } else {
-        "↑↓ 选择 | Enter/Space 切换/编辑 | Ctrl+S 保存 | Esc 返回"
+        t("settings.footer.browse")
     };
 
     // 如果有消息，显示在 footer
     if let Some(msg) = &app.settings.message {
         let msg_color = if msg.starts_with('✓') {
             Color::Green
+        } else if msg.starts_with('…') {
+            Color::Yellow
         } else {
             Color::Red
         };
@@ -220,10 +414,19 @@ pub fn render_settings(f: &mut Frame, app: &App) {
     } else {
         layout::render_footer(f, footer_text, chunks[2]);
     }
+
+    if app.settings.reset_confirm {
+        layout::render_confirm_dialog(
+            f,
+            "恢复全部设置为默认值？（API Key 保留）",
+            "y 确认（Ctrl+S 保存后写入配置文件） | 其他键取消",
+        );
+    }
 }
 
 /// 渲染设置项列表
 fn render_items(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
     if app.settings.items.is_empty() {
         let hint = Paragraph::new("正在加载设置...")
             .style(Style::default().fg(Color::DarkGray));
@@ -234,35 +437,51 @@ fn render_items(f: &mut Frame, app: &App, area: Rect) {
     // 计算 label 最大宽度用于对齐
     let max_label_width = app.settings.items.iter()
         .filter_map(|item| match item {
-            SettingsItem::TextEdit { label, .. } => Some(UnicodeWidthStr::width(label.as_str())),
+            SettingsItem::TextEdit { label, .. } | SettingsItem::Select { label, .. } => {
+                Some(UnicodeWidthStr::width(label.as_str()))
+            }
             _ => None,
         })
         .max()
         .unwrap_or(10);
 
+    let visible = visible_items(app);
+    if !visible.contains(&true) {
+        let hint = Paragraph::new(format!("没有名称包含 \"{}\" 的设置项", app.settings.filter))
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, area);
+        return;
+    }
+
     let visible_height = area.height as usize;
     let mut lines: Vec<Line> = Vec::new();
     let mut focusable_idx = 0;
 
     for (i, item) in app.settings.items.iter().enumerate() {
+        if !visible[i] {
+            if !matches!(item, SettingsItem::Section(_)) {
+                focusable_idx += 1;
+            }
+            continue;
+        }
         match item {
             SettingsItem::Section(title) => {
                 // 分组前空一行（非首项）
-                if i > 0 {
+                if !lines.is_empty() {
                     lines.push(Line::from(""));
                 }
                 lines.push(Line::from(Span::styled(
                     format!("── {} ──", title),
-                    Style::default().fg(PINK).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.primary).add_modifier(Modifier::BOLD),
                 )));
             }
             SettingsItem::Toggle { label, value, .. } => {
                 let is_selected = focusable_idx == app.settings.selected;
                 let checkbox = if *value { "[✓]" } else { "[ ]" };
-                let checkbox_color = if *value { BLUE } else { DIM };
+                let checkbox_color = if *value { theme.secondary } else { theme.dim };
 
                 if is_selected {
-                    let bg = Style::default().bg(SEL_BG);
+                    let bg = Style::default().bg(theme.sel_bg);
                     lines.push(Line::from(vec![
                         Span::styled(
                             format!(" {} ", checkbox),
@@ -270,7 +489,7 @@ fn render_items(f: &mut Frame, app: &App, area: Rect) {
                         ),
                         Span::styled(
                             format!(" {}", label),
-                            bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD),
+                            bg.fg(theme.text).add_modifier(Modifier::BOLD),
                         ),
                     ]));
                 } else {
@@ -299,21 +518,23 @@ fn render_items(f: &mut Frame, app: &App, area: Rect) {
                     // 编辑中：显示 buffer 和光标
                     let buf = &app.settings.edit_buffer;
                     let cursor_pos = app.settings.edit_cursor;
-                    let before: String = buf.chars().take(cursor_pos).collect();
-                    let cursor_char: String = buf.chars().skip(cursor_pos).take(1).collect();
-                    let after: String = buf.chars().skip(cursor_pos + 1).collect();
+                    // 换行显示为 ↵，保持单行渲染且字符位置一一对应
+                    let shown = buf.chars().map(|c| if c == '\n' { '↵' } else { c });
+                    let before: String = shown.clone().take(cursor_pos).collect();
+                    let cursor_char: String = shown.clone().skip(cursor_pos).take(1).collect();
+                    let after: String = shown.skip(cursor_pos + 1).collect();
                     let cursor_display = if cursor_char.is_empty() {
                         " ".to_string()
                     } else {
                         cursor_char
                     };
 
-                    let bg = Style::default().bg(SEL_BG);
+                    let bg = Style::default().bg(theme.sel_bg);
                     lines.push(Line::from(vec![
-                        Span::styled(label_padded, bg.fg(BLUE).add_modifier(Modifier::BOLD)),
-                        Span::styled(before, bg.fg(BRIGHT_WHITE)),
+                        Span::styled(label_padded, bg.fg(theme.secondary).add_modifier(Modifier::BOLD)),
+                        Span::styled(before, bg.fg(theme.text)),
                         Span::styled(cursor_display, Style::default().fg(Color::Black).bg(Color::Yellow)),
-                        Span::styled(after, bg.fg(BRIGHT_WHITE)),
+                        Span::styled(after, bg.fg(theme.text)),
                     ]));
                 } else {
                     let display_value = if *masked && !value.is_empty() {
@@ -321,35 +542,60 @@ fn render_items(f: &mut Frame, app: &App, area: Rect) {
                     } else if value.is_empty() {
                         "(未设置)".to_string()
                     } else {
-                        value.clone()
+                        value.replace('\n', "↵")
                     };
 
                     if is_selected {
-                        let bg = Style::default().bg(SEL_BG);
+                        let bg = Style::default().bg(theme.sel_bg);
                         lines.push(Line::from(vec![
-                            Span::styled(label_padded, bg.fg(BLUE).add_modifier(Modifier::BOLD)),
+                            Span::styled(label_padded, bg.fg(theme.secondary).add_modifier(Modifier::BOLD)),
                             Span::styled(
                                 display_value,
-                                bg.fg(BRIGHT_WHITE).add_modifier(Modifier::BOLD),
+                                bg.fg(theme.text).add_modifier(Modifier::BOLD),
                             ),
                         ]));
                     } else {
-                        let value_color = if value.is_empty() { DIM } else { Color::White };
+                        let value_color = if value.is_empty() { theme.dim } else { Color::White };
                         lines.push(Line::from(vec![
-                            Span::styled(label_padded, Style::default().fg(BLUE)),
+                            Span::styled(label_padded, Style::default().fg(theme.secondary)),
                             Span::styled(display_value, Style::default().fg(value_color)),
                         ]));
                     }
                 }
                 focusable_idx += 1;
             }
+            SettingsItem::Select { label, options, index, .. } => {
+                let is_selected = focusable_idx == app.settings.selected;
+                let label_width = UnicodeWidthStr::width(label.as_str());
+                let padding = max_label_width.saturating_sub(label_width);
+                let label_padded = format!(" {}:{} ", label, " ".repeat(padding));
+                let current = options.get(*index).cloned().unwrap_or_default();
+                let display_value = format!("‹ {} ›", current);
+
+                if is_selected {
+                    let bg = Style::default().bg(theme.sel_bg);
+                    lines.push(Line::from(vec![
+                        Span::styled(label_padded, bg.fg(theme.secondary).add_modifier(Modifier::BOLD)),
+                        Span::styled(
+                            display_value,
+                            bg.fg(theme.text).add_modifier(Modifier::BOLD),
+                        ),
+                    ]));
+                } else {
+                    lines.push(Line::from(vec![
+                        Span::styled(label_padded, Style::default().fg(theme.secondary)),
+                        Span::styled(display_value, Style::default().fg(Color::White)),
+                    ]));
+                }
+                focusable_idx += 1;
+            }
         }
     }
 
     // 滚动处理
     let total_lines = lines.len();
     let scroll = if total_lines > visible_height {
-        let selected_line = find_selected_line(&app.settings.items, app.settings.selected);
+        let selected_line = find_selected_line(&app.settings.items, app.settings.selected, &visible);
         if selected_line >= visible_height {
             selected_line.saturating_sub(visible_height / 2)
         } else {
@@ -382,14 +628,41 @@ fn render_items(f: &mut Frame, app: &App, area: Rect) {
 }
 
 /// 找到选中项在渲染行中的行号
-fn find_selected_line(items: &[SettingsItem], selected: usize) -> usize {
+/// 各项是否显示：匹配筛选的可聚焦项，以及含有匹配项的分组标题
+fn visible_items(app: &App) -> Vec<bool> {
+    let mut visible = vec![false; app.settings.items.len()];
+    let mut section = None;
+    let mut focusable_idx = 0;
+    for (i, item) in app.settings.items.iter().enumerate() {
+        if matches!(item, SettingsItem::Section(_)) {
+            section = Some(i);
+            continue;
+        }
+        if app.settings.filtered.contains(&focusable_idx) {
+            visible[i] = true;
+            if let Some(s) = section {
+                visible[s] = true;
+            }
+        }
+        focusable_idx += 1;
+    }
+    visible
+}
+
+fn find_selected_line(items: &[SettingsItem], selected: usize, visible: &[bool]) -> usize {
     let mut line = 0;
     let mut focusable_idx = 0;
 
     for (i, item) in items.iter().enumerate() {
+        if !visible[i] {
+            if !matches!(item, SettingsItem::Section(_)) {
+                focusable_idx += 1;
+            }
+            continue;
+        }
         match item {
             SettingsItem::Section(_) => {
-                if i > 0 {
+                if line > 0 {
                     line += 1;
                 }
                 line += 1;
@@ -408,7 +681,7 @@ fn find_selected_line(items: &[SettingsItem], selected: usize) -> usize {
 
 /// API Key 脱敏：sk-abc...xyz → sk-***...***
 fn mask_value(value: &str) -> String {
-    let len = value.len();
+    let len = value.chars().count();
     if len <= 8 {
         "*".repeat(len)
     } else {
@@ -417,3 +690,22 @@ fn mask_value(value: &str) -> String {
         format!("{}****{}", prefix, suffix)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::mask_value;
+
+    #[test]
+    fn mask_multibyte_key() {
+        assert_eq!(mask_value("密钥密钥密钥密钥密钥"), "密钥密钥****密钥密钥");
+        assert_eq!(mask_value("🔑abcdefgh🔒"), "🔑abc****fgh🔒");
+    }
+
+    #[test]
+    fn mask_short_key() {
+        assert_eq!(mask_value(""), "");
+        assert_eq!(mask_value("🔑🔑"), "**");
+        assert_eq!(mask_value("sk-12345"), "********");
+        assert_eq!(mask_value("sk-123456"), "sk-1****3456");
+    }
+}
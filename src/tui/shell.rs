@@ -1,10 +1,11 @@
 //! 自定义命令模式（Shell 模式）
 //! 用户可以自由输入任意命令并查看流式输出，支持历史记录。
+//! 简单命令直接执行；含管道、重定向等元字符时通过 `sh -c` 执行。
 
-use super::input::{str_delete_back, str_delete_forward, str_insert_char};
+use super::input::{handle_emacs_key, str_delete_back, str_delete_forward, str_insert_char, EditResult};
+use super::i18n::{t, tf};
 use super::layout;
-use super::state::{App, AppEvent, AppMode, ShellPhase};
-use super::theme::{BRIGHT_WHITE, DIM, PINK};
+use super::state::{follow_label, App, AppEvent, AppMode, ShellPhase};
 use crate::tui::input::InputBox;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
@@ -14,6 +15,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 
 fn input_box_from_app(app: &App) -> InputBox {
@@ -37,13 +39,30 @@ pub fn handle_shell_key(
 ) -> bool {
     match app.shell.phase {
         ShellPhase::Input => handle_input_key(key, app, tx),
+        ShellPhase::PickingPackage => handle_picker_key(key, app),
         ShellPhase::Running => handle_running_key(key, app),
         ShellPhase::Done | ShellPhase::Error => handle_done_key(key, app, tx, term_height),
     }
 }
 
 fn handle_input_key(key: KeyEvent, app: &mut App, tx: &mpsc::Sender<AppEvent>) -> bool {
+    if key.code != KeyCode::Tab {
+        app.shell.completions.clear();
+        app.shell.completion_idx = None;
+    }
+    match handle_emacs_key(key, &mut app.shell.input, &mut app.shell.cursor) {
+        EditResult::Changed => {
+            app.shell.progress.clear();
+            return true;
+        }
+        EditResult::Moved => return true,
+        EditResult::Ignored => {}
+    }
     match key.code {
+        KeyCode::Tab => {
+            complete_input(app);
+            true
+        }
         KeyCode::Esc => {
             app.mode = AppMode::Dashboard;
             app.reset_shell_state();
@@ -54,9 +73,9 @@ fn handle_input_key(key: KeyEvent, app: &mut App, tx: &mpsc::Sender<AppEvent>) -
             if cmd.is_empty() {
                 return true;
             }
-            // 保存到历史
-            if app.shell.history.last().map(|s| s.as_str()) != Some(&cmd) {
-                app.shell.history.push(cmd.clone());
+            // 保存到历史并持久化
+            if crate::history::push(&mut app.shell.history, &cmd) {
+                crate::history::save(crate::history::SHELL_HISTORY_FILE, &app.shell.history);
             }
             app.shell.history_idx = None;
             spawn_shell_task(app, tx, cmd);
@@ -128,18 +147,141 @@ fn handle_input_key(key: KeyEvent, app: &mut App, tx: &mpsc::Sender<AppEvent>) -
             app.shell.cursor = app.shell.input.chars().count();
             true
         }
+        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            open_package_picker(app);
+            true
+        }
         KeyCode::Char(c) => {
             if key.modifiers.contains(KeyModifiers::CONTROL) {
                 return false;
             }
             str_insert_char(&mut app.shell.input, &mut app.shell.cursor, c);
+            app.shell.progress.clear();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Tab 补全：唯一候选直接补全；多个候选先补到公共前缀，再次 Tab 循环切换
+fn complete_input(app: &mut App) {
+    if !app.shell.completions.is_empty() {
+        let idx = app
+            .shell
+            .completion_idx
+            .map_or(0, |i| (i + 1) % app.shell.completions.len());
+        app.shell.completion_idx = Some(idx);
+        app.shell.input = app.shell.completions[idx].clone();
+        app.shell.cursor = app.shell.input.chars().count();
+        return;
+    }
+
+    let candidates = super::completion::complete(&app.shell.input);
+    match candidates.len() {
+        0 => {}
+        1 => {
+            app.shell.input = format!("{} ", candidates[0]);
+            app.shell.cursor = app.shell.input.chars().count();
+        }
+        _ => {
+            let prefix = super::completion::common_prefix(&candidates);
+            if prefix.len() > app.shell.input.len() {
+                app.shell.input = prefix;
+                app.shell.cursor = app.shell.input.chars().count();
+            }
+            app.shell.completions = candidates;
+        }
+    }
+}
+
+/// 打开本地软件包选择器：输入框为目录时列出该目录，为包文件时直接选中，否则列出当前目录
+fn open_package_picker(app: &mut App) {
+    let input = app.shell.input.trim();
+    let path = match input.strip_prefix("~/") {
+        Some(rest) => std::env::var("HOME")
+            .map(|home| PathBuf::from(home).join(rest))
+            .unwrap_or_else(|_| PathBuf::from(input)),
+        None if input.is_empty() => PathBuf::from("."),
+        None => PathBuf::from(input),
+    };
+
+    let result = if path.is_file() {
+        if crate::package_manager::is_local_package(&path) {
+            Ok(vec![path])
+        } else {
+            Err(format!("✗ {} 不是软件包文件（*.pkg.tar.*）", path.display()))
+        }
+    } else if path.is_dir() {
+        crate::package_manager::find_local_packages(&path).map_err(|e| format!("✗ {}", e))
+    } else {
+        Err(format!("✗ 路径不存在: {}", path.display()))
+    };
+
+    match result {
+        Ok(files) => {
+            app.shell.progress.clear();
+            app.shell.local_packages = files;
+            app.shell.local_selected = 0;
+            app.shell.phase = ShellPhase::PickingPackage;
+        }
+        Err(msg) => app.shell.progress = msg,
+    }
+}
+
+fn handle_picker_key(key: KeyEvent, app: &mut App) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.shell.phase = ShellPhase::Input;
+            app.shell.local_packages.clear();
+            app.shell.local_selected = 0;
+            app.shell.progress.clear();
+            true
+        }
+        KeyCode::Up => {
+            app.shell.local_selected = app.shell.local_selected.saturating_sub(1);
+            true
+        }
+        KeyCode::Down => {
+            if app.shell.local_selected + 1 < app.shell.local_packages.len() {
+                app.shell.local_selected += 1;
+            }
             true
         }
         _ => false,
     }
 }
 
+/// 当前选中的本地软件包（文件仍存在时）
+pub fn selected_local_package(app: &App) -> Option<PathBuf> {
+    app.shell
+        .local_packages
+        .get(app.shell.local_selected)
+        .filter(|p| p.is_file())
+        .cloned()
+}
+
+/// 通过提权的 pacman -U 安装本地软件包（调用前需完成提权验证）
+pub fn spawn_local_install(app: &mut App, tx: &mpsc::Sender<AppEvent>, path: PathBuf) {
+    let file = path.to_string_lossy().to_string();
+    let privilege = crate::package_manager::privilege::name();
+    let display = format!("{} pacman -U --noconfirm {}", privilege, file);
+    let parts = vec![
+        privilege,
+        "pacman".to_string(),
+        "-U".to_string(),
+        "--noconfirm".to_string(),
+        file,
+    ];
+    app.shell.local_packages.clear();
+    app.shell.local_selected = 0;
+    spawn_command(app, tx, display, parts);
+}
+
 fn handle_running_key(key: KeyEvent, app: &mut App) -> bool {
+    let content = app.shell.get_content();
+    if app.shell.search.handle_key(key, &content, &mut app.shell.scroll) {
+        return true;
+    }
     match key.code {
         KeyCode::Esc => {
             // 取消正在运行的命令
@@ -158,6 +300,10 @@ fn handle_running_key(key: KeyEvent, app: &mut App) -> bool {
             }
             true
         }
+        KeyCode::Char('f') => {
+            app.shell.toggle_follow();
+            true
+        }
         _ => false,
     }
 }
@@ -168,9 +314,14 @@ fn handle_done_key(
     tx: &mpsc::Sender<AppEvent>,
     term_height: u16,
 ) -> bool {
+    let content = app.shell.get_content();
+    if app.shell.search.handle_key(key, &content, &mut app.shell.scroll) {
+        return true;
+    }
     match key.code {
         KeyCode::Esc => {
             // 返回输入模式，准备下一条命令
+            app.shell.search.clear();
             app.shell.phase = ShellPhase::Input;
             app.shell.input.clear();
             app.shell.cursor = 0;
@@ -183,6 +334,7 @@ fn handle_done_key(
         KeyCode::Enter => {
             // 快速再次执行同一条命令（如果历史非空）
             if let Some(last) = app.shell.history.last().cloned() {
+                app.shell.search.clear();
                 app.shell.phase = ShellPhase::Input;
                 app.shell.input = last.clone();
                 app.shell.cursor = app.shell.input.chars().count();
@@ -195,12 +347,11 @@ fn handle_done_key(
             true
         }
         KeyCode::Char('y') => {
-            let text = app.shell.lines.join("\n");
-            if layout::copy_to_clipboard(&text) {
-                app.shell.progress = "✓ 内容已复制到剪贴板".to_string();
-            } else {
-                app.shell.progress = "复制失败 (请确认已安装 wl-copy/xclip/xsel)".to_string();
-            }
+            let text = layout::plain_text(&app.shell.lines);
+            app.shell.progress = match layout::copy_to_clipboard(&text) {
+                Ok(()) => "✓ 内容已复制到剪贴板".to_string(),
+                Err(e) => format!("复制失败: {}", e),
+            };
             true
         }
         KeyCode::Up => {
@@ -227,10 +378,39 @@ fn handle_done_key(
             app.shell.scroll = (app.shell.scroll + 10).min(max_scroll);
             true
         }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.shell.scroll = 0;
+            true
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            let content = app.shell.get_content();
+            let visible = layout::visible_content_height(term_height);
+            app.shell.scroll = content.len().saturating_sub(visible);
+            true
+        }
         _ => false,
     }
 }
 
+/// 是否包含需要 shell 解释的元字符（单引号内的不算）
+fn needs_shell(cmd: &str) -> bool {
+    let mut in_single = false;
+    let mut in_double = false;
+    for c in cmd.chars() {
+        match c {
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            // 变量和命令替换在双引号内同样生效
+            '$' | '`' if !in_single => return true,
+            '|' | '&' | ';' | '<' | '>' | '*' | '?' | '(' | ')' if !in_single && !in_double => {
+                return true;
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 /// 解析命令字符串为参数列表
 fn parse_command(cmd: &str) -> Vec<String> {
     // 简单按空格拆分，支持单引号/双引号包裹的参数
@@ -266,11 +446,20 @@ fn parse_command(cmd: &str) -> Vec<String> {
 
 /// 启动命令执行异步任务
 fn spawn_shell_task(app: &mut App, tx: &mpsc::Sender<AppEvent>, cmd: String) {
-    let cmd_parts = parse_command(&cmd);
+    // 含管道/重定向等元字符时交给 sh -c，其余直接执行，避免意外的 shell 展开
+    let cmd_parts = if needs_shell(&cmd) {
+        vec!["sh".to_string(), "-c".to_string(), cmd.clone()]
+    } else {
+        parse_command(&cmd)
+    };
     if cmd_parts.is_empty() {
         return;
     }
+    spawn_command(app, tx, cmd, cmd_parts);
+}
 
+/// 执行已拆分好的命令，cmd 仅用于显示
+fn spawn_command(app: &mut App, tx: &mpsc::Sender<AppEvent>, cmd: String, cmd_parts: Vec<String>) {
     app.shell.phase = ShellPhase::Running;
     app.shell.lines.clear();
     app.shell.output = None;
@@ -320,6 +509,7 @@ pub fn render_shell(f: &mut Frame, app: &App) {
 
     match app.shell.phase {
         ShellPhase::Input => render_input_view(f, app, chunks[1], chunks[2]),
+        ShellPhase::PickingPackage => render_picker_view(f, app, chunks[1], chunks[2]),
         ShellPhase::Running => render_output_view(f, app, chunks[1], chunks[2]),
         ShellPhase::Done | ShellPhase::Error => render_output_view(f, app, chunks[1], chunks[2]),
     }
@@ -327,10 +517,11 @@ pub fn render_shell(f: &mut Frame, app: &App) {
 
 fn render_shell_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let title = match app.shell.phase {
-        ShellPhase::Input => "💻 自定义命令",
-        ShellPhase::Running => "⚙️  命令执行中...",
-        ShellPhase::Done => "✅ 命令完成",
-        ShellPhase::Error => "❌ 命令错误",
+        ShellPhase::Input => t("shell.title.input"),
+        ShellPhase::PickingPackage => t("shell.title.picking"),
+        ShellPhase::Running => t("shell.title.running"),
+        ShellPhase::Done => t("shell.title.done"),
+        ShellPhase::Error => t("shell.title.error"),
     };
     let header = Paragraph::new(title)
         .style(
@@ -349,8 +540,9 @@ fn render_input_view(
     content_area: ratatui::layout::Rect,
     footer_area: ratatui::layout::Rect,
 ) {
+    let theme = app.theme();
     let block = Block::default()
-        .title("输入命令")
+        .title(t("shell.input.title"))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow));
     let inner = block.inner(content_area);
@@ -364,14 +556,35 @@ fn render_input_view(
     // 输入框
     let ib = input_box_from_app(app);
     let prompt = Line::from(vec![
-        Span::styled("$ ", Style::default().fg(PINK).add_modifier(Modifier::BOLD)),
-        Span::styled(ib.content().to_string(), Style::default().fg(BRIGHT_WHITE)),
+        Span::styled("$ ", Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)),
+        Span::styled(ib.content().to_string(), Style::default().fg(theme.text)),
         Span::styled("_", Style::default().fg(Color::White).add_modifier(Modifier::RAPID_BLINK)),
     ]);
     f.render_widget(Paragraph::new(prompt), padded);
 
-    // 历史提示
-    if !app.shell.history.is_empty() {
+    // 补全候选（优先于历史提示显示）
+    if !app.shell.completions.is_empty() {
+        let hint_area = ratatui::layout::Rect {
+            y: padded.y + 2,
+            height: padded.height.saturating_sub(2),
+            ..padded
+        };
+        let mut spans = Vec::new();
+        for (i, cand) in app.shell.completions.iter().enumerate() {
+            let word = cand.rsplit(char::is_whitespace).next().unwrap_or(cand);
+            let style = if app.shell.completion_idx == Some(i) {
+                Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            spans.push(Span::styled(word.to_string(), style));
+            spans.push(Span::raw("  "));
+        }
+        f.render_widget(
+            Paragraph::new(Line::from(spans)).wrap(ratatui::widgets::Wrap { trim: false }),
+            hint_area,
+        );
+    } else if !app.shell.history.is_empty() {
         let hint_area = ratatui::layout::Rect {
             y: padded.y + 2,
             height: padded.height.saturating_sub(2),
@@ -388,7 +601,7 @@ fn render_input_view(
                     Line::from(vec![
                         Span::styled(
                             format!("  {}: ", idx + 1),
-                            Style::default().fg(DIM),
+                            Style::default().fg(theme.dim),
                         ),
                         Span::styled(cmd.clone(), Style::default().fg(Color::DarkGray)),
                     ])
@@ -400,10 +613,68 @@ fn render_input_view(
     }
 
     // 页脚
-    let footer = if app.shell.history.is_empty() {
-        "输入命令后 Enter 执行 | ↑↓ 历史 | Esc 返回"
+    let footer = if !app.shell.progress.is_empty() {
+        app.shell.progress.as_str()
+    } else if app.shell.history.is_empty() {
+        t("shell.footer.input_empty")
+    } else {
+        t("shell.footer.input")
+    };
+    layout::render_footer(f, footer, footer_area);
+}
+
+fn render_picker_view(
+    f: &mut Frame,
+    app: &App,
+    content_area: ratatui::layout::Rect,
+    footer_area: ratatui::layout::Rect,
+) {
+    let theme = app.theme();
+    let block = Block::default()
+        .title(tf("shell.picker.title", &[&app.shell.local_packages.len()]))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(content_area);
+    f.render_widget(block, content_area);
+
+    if app.shell.local_packages.is_empty() {
+        let hint = Paragraph::new(t("shell.picker.empty"))
+            .style(Style::default().fg(theme.dim));
+        f.render_widget(hint, inner);
+        layout::render_footer(f, t("shell.footer.picker_empty"), footer_area);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let start = app.shell.local_selected.saturating_sub(visible.saturating_sub(1));
+    let lines: Vec<Line> = app
+        .shell
+        .local_packages
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .map(|(i, path)| {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if i == app.shell.local_selected {
+                Line::from(Span::styled(
+                    format!("▸ {}", name),
+                    Style::default().fg(theme.primary).add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(Span::styled(format!("  {}", name), Style::default().fg(theme.text)))
+            }
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+
+    let owned_footer: String;
+    let footer = if app.shell.progress.is_empty() {
+        owned_footer = tf("shell.footer.picker", &[&crate::package_manager::privilege::name()]);
+        &owned_footer
     } else {
-        "Enter 执行 | ↑↓ 历史记录 | Esc 返回主页"
+        owned_footer = tf("shell.footer.picker_progress", &[&app.shell.progress]);
+        &owned_footer
     };
     layout::render_footer(f, footer, footer_area);
 }
@@ -414,6 +685,7 @@ fn render_output_view(
     content_area: ratatui::layout::Rect,
     footer_area: ratatui::layout::Rect,
 ) {
+    let theme = app.theme();
     let content = app.shell.get_content();
     let total_lines = content.len();
     // content_area 是 main_layout 的 chunks[1]，减去 block 上下 border 2 行即可视行数
@@ -422,13 +694,14 @@ fn render_output_view(
 
     let lines: Vec<Line> = content
         .iter()
+        .enumerate()
         .skip(scroll)
         .take(visible)
-        .map(|line| {
-            if line.starts_with("$ ") {
+        .map(|(idx, line)| {
+            let rendered = if line.starts_with("$ ") {
                 Line::from(Span::styled(
                     line.clone(),
-                    Style::default().fg(PINK).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.primary).add_modifier(Modifier::BOLD),
                 ))
             } else if line.starts_with("⚠ ") {
                 Line::from(Span::styled(
@@ -436,16 +709,20 @@ fn render_output_view(
                     Style::default().fg(Color::Yellow),
                 ))
             } else {
-                Line::from(Span::styled(line.clone(), Style::default().fg(BRIGHT_WHITE)))
+                Line::from(Span::styled(line.clone(), Style::default().fg(theme.text)))
+            };
+            match app.shell.search.highlight(idx) {
+                Some(style) => rendered.patch_style(style),
+                None => rendered,
             }
         })
         .collect();
 
     let block_title = match app.shell.phase {
-        ShellPhase::Running => "输出 (Esc 取消)",
-        ShellPhase::Done => "输出",
-        ShellPhase::Error => "输出 (错误)",
-        ShellPhase::Input => "输出",
+        ShellPhase::Running => t("shell.output.running"),
+        ShellPhase::Done => t("shell.output.title"),
+        ShellPhase::Error => t("shell.output.error"),
+        ShellPhase::Input | ShellPhase::PickingPackage => t("shell.output.title"),
     };
 
     let block = Block::default()
@@ -474,28 +751,44 @@ fn render_output_view(
     let footer = match app.shell.phase {
         ShellPhase::Running => {
             if app.shell.progress.is_empty() {
-                "执行中... | Esc 取消 | ↑↓ 滚动"
+                owned_footer = tf("shell.footer.running", &[&follow_label(app.shell.follow)]);
             } else {
-                owned_footer = format!("{} | Esc 取消 | ↑↓ 滚动", app.shell.progress);
-                &owned_footer
+                owned_footer = tf(
+                    "shell.footer.running_progress",
+                    &[&app.shell.progress, &follow_label(app.shell.follow)],
+                );
             }
+            &owned_footer
         }
         ShellPhase::Done => {
             owned_footer = if !app.shell.progress.is_empty() {
-                format!("{} | Enter 重新执行 | Esc 新命令 | y 复制 | ↑↓ 滚动", app.shell.progress)
+                tf("shell.footer.done_progress", &[&app.shell.progress])
             } else if let Some(output) = &app.shell.output {
                 if output.success {
-                    "✓ 命令成功 | Enter 重新执行 | Esc 新命令 | y 复制 | ↑↓ 滚动".to_string()
+                    t("shell.footer.done_ok").to_string()
                 } else {
-                    "✗ 命令失败 | Enter 重新执行 | Esc 新命令 | y 复制 | ↑↓ 滚动".to_string()
+                    t("shell.footer.done_failed").to_string()
                 }
             } else {
-                "Esc 返回输入 | Enter 重新执行 | y 复制 | ↑↓ 滚动".to_string()
+                t("shell.footer.done").to_string()
             };
             &owned_footer
         }
-        ShellPhase::Error => "❌ 执行出错 | Esc 新命令 | ↑↓ 滚动",
-        ShellPhase::Input => "",
+        ShellPhase::Error => t("shell.footer.error"),
+        ShellPhase::Input | ShellPhase::PickingPackage => "",
+    };
+    // 搜索输入中只显示输入框；已有查询时作为前缀显示匹配进度
+    let with_search: String;
+    let footer = match app.shell.search.footer_text() {
+        Some(search) if app.shell.search.is_editing() => {
+            with_search = search;
+            &with_search
+        }
+        Some(search) => {
+            with_search = format!("{} | {}", search, footer);
+            &with_search
+        }
+        None => footer,
     };
     layout::render_footer(f, footer, footer_area);
 }
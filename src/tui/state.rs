@@ -1,7 +1,11 @@
-use crate::config::Config;
-use crate::package_manager::{InstalledPackage, PackageDetail, PackageInfo, PackageManager, ProgressInfo, UpdateOutput};
+use crate::config::{Config, Provider, ReportFormat, ReportMode, ThemePreset, ANALYSIS_LANGUAGES, TEMPERATURE_RANGE, UI_LANGUAGES};
+use crate::deepseek::{Message, TokenUsage};
+use crate::news::NewsItem;
+use crate::package_manager::{InstalledPackage, OptionalDep, PackageCounts, PackageDetail, PackageInfo, PackageManager, PackageSize, PacmanConf, PacmanLogEntry, ProgressInfo, RemoveVariant, SearchCancel, UpdateOutput};
 use crate::sysinfo::SystemInfo;
-use std::collections::HashSet;
+use super::i18n::t;
+use super::search::LogSearch;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::Instant;
 
 // ========== 枚举 ==========
@@ -15,6 +19,8 @@ pub enum AppMode {
     Query,    // Shift+Q: -Qs/-Ss/-Qi/-Ql
     Settings, // Shift+C: 设置
     Shell,    // Shift+X: 自定义命令
+    Pacnew,   // p: 合并 .pacnew / .pacsave
+    Plan,     // Shift+P: 事务计划
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -52,9 +58,81 @@ pub enum FileListMode {
     Directories,
 }
 
+/// 按仓库筛选软件包，repo 为 None 时返回全部
+pub fn filter_by_repo(packages: &[PackageInfo], repo: &Option<String>) -> Vec<PackageInfo> {
+    match repo {
+        Some(repo) => packages.iter().filter(|p| &p.repo == repo).cloned().collect(),
+        None => packages.to_vec(),
+    }
+}
+
+/// 在结果中出现过的仓库间循环切换筛选：全部 → 第一个仓库 → … → 全部
+pub fn next_repo_filter(current: &Option<String>, packages: &[PackageInfo]) -> Option<String> {
+    let mut repos: Vec<&str> = Vec::new();
+    for p in packages {
+        if !repos.contains(&p.repo.as_str()) {
+            repos.push(&p.repo);
+        }
+    }
+    let next = match current {
+        None => 0,
+        Some(cur) => match repos.iter().position(|r| r == cur) {
+            Some(i) => i + 1,
+            None => 0,
+        },
+    };
+    repos.get(next).map(|r| r.to_string())
+}
+
+/// 软件包列表排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    /// 搜索/筛选结果的原始顺序
+    #[default]
+    Default,
+    Name,
+    /// 安装大小，从大到小
+    Size,
+    /// 安装时间，从新到旧
+    InstallDate,
+    /// 安装时间，从旧到新（找长期未动的包）
+    InstallDateOldest,
+}
+
+impl SortKey {
+    /// 安装模式可用的排序方式
+    pub const INSTALL: [SortKey; 3] = [SortKey::Default, SortKey::Name, SortKey::Size];
+    /// 卸载模式可用的排序方式
+    pub const REMOVE: [SortKey; 5] = [
+        SortKey::Default,
+        SortKey::Name,
+        SortKey::Size,
+        SortKey::InstallDate,
+        SortKey::InstallDateOldest,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Default => "默认",
+            SortKey::Name => "名称",
+            SortKey::Size => "大小",
+            SortKey::InstallDate => "最近安装",
+            SortKey::InstallDateOldest => "最早安装",
+        }
+    }
+
+    /// 在给定的可选项中切换到下一个
+    pub fn next_in(&self, keys: &[SortKey]) -> SortKey {
+        let pos = keys.iter().position(|k| k == self).unwrap_or(0);
+        keys[(pos + 1) % keys.len()]
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum InstallPhase {
     Searching,
+    /// 安装 AUR 包前审查 PKGBUILD
+    ReviewingPkgbuild,
     PreviewingInstall,
     Installing,
     InstallComplete,
@@ -77,6 +155,7 @@ pub enum RemovePhase {
 #[derive(Debug, Clone, PartialEq)]
 pub enum ShellPhase {
     Input,    // 输入命令
+    PickingPackage, // 选择本地软件包文件
     Running,  // 执行中
     Done,     // 执行完成
     Error,
@@ -99,7 +178,55 @@ pub enum SettingsItem {
         key: String,
         value: String,
         masked: bool,
+        /// 允许 Ctrl+J 插入换行
+        multiline: bool,
     },
+    /// 下拉选择项（Enter/Space/←→ 循环切换）
+    Select {
+        label: String,
+        key: String,
+        options: Vec<String>,
+        index: usize,
+    },
+}
+
+impl SettingsItem {
+    /// 显示名称（分组为标题）
+    pub fn label(&self) -> &str {
+        match self {
+            SettingsItem::Section(title) => title,
+            SettingsItem::Toggle { label, .. }
+            | SettingsItem::TextEdit { label, .. }
+            | SettingsItem::Select { label, .. } => label,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PacnewView {
+    /// 待合并文件列表
+    List,
+    /// 选中文件与在用配置的 diff
+    Diff,
+}
+
+/// 等待确认的 .pacnew 操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PacnewAction {
+    /// 用新文件覆盖在用配置
+    Accept,
+    /// 删除新文件，保留在用配置
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanPhase {
+    /// 查看 / 编辑待执行的包
+    Editing,
+    /// 卸载与安装的合并预览
+    Previewing,
+    Running,
+    Complete,
 }
 
 // ========== 事件 ==========
@@ -114,33 +241,85 @@ pub enum AppEvent {
         packages_before: Option<String>,
         packages_after: Option<String>,
     },
-    AnalysisComplete(String),
+    /// 更新完成后的重启检测结果（运行中的内核模块目录已不存在）
+    RebootCheck(bool),
+    /// 更新完成后扫描到的 .pacnew / .pacsave 文件
+    PacnewFound(Vec<std::path::PathBuf>),
+    AnalysisChunk(String),
+    ChatReply(String),
+    ChatFailed(String),
+    AnalysisComplete {
+        analysis: String,
+        usage: Option<TokenUsage>,
+    },
     ReportSaved(String),
     Error(String),
     InstalledCount(usize),
+    /// 显式 / 依赖 / 外部包数量
+    PackageCounts(PackageCounts),
+    OrphanCount(usize),
+    /// 包缓存占用字节数
+    CacheSize(u64),
+    /// 占用空间最大的已安装包（降序）
+    LargestPackages(Vec<InstalledPackage>),
+    /// pacman.log 中最近的包操作（最新的在前）
+    RecentChanges(Vec<PacmanLogEntry>),
     QueryLocalResults { results: Vec<PackageInfo>, seq: u64 },
-    QueryRemoteResults { results: Vec<PackageInfo>, seq: u64 },
+    QueryRemoteResults { results: anyhow::Result<Vec<PackageInfo>>, seq: u64 },
     QueryDetailLoaded {
         detail: PackageDetail,
         files: Vec<String>,
         dirs: Vec<String>,
+        optdeps: Vec<OptionalDep>,
     },
-    UpdatePreviewReady(Vec<String>),
+    /// 可用更新列表；Err 表示检查本身失败
+    UpdatePreviewReady(anyhow::Result<Vec<String>>),
+    /// 本次更新待下载的总字节数
+    UpdateDownloadSize(u64),
+    NewsLoaded(Vec<NewsItem>),
     // Install
-    InstallSearchResults { results: Vec<PackageInfo>, seq: u64 },
+    InstallSearchResults { results: anyhow::Result<Vec<PackageInfo>>, seq: u64 },
+    /// AUR 包的 PKGBUILD 内容（已按行拆分）
+    InstallPkgbuildReady(Vec<String>),
+    /// 搜索结果的下载/安装大小（按包名）
+    InstallSizesLoaded(HashMap<String, PackageSize>),
+    /// 导入的包列表已按是否安装拆分（path, 待安装, 已安装跳过）
+    InstallListImported { path: std::path::PathBuf, to_install: Vec<String>, skipped: Vec<String> },
     InstallPreviewReady(Vec<String>),
     InstallLine(String),
     InstallComplete { output: UpdateOutput },
-    InstallAnalysisComplete(String),
+    InstallAnalysisComplete {
+        analysis: String,
+        usage: Option<TokenUsage>,
+    },
     // Remove
     RemovePackagesLoaded(Vec<InstalledPackage>),
     RemovePreviewReady(Vec<String>),
     RemoveLine(String),
     RemoveComplete { output: UpdateOutput },
-    RemoveAnalysisComplete(String),
+    RemoveAnalysisComplete {
+        analysis: String,
+        usage: Option<TokenUsage>,
+    },
+    // Plan
+    PlanPreviewReady { preview: Vec<String>, seq: u64 },
+    PlanLine(String),
+    /// 计划执行结束；未执行的步骤为 None（列表为空或前一步失败）
+    PlanComplete {
+        removed: Option<UpdateOutput>,
+        installed: Option<UpdateOutput>,
+    },
+    /// 计划的 AI 分析结束；log 为分析所用的执行日志，随报告一起保存
+    PlanAnalysisComplete {
+        result: anyhow::Result<(String, Option<TokenUsage>)>,
+        log: String,
+    },
     // Shell
     ShellLine(String),
     ShellComplete { output: UpdateOutput },
+    // Settings
+    /// API 连接测试结果：成功时为响应耗时
+    SettingsApiTested(Result<std::time::Duration, String>),
 }
 
 // ========== 子状态结构体 ==========
@@ -153,12 +332,52 @@ pub struct UpdateModeState {
     pub progress: String,
     pub progress_info: ProgressInfo,
     pub last_line_is_progress: bool,
+    /// 是否自动滚动跟随最新输出（f 切换）
+    pub follow: bool,
     pub packages_before: Option<String>,
     pub packages_after: Option<String>,
     pub analysis: Option<String>,
+    /// 最近一次 AI 分析的 token 用量
+    pub token_usage: Option<TokenUsage>,
     pub scroll: usize,
+    /// 另一个视图（日志 / AI 分析）的滚动位置，Tab 切回时恢复
+    pub view_scroll: usize,
     pub report_path: Option<String>,
     pub preview: Vec<String>,
+    /// Arch 官方新闻（获取失败时为空）
+    pub news: Vec<NewsItem>,
+    /// 更新前后显式安装包的差异（带 DIFF_* 前缀的渲染行）
+    pub package_diff: Vec<String>,
+    /// 进行中的流式 AI 分析任务，用于 Esc 取消
+    pub analysis_task: Option<tokio::task::AbortHandle>,
+    /// 针对分析报告的追问对话（问答交替，不含报告本身）
+    pub chat: Vec<Message>,
+    /// 追问输入内容，Some 表示正在输入
+    pub chat_input: Option<String>,
+    /// 正在等待追问的回复
+    pub chat_pending: bool,
+    /// 更新后需要重启（内核已替换或更新了内核包）
+    pub reboot_required: bool,
+    /// 本次更新中的内核包 (包名, 旧版本, 新版本)
+    pub kernel_update: Option<(String, String, String)>,
+    /// 本次更新中建议重启的关键包（systemd、glibc 等）
+    pub critical_updates: Vec<String>,
+    /// 更新后待合并的 .pacnew / .pacsave 文件
+    pub pacnew_files: Vec<std::path::PathBuf>,
+    /// 日志 "/" 搜索
+    pub search: LogSearch,
+    /// 本次更新中仓库包的下载总字节数（pacman -Si 的 Download Size 之和，0 表示未知）
+    pub download_total: u64,
+    /// 各包已下载字节数（来自进度行）
+    pub downloaded: HashMap<String, u64>,
+    /// 收到第一条下载进度的时间，用于估算剩余时间
+    pub download_started: Option<Instant>,
+    /// 预览列表中的光标位置
+    pub preview_selected: usize,
+    /// 预览中选中的包（preview 下标），非空时只升级这些包（部分更新）
+    pub marked: HashSet<usize>,
+    /// 完整更新前先单独更新 archlinux-keyring
+    pub keyring_first: bool,
 }
 
 pub struct QueryModeState {
@@ -175,9 +394,31 @@ pub struct QueryModeState {
     pub dirs: Vec<String>,
     pub file_mode: FileListMode,
     pub detail_scroll: usize,
+    /// 另一个文件列表（文件 / 目录）的滚动位置，Tab 切回时恢复
+    pub file_mode_scroll: usize,
     pub searching: bool,
     pub search_seq: u64,
     pub search_scheduled: Option<Instant>,
+    /// 本界面远程搜索进程的取消句柄
+    pub search_cancel: SearchCancel,
+    /// 搜索历史（旧的在前），持久化到配置目录
+    pub history: Vec<String>,
+    /// 正在浏览的历史索引，None 表示未浏览
+    pub history_idx: Option<usize>,
+    /// 未按仓库筛选的远程结果
+    pub remote_all: Vec<PackageInfo>,
+    /// 远程结果的仓库筛选，None 表示全部
+    pub repo_filter: Option<String>,
+    /// 详情视图的提示信息（✓/✗）
+    pub message: Option<String>,
+    /// 多选标记的包（按包名），重新搜索后仍保留
+    pub marked: BTreeMap<String, PackageInfo>,
+    /// 远程搜索失败的原因（如 "网络不可用"），本地搜索不受影响
+    pub remote_error: Option<String>,
+    /// 详情中的可选依赖及其安装状态
+    pub optdeps: Vec<OptionalDep>,
+    /// 选中的可选依赖（[ ] 切换）
+    pub optdep_selected: usize,
 }
 
 pub struct InstallModeState {
@@ -186,20 +427,54 @@ pub struct InstallModeState {
     pub cursor: usize,
     pub results: Vec<PackageInfo>,
     pub selected: usize,
-    pub marked: HashSet<usize>,
+    /// 多选标记的包名，重新搜索后仍保留
+    pub marked: HashSet<String>,
+    /// 搜索中出现过的 AUR 包名，标记项不在当前结果中时用于判断是否需要审查 PKGBUILD
+    pub known_aur: HashSet<String>,
+    /// 远程搜索失败的原因（如 "网络不可用"），区别于没有匹配
+    pub search_error: Option<String>,
     pub preview: Vec<String>,
     pub lines: Vec<String>,
     pub output: Option<UpdateOutput>,
     pub progress: String,
     pub progress_info: ProgressInfo,
     pub last_line_is_progress: bool,
+    /// 是否自动滚动跟随最新输出（f 切换）
+    pub follow: bool,
     pub analysis: Option<String>,
+    /// 最近一次 AI 分析的 token 用量
+    pub token_usage: Option<TokenUsage>,
     pub scroll: usize,
+    /// 另一个视图（日志 / AI 分析）的滚动位置，Tab 切回时恢复
+    pub view_scroll: usize,
     pub searching: bool,
     pub search_seq: u64,
     pub search_scheduled: Option<Instant>,
+    /// 本界面远程搜索进程的取消句柄
+    pub search_cancel: SearchCancel,
     pub view_mode: ViewMode,
     pub report_path: Option<String>,
+    /// 导入列表时已安装而跳过的包
+    pub skipped: Vec<String>,
+    pub sort: SortKey,
+    /// 未排序的搜索结果，用于恢复默认顺序
+    pub unsorted: Vec<PackageInfo>,
+    /// 仓库筛选，None 表示全部
+    pub repo_filter: Option<String>,
+    /// 是否显示大小列
+    pub show_sizes: bool,
+    /// 已获取的包大小（按包名，跨搜索保留）
+    pub sizes: HashMap<String, PackageSize>,
+    /// 已请求过大小的包名，避免重复查询
+    pub sizes_requested: HashSet<String>,
+    /// 以显式安装方式安装（--asexplicit）
+    pub as_explicit: bool,
+    /// 待审查的 PKGBUILD 内容
+    pub pkgbuild: Vec<String>,
+    /// 预览中提示空间不足时，是否已按过第一次 Enter
+    pub space_confirmed: bool,
+    /// 日志 "/" 搜索
+    pub search: LogSearch,
 }
 
 pub struct RemoveModeState {
@@ -216,11 +491,28 @@ pub struct RemoveModeState {
     pub progress: String,
     pub progress_info: ProgressInfo,
     pub last_line_is_progress: bool,
+    /// 是否自动滚动跟随最新输出（f 切换）
+    pub follow: bool,
     pub analysis: Option<String>,
+    /// 最近一次 AI 分析的 token 用量
+    pub token_usage: Option<TokenUsage>,
     pub scroll: usize,
+    /// 另一个视图（日志 / AI 分析）的滚动位置，Tab 切回时恢复
+    pub view_scroll: usize,
     pub loading: bool,
     pub view_mode: ViewMode,
     pub report_path: Option<String>,
+    pub sort: SortKey,
+    /// 卸载方式（-Rns / -Rs / -R）
+    pub variant: RemoveVariant,
+    /// 预览中含受保护包时，是否已按过第一次 Enter
+    pub protected_confirmed: bool,
+    /// 从仪表盘进入的孤立包清理：列表为 -Qtdi 结果，加载后全部标记
+    pub orphans: bool,
+    /// 从仪表盘进入的大包清理：列表为占用空间最大的若干个包，按大小排序
+    pub largest: bool,
+    /// 日志 "/" 搜索
+    pub search: LogSearch,
 }
 
 pub struct SettingsModeState {
@@ -231,6 +523,16 @@ pub struct SettingsModeState {
     pub edit_cursor: usize,
     pub message: Option<String>,
     pub scroll: usize,
+    /// 按名称筛选设置项的关键词（/ 开始输入）
+    pub filter: String,
+    pub filter_cursor: usize,
+    pub filter_editing: bool,
+    /// 匹配筛选的可聚焦项索引（与 selected 同一编号）
+    pub filtered: Vec<usize>,
+    /// 按了恢复默认，等待 y 确认
+    pub reset_confirm: bool,
+    /// API 连接测试进行中
+    pub api_testing: bool,
 }
 
 pub struct ShellModeState {
@@ -254,22 +556,97 @@ pub struct ShellModeState {
     pub progress_info: ProgressInfo,
     /// 是否最后一行是进度行（用于原地覆盖）
     pub last_line_is_progress: bool,
+    /// 是否自动滚动跟随最新输出（f 切换）
+    pub follow: bool,
     /// 最终结果
     pub output: Option<UpdateOutput>,
+    /// 本地软件包文件列表（PickingPackage 阶段）
+    pub local_packages: Vec<std::path::PathBuf>,
+    /// 本地软件包选中索引
+    pub local_selected: usize,
+    /// Tab 补全候选（完整输入行）
+    pub completions: Vec<String>,
+    /// 当前循环到的补全候选（None = 尚未开始循环）
+    pub completion_idx: Option<usize>,
+    /// 输出 "/" 搜索
+    pub search: LogSearch,
+}
+
+pub struct PacnewModeState {
+    /// 待合并的 .pacnew / .pacsave 文件
+    pub files: Vec<std::path::PathBuf>,
+    pub selected: usize,
+    pub view: PacnewView,
+    /// 在用配置 → 新文件的统一 diff（DIFF_* 前缀行）
+    pub diff: Vec<String>,
+    pub scroll: usize,
+    /// 等待 y 确认的操作
+    pub pending: Option<PacnewAction>,
+    /// 操作结果提示
+    pub message: Option<String>,
+    /// Esc 从列表返回的模式
+    pub return_mode: AppMode,
+}
+
+/// 事务计划：在安装 / 卸载模式中用 Ctrl+P 收集的包，先卸载后安装依次执行
+pub struct PlanModeState {
+    pub phase: PlanPhase,
+    /// 待卸载的包（按加入顺序）
+    pub remove: Vec<String>,
+    /// 待安装的包（按加入顺序）
+    pub install: Vec<String>,
+    /// 待安装包中来自 AUR 的（计划中不做 PKGBUILD 审查，预览中提示）
+    pub aur: HashSet<String>,
+    /// 卸载方式
+    pub variant: RemoveVariant,
+    /// 编辑列表中的选中项（卸载在前、安装在后）
+    pub selected: usize,
+    pub preview: Vec<String>,
+    /// 预览请求序号，只接受最近一次请求的结果
+    pub preview_seq: u64,
+    /// 预览是否仍在获取中（Esc 返回后置为 false，迟到的结果随之丢弃）
+    pub fetching: bool,
+    /// 预览含 DANGER 提示时，是否已按过第一次 Enter
+    pub danger_confirmed: bool,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+    pub follow: bool,
+    pub progress_info: ProgressInfo,
+    pub last_line_is_progress: bool,
+    /// 复制等操作的结果提示
+    pub message: Option<String>,
+    /// 上次执行是否全部成功，完成后 Esc 据此清空计划
+    pub succeeded: bool,
+    /// 执行结束后的 AI 分析是否进行中
+    pub analyzing: bool,
+    /// 最近一次 AI 分析的 token 用量
+    pub token_usage: Option<TokenUsage>,
+    pub report_path: Option<String>,
 }
 
 // ========== 子状态 impl ==========
 
+/// 切换视图并交换滚动位置：当前位置存入 other_scroll，恢复另一视图上次的位置
+fn toggle_view(view_mode: &mut ViewMode, scroll: &mut usize, other_scroll: &mut usize) {
+    *view_mode = match view_mode {
+        ViewMode::UpdateLog => ViewMode::AIAnalysis,
+        ViewMode::AIAnalysis => ViewMode::UpdateLog,
+    };
+    std::mem::swap(scroll, other_scroll);
+}
+
 /// 处理新到达的一行输出（共用）
 ///
 /// - `PROGRESS:` 前缀行：解析结构化进度信息，并在主面板中原地覆盖最后一行
 /// - 普通 `\n` 行：追加到 `lines[]`，并从 `(n/n)` 模式更新操作标签
+/// - `follow` 为 false 时不移动滚动位置
 fn handle_add_line(
     line: String,
     lines: &mut Vec<String>,
     scroll: &mut usize,
     progress_info: &mut ProgressInfo,
     last_line_is_progress: &mut bool,
+    follow: bool,
 ) {
     if let Some(content) = line.strip_prefix("PROGRESS:") {
         let content = content.trim().to_string();
@@ -282,7 +659,9 @@ fn handle_add_line(
         } else {
             lines.push(format!("PROGRESS_LINE:{}", content));
             *last_line_is_progress = true;
-            *scroll = lines.len().saturating_sub(1);
+            if follow {
+                *scroll = lines.len().saturating_sub(1);
+            }
         }
         return;
     }
@@ -291,13 +670,44 @@ fn handle_add_line(
     if let Some(label) = extract_action_label(&line) {
         progress_info.label = label.clone();
         progress_info.raw = label;
+        progress_info.percent = None;
     }
     lines.push(line);
-    if lines.len() > 1 {
+    if follow && lines.len() > 1 {
         *scroll = lines.len().saturating_sub(1);
     }
 }
 
+/// 切换跟随输出；重新开启时跳到最后一行
+fn toggle_follow(follow: &mut bool, scroll: &mut usize, lines: &[String]) {
+    *follow = !*follow;
+    if *follow {
+        *scroll = lines.len().saturating_sub(1);
+    }
+}
+
+/// 页脚中的跟随状态提示
+pub fn follow_label(follow: bool) -> &'static str {
+    if follow { t("common.follow_on") } else { t("common.follow_off") }
+}
+
+/// 解析设置页输入的温度，非数字或超出范围时返回提示
+fn parse_temperature(buf: &str) -> Result<f32, String> {
+    let t = buf
+        .trim()
+        .parse::<f32>()
+        .map_err(|_| format!("✗ 温度必须是数字: {}", buf))?;
+    if !TEMPERATURE_RANGE.contains(&t) {
+        return Err(format!(
+            "✗ 温度 {} 超出范围 ({:.1} - {:.1})",
+            t,
+            TEMPERATURE_RANGE.start(),
+            TEMPERATURE_RANGE.end()
+        ));
+    }
+    Ok(t)
+}
+
 fn extract_action_label(line: &str) -> Option<String> {
     let trimmed = line.trim();
     if trimmed.starts_with('(') {
@@ -353,21 +763,274 @@ impl UpdateModeState {
             progress: String::new(),
             progress_info: ProgressInfo::default(),
             last_line_is_progress: false,
+            follow: true,
             packages_before: None,
             packages_after: None,
             analysis: None,
+            token_usage: None,
             scroll: 0,
+            view_scroll: 0,
             report_path: None,
             preview: Vec::new(),
+            news: Vec::new(),
+            package_diff: Vec::new(),
+            analysis_task: None,
+            chat: Vec::new(),
+            chat_input: None,
+            chat_pending: false,
+            reboot_required: false,
+            kernel_update: None,
+            critical_updates: Vec::new(),
+            pacnew_files: Vec::new(),
+            search: LogSearch::default(),
+            download_total: 0,
+            downloaded: HashMap::new(),
+            download_started: None,
+            preview_selected: 0,
+            marked: HashSet::new(),
+            keyring_first: false,
+        }
+    }
+
+    /// Tab 切换日志 / AI 分析视图，两个视图各自保留滚动位置
+    pub fn toggle_view(&mut self) {
+        toggle_view(&mut self.view_mode, &mut self.scroll, &mut self.view_scroll);
+    }
+
+    /// 切到新生成的 AI 分析并滚到顶部，日志的滚动位置留待切回
+    pub fn show_analysis(&mut self) {
+        if self.view_mode == ViewMode::UpdateLog {
+            self.toggle_view();
+        }
+        self.scroll = 0;
+    }
+
+    /// 更新因 PGP 签名校验失败（可先更新密钥环后重试）
+    pub fn has_signature_error(&self) -> bool {
+        self.output
+            .as_ref()
+            .is_some_and(|o| !o.success && o.has_signature_error())
+    }
+
+    /// 是否为部分更新（预览中选中了部分包）
+    pub fn is_partial(&self) -> bool {
+        !self.marked.is_empty()
+    }
+
+    /// 本次要升级的预览行：部分更新时为选中的包，否则为全部
+    pub fn target_preview(&self) -> Vec<String> {
+        self.preview
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.marked.is_empty() || self.marked.contains(i))
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    /// 部分更新时传给 pacman -Sy 的包名，完整更新时为空
+    pub fn partial_targets(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            return Vec::new();
         }
+        self.target_preview()
+            .iter()
+            .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+            .collect()
     }
 
     pub fn get_content(&self) -> Vec<String> {
-        get_mode_content(&self.view_mode, &self.output, &self.lines, &self.analysis, "等待更新...")
+        let mut content =
+            get_mode_content(&self.view_mode, &self.output, &self.lines, &self.analysis, "等待更新...");
+        if self.view_mode == ViewMode::UpdateLog && self.output.is_some() {
+            content.extend(self.package_diff.iter().cloned());
+            content.extend(self.signature_notice());
+            content.extend(self.reboot_notice());
+            content.extend(self.pacnew_section());
+        }
+        if self.view_mode == ViewMode::AIAnalysis && (!self.chat.is_empty() || self.chat_pending) {
+            content.push(String::new());
+            content.push("── 追问 ──".to_string());
+            for message in &self.chat {
+                content.push(String::new());
+                let prefix = if message.role == "user" { "你: " } else { "AI: " };
+                for (i, line) in message.content.lines().enumerate() {
+                    if i == 0 {
+                        content.push(format!("{}{}", prefix, line));
+                    } else {
+                        content.push(format!("    {}", line));
+                    }
+                }
+            }
+            if self.chat_pending {
+                content.push(String::new());
+                content.push("AI: 思考中...".to_string());
+            }
+        }
+        content
+    }
+
+    /// 需要重启 / 关键组件更新的醒目提示
+    fn reboot_notice(&self) -> Vec<String> {
+        let mut notice = Vec::new();
+        if self.reboot_required {
+            notice.push(String::new());
+            notice.push(match &self.kernel_update {
+                Some((name, old, new)) => format!("DANGER:⚠ 需要重启：内核 {} 已从 {} 更新到 {}", name, old, new),
+                None => "DANGER:⚠ 需要重启：正在运行的内核已被替换，内核模块将无法加载".to_string(),
+            });
+        }
+        if !self.critical_updates.is_empty() {
+            if notice.is_empty() {
+                notice.push(String::new());
+            }
+            notice.push(format!(
+                "WARN:⚠ 关键组件已更新（{}），建议重启以完全生效",
+                self.critical_updates.join(", ")
+            ));
+        }
+        notice
+    }
+
+    /// 签名校验失败时的恢复建议
+    fn signature_notice(&self) -> Vec<String> {
+        if !self.has_signature_error() {
+            return Vec::new();
+        }
+        vec![
+            String::new(),
+            "DANGER:✗ 软件包 PGP 签名校验失败，通常是 archlinux-keyring 过旧所致".to_string(),
+            format!("  按 k 先更新 {} (-Sy {}) 再重新执行完整更新", crate::package_manager::KEYRING_PACKAGE, crate::package_manager::KEYRING_PACKAGE),
+        ]
+    }
+
+    /// 待合并配置文件段落
+    fn pacnew_section(&self) -> Vec<String> {
+        if self.pacnew_files.is_empty() {
+            return Vec::new();
+        }
+        let mut section = vec![
+            String::new(),
+            format!("── 待合并的配置文件: {} 个 ──", self.pacnew_files.len()),
+        ];
+        section.extend(self.pacnew_files.iter().map(|p| format!("WARN:  {}", p.display())));
+        section.push("  按 p 逐个查看差异并合并".to_string());
+        section
+    }
+
+    /// 根据 packages_before / packages_after 生成显式安装包差异段落
+    pub fn build_package_diff(&mut self) {
+        self.package_diff.clear();
+        let (before, after) = match (&self.packages_before, &self.packages_after) {
+            (Some(b), Some(a)) => (b, a),
+            _ => return,
+        };
+        let (added, removed) = crate::package_manager::diff_explicit_packages(before, after);
+        let changed = crate::package_manager::diff_explicit_versions(before, after);
+
+        self.package_diff.push(String::new());
+        self.package_diff.push(format!(
+            "── 显式安装包变化: +{} -{} ~{} ──",
+            added.len(),
+            removed.len(),
+            changed.len()
+        ));
+        if added.is_empty() && removed.is_empty() {
+            self.package_diff.push("  (无新增或移除的显式安装包)".to_string());
+        }
+        for pkg in &added {
+            self.package_diff.push(format!("DIFF_ADD:+ {}", pkg));
+        }
+        for pkg in &removed {
+            self.package_diff.push(format!("DIFF_DEL:- {}", pkg));
+        }
+        for (name, old, new) in &changed {
+            self.package_diff.push(format!("DIFF_CHG:~ {} {} -> {}", name, old, new));
+        }
+    }
+
+    /// 是否有最近发布的官方新闻
+    pub fn has_recent_news(&self) -> bool {
+        self.news.iter().any(|n| n.is_recent())
+    }
+
+    /// 将官方新闻追加到预览内容末尾
+    pub fn push_news_lines(&mut self) {
+        if self.news.is_empty() {
+            return;
+        }
+        self.lines.push(String::new());
+        self.lines.push("── Arch 官方新闻 ──".to_string());
+        for item in &self.news {
+            let mark = if item.is_recent() { "⚠ " } else { "  " };
+            self.lines.push(format!("{}{}  {}", mark, item.date_str(), item.title));
+            if item.is_recent() && !item.link.is_empty() {
+                self.lines.push(format!("              {}", item.link));
+            }
+        }
     }
 
     pub fn add_line(&mut self, line: String) {
-        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress);
+        let is_progress = line.starts_with("PROGRESS:");
+        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress, self.follow);
+        if is_progress {
+            self.record_download();
+        }
+    }
+
+    /// 切换跟随输出（f）
+    pub fn toggle_follow(&mut self) {
+        toggle_follow(&mut self.follow, &mut self.scroll, &self.lines);
+    }
+
+    /// 从刚解析的下载进度行记录该包已下载的字节数（跳过并行下载的 "Total (n/m)" 汇总行）
+    fn record_download(&mut self) {
+        let info = &self.progress_info;
+        if info.label.is_empty() || info.total_size.is_empty() || info.speed.is_empty() {
+            return;
+        }
+        let aggregate = info
+            .raw
+            .split_whitespace()
+            .any(|t| t.ends_with(')') && t.contains('/'));
+        if aggregate {
+            return;
+        }
+        let bytes = crate::package_manager::parse_size(&info.total_size);
+        self.download_started.get_or_insert_with(Instant::now);
+        self.downloaded.insert(info.label.clone(), bytes);
+    }
+
+    /// 整体下载进度 (已下载, 总计)，总大小未知或尚未开始下载时为 None
+    pub fn download_progress(&self) -> Option<(u64, u64)> {
+        if self.download_total == 0 || self.downloaded.is_empty() {
+            return None;
+        }
+        let done: u64 = self.downloaded.values().sum();
+        Some((done.min(self.download_total), self.download_total))
+    }
+
+    /// 整体下载百分比，用于状态栏进度条
+    pub fn download_percent(&self) -> Option<u8> {
+        let (done, total) = self.download_progress()?;
+        Some((done * 100 / total) as u8)
+    }
+
+    /// 整体下载状态栏文本：已下载 / 总计 百分比 剩余时间
+    pub fn download_footer_text(&self) -> Option<String> {
+        let (done, total) = self.download_progress()?;
+        let mut text = format!(
+            "{} / {} {}%",
+            crate::package_manager::format_size(done),
+            crate::package_manager::format_size(total),
+            done * 100 / total
+        );
+        // 按开始下载以来的平均速度估算剩余时间
+        let elapsed = self.download_started.map(|t| t.elapsed().as_secs_f64()).unwrap_or(0.0);
+        if done > 0 && done < total && elapsed >= 1.0 {
+            let remaining = ((total - done) as f64 / (done as f64 / elapsed)) as u64;
+            text.push_str(&format!(" 剩余 {:02}:{:02}", remaining / 60, remaining % 60));
+        }
+        Some(text)
     }
 
     pub fn scroll_up(&mut self) {
@@ -418,11 +1081,50 @@ impl QueryModeState {
             dirs: Vec::new(),
             file_mode: FileListMode::Files,
             detail_scroll: 0,
+            file_mode_scroll: 0,
             searching: false,
             search_seq: 0,
+            search_cancel: SearchCancel::default(),
             search_scheduled: None,
+            history: Vec::new(),
+            history_idx: None,
+            remote_all: Vec::new(),
+            repo_filter: None,
+            message: None,
+            marked: BTreeMap::new(),
+            remote_error: None,
+            optdeps: Vec::new(),
+            optdep_selected: 0,
+        }
+    }
+
+    /// 当前面板中选中的包
+    pub fn selected_package(&self) -> Option<&PackageInfo> {
+        match self.panel {
+            QueryPanel::Local => self.local_results.get(self.local_selected),
+            QueryPanel::Remote => self.remote_results.get(self.remote_selected),
+        }
+    }
+
+    /// 切换选中包的多选标记
+    pub fn toggle_marked(&mut self) {
+        if let Some(pkg) = self.selected_package().cloned() {
+            if self.marked.remove(&pkg.name).is_none() {
+                self.marked.insert(pkg.name.clone(), pkg);
+            }
         }
     }
+
+    /// 标记中已安装 / 未安装的包
+    pub fn marked_by_installed(&self, installed: bool) -> Vec<PackageInfo> {
+        self.marked.values().filter(|p| p.installed == installed).cloned().collect()
+    }
+
+    /// 按仓库筛选远程结果
+    pub fn apply_repo_filter(&mut self) {
+        self.remote_results = filter_by_repo(&self.remote_all, &self.repo_filter);
+        self.remote_selected = self.remote_selected.min(self.remote_results.len().saturating_sub(1));
+    }
 }
 
 impl InstallModeState {
@@ -434,28 +1136,122 @@ impl InstallModeState {
             results: Vec::new(),
             selected: 0,
             marked: HashSet::new(),
+            known_aur: HashSet::new(),
+            search_error: None,
             preview: Vec::new(),
             lines: Vec::new(),
             output: None,
             progress: String::new(),
             progress_info: ProgressInfo::default(),
             last_line_is_progress: false,
+            follow: true,
             analysis: None,
+            token_usage: None,
             scroll: 0,
+            view_scroll: 0,
             searching: false,
             search_seq: 0,
+            search_cancel: SearchCancel::default(),
             search_scheduled: None,
             view_mode: ViewMode::UpdateLog,
             report_path: None,
+            skipped: Vec::new(),
+            sort: SortKey::Default,
+            unsorted: Vec::new(),
+            repo_filter: None,
+            show_sizes: false,
+            sizes: HashMap::new(),
+            sizes_requested: HashSet::new(),
+            as_explicit: false,
+            pkgbuild: Vec::new(),
+            space_confirmed: false,
+            search: LogSearch::default(),
         }
     }
 
+    /// Tab 切换日志 / AI 分析视图，两个视图各自保留滚动位置
+    pub fn toggle_view(&mut self) {
+        toggle_view(&mut self.view_mode, &mut self.scroll, &mut self.view_scroll);
+    }
+
+    /// 切到新生成的 AI 分析并滚到顶部，日志的滚动位置留待切回
+    pub fn show_analysis(&mut self) {
+        if self.view_mode == ViewMode::UpdateLog {
+            self.toggle_view();
+        }
+        self.scroll = 0;
+    }
+
     pub fn get_content(&self) -> Vec<String> {
         get_mode_content(&self.view_mode, &self.output, &self.lines, &self.analysis, "等待安装...")
     }
 
+    /// 安装预览中是否提示根分区空间不足
+    pub fn has_space_warning(&self) -> bool {
+        self.preview.iter().any(|l| l.starts_with("DANGER:"))
+    }
+
     pub fn add_line(&mut self, line: String) {
-        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress);
+        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress, self.follow);
+    }
+
+    /// 切换跟随输出（f）
+    pub fn toggle_follow(&mut self) {
+        toggle_follow(&mut self.follow, &mut self.scroll, &self.lines);
+    }
+
+    /// 追加到 -S --noconfirm 之后的安装参数
+    pub fn install_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if self.as_explicit {
+            flags.push("--asexplicit".to_string());
+        }
+        flags
+    }
+
+    /// 设置新的搜索结果并按当前排序方式排序
+    pub fn set_results(&mut self, results: Vec<PackageInfo>) {
+        self.known_aur
+            .extend(results.iter().filter(|p| p.repo == "aur").map(|p| p.name.clone()));
+        self.unsorted = results;
+        self.apply_sort();
+    }
+
+    /// 第 idx 个结果是否已被标记
+    pub fn is_marked(&self, idx: usize) -> bool {
+        self.results.get(idx).is_some_and(|p| self.marked.contains(&p.name))
+    }
+
+    /// 切换第 idx 个结果的标记
+    pub fn toggle_marked(&mut self, idx: usize) {
+        if let Some(name) = self.results.get(idx).map(|p| p.name.clone()) {
+            if !self.marked.remove(&name) {
+                self.marked.insert(name);
+            }
+        }
+    }
+
+    /// 按当前仓库筛选和排序方式重排结果，选中行按名称保留
+    pub fn apply_sort(&mut self) {
+        let selected = self.results.get(self.selected).map(|p| p.name.clone());
+
+        self.results = filter_by_repo(&self.unsorted, &self.repo_filter);
+        match self.sort {
+            SortKey::Name => self.results.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortKey::Size => {
+                let sizes = &self.sizes;
+                self.results.sort_by_key(|p| {
+                    std::cmp::Reverse(
+                        sizes.get(&p.name).map(|s| crate::package_manager::parse_size(&s.installed)).unwrap_or(0),
+                    )
+                });
+            }
+            _ => {}
+        }
+
+        self.selected = selected
+            .and_then(|name| self.results.iter().position(|p| p.name == name))
+            .unwrap_or(0);
     }
 }
 
@@ -475,39 +1271,90 @@ impl RemoveModeState {
             progress: String::new(),
             progress_info: ProgressInfo::default(),
             last_line_is_progress: false,
+            follow: true,
             analysis: None,
+            token_usage: None,
             scroll: 0,
+            view_scroll: 0,
             loading: false,
             view_mode: ViewMode::UpdateLog,
             report_path: None,
+            sort: SortKey::Default,
+            variant: RemoveVariant::default(),
+            protected_confirmed: false,
+            orphans: false,
+            largest: false,
+            search: LogSearch::default(),
         }
     }
 
+    /// Tab 切换日志 / AI 分析视图，两个视图各自保留滚动位置
+    pub fn toggle_view(&mut self) {
+        toggle_view(&mut self.view_mode, &mut self.scroll, &mut self.view_scroll);
+    }
+
+    /// 切到新生成的 AI 分析并滚到顶部，日志的滚动位置留待切回
+    pub fn show_analysis(&mut self) {
+        if self.view_mode == ViewMode::UpdateLog {
+            self.toggle_view();
+        }
+        self.scroll = 0;
+    }
+
+    /// 卸载预览中是否包含受保护的系统关键包
+    pub fn has_protected(&self) -> bool {
+        self.preview.iter().any(|l| l.starts_with("DANGER:"))
+    }
+
     pub fn get_content(&self) -> Vec<String> {
         get_mode_content(&self.view_mode, &self.output, &self.lines, &self.analysis, "等待卸载...")
     }
 
     pub fn add_line(&mut self, line: String) {
-        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress);
+        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress, self.follow);
     }
 
-    /// 对卸载的包列表应用筛选
+    /// 切换跟随输出（f）
+    pub fn toggle_follow(&mut self) {
+        toggle_follow(&mut self.follow, &mut self.scroll, &self.lines);
+    }
+
+    /// 对卸载的包列表应用筛选，之前选中的包仍在结果中时保持选中
     pub fn apply_filter(&mut self) {
-        let keyword = self.input.to_lowercase();
-        if keyword.is_empty() {
+        let previous = self
+            .filtered
+            .get(self.selected)
+            .and_then(|&i| self.packages.get(i))
+            .map(|p| p.name.clone());
+        if self.input.trim().is_empty() {
             self.filtered = (0..self.packages.len()).collect();
         } else {
-            self.filtered = self.packages
-                .iter()
-                .enumerate()
-                .filter(|(_, pkg)| {
-                    pkg.name.to_lowercase().contains(&keyword)
-                        || pkg.description.to_lowercase().contains(&keyword)
-                })
-                .map(|(i, _)| i)
-                .collect();
-        }
-        self.selected = 0;
+            // 模糊匹配并按得分排序，包名子串匹配优先
+            self.filtered = crate::package_manager::fuzzy::rank(
+                &self.input,
+                self.packages.iter().map(|p| (p.name.as_str(), p.description.as_str())),
+            );
+        }
+        let packages = &self.packages;
+        match self.sort {
+            SortKey::Default => {}
+            SortKey::Name => self.filtered.sort_by(|&a, &b| packages[a].name.cmp(&packages[b].name)),
+            SortKey::Size => self.filtered.sort_by_key(|&i| {
+                std::cmp::Reverse(crate::package_manager::parse_size(&packages[i].size))
+            }),
+            SortKey::InstallDate => {
+                self.filtered.sort_by_key(|&i| std::cmp::Reverse(packages[i].install_date))
+            }
+            // 日期未知的排在最后
+            SortKey::InstallDateOldest => self
+                .filtered
+                .sort_by_key(|&i| (packages[i].install_date.is_none(), packages[i].install_date)),
+        }
+        // 列表按选中行滚动（见 render_package_list），保持 selected 即可保证其可见
+        let packages = &self.packages;
+        self.selected = previous
+            .and_then(|name| self.filtered.iter().position(|&i| packages[i].name == name))
+            .unwrap_or_else(|| self.selected.min(self.filtered.len().saturating_sub(1)));
         self.marked.retain(|idx| self.filtered.contains(idx));
     }
 }
@@ -522,6 +1369,12 @@ impl SettingsModeState {
             edit_cursor: 0,
             message: None,
             scroll: 0,
+            filter: String::new(),
+            filter_cursor: 0,
+            filter_editing: false,
+            filtered: Vec::new(),
+            reset_confirm: false,
+            api_testing: false,
         }
     }
 }
@@ -539,12 +1392,23 @@ impl ShellModeState {
             progress: String::new(),
             progress_info: ProgressInfo::default(),
             last_line_is_progress: false,
+            follow: true,
             output: None,
+            local_packages: Vec::new(),
+            local_selected: 0,
+            completions: Vec::new(),
+            completion_idx: None,
+            search: LogSearch::default(),
         }
     }
 
     pub fn add_line(&mut self, line: String) {
-        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress);
+        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress, self.follow);
+    }
+
+    /// 切换跟随输出（f）
+    pub fn toggle_follow(&mut self) {
+        toggle_follow(&mut self.follow, &mut self.scroll, &self.lines);
     }
 
     pub fn get_content(&self) -> Vec<String> {
@@ -556,6 +1420,109 @@ impl ShellModeState {
     }
 }
 
+impl PacnewModeState {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            selected: 0,
+            view: PacnewView::List,
+            diff: Vec::new(),
+            scroll: 0,
+            pending: None,
+            message: None,
+            return_mode: AppMode::Dashboard,
+        }
+    }
+}
+
+impl PlanModeState {
+    pub fn new() -> Self {
+        Self {
+            phase: PlanPhase::Editing,
+            remove: Vec::new(),
+            install: Vec::new(),
+            aur: HashSet::new(),
+            variant: RemoveVariant::default(),
+            selected: 0,
+            preview: Vec::new(),
+            preview_seq: 0,
+            fetching: false,
+            danger_confirmed: false,
+            lines: Vec::new(),
+            scroll: 0,
+            follow: true,
+            progress_info: ProgressInfo::default(),
+            last_line_is_progress: false,
+            message: None,
+            succeeded: false,
+            analyzing: false,
+            token_usage: None,
+            report_path: None,
+        }
+    }
+
+    /// 清空计划；预览序号继续递增，清空前仍在获取的预览不会被误认为新结果
+    pub fn reset(&mut self) {
+        let seq = self.preview_seq;
+        *self = Self::new();
+        self.preview_seq = seq;
+    }
+
+    pub fn len(&self) -> usize {
+        self.remove.len() + self.install.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 加入待安装列表；已在待卸载列表中的包改为安装
+    pub fn add_install(&mut self, names: &[String]) {
+        for name in names {
+            self.remove.retain(|n| n != name);
+            if !self.install.contains(name) {
+                self.install.push(name.clone());
+            }
+        }
+    }
+
+    /// 加入待卸载列表；已在待安装列表中的包改为卸载
+    pub fn add_remove(&mut self, names: &[String]) {
+        for name in names {
+            self.install.retain(|n| n != name);
+            self.aur.remove(name);
+            if !self.remove.contains(name) {
+                self.remove.push(name.clone());
+            }
+        }
+    }
+
+    /// 移除编辑列表中选中的一项
+    pub fn remove_selected(&mut self) {
+        if self.selected < self.remove.len() {
+            self.remove.remove(self.selected);
+        } else if let Some(i) = self.selected.checked_sub(self.remove.len()).filter(|&i| i < self.install.len()) {
+            let name = self.install.remove(i);
+            self.aur.remove(&name);
+        }
+        self.selected = self.selected.min(self.len().saturating_sub(1));
+    }
+
+    /// 预览中是否有需要二次确认的提示（受保护包、空间不足）
+    pub fn has_danger(&self) -> bool {
+        self.preview.iter().any(|l| l.starts_with("DANGER:"))
+    }
+
+    pub fn add_line(&mut self, line: String) {
+        handle_add_line(line, &mut self.lines, &mut self.scroll, &mut self.progress_info, &mut self.last_line_is_progress, self.follow);
+    }
+
+    /// 切换跟随输出（f）
+    pub fn toggle_follow(&mut self) {
+        toggle_follow(&mut self.follow, &mut self.scroll, &self.lines);
+    }
+}
+
 // ========== App ==========
 
 pub struct App {
@@ -565,7 +1532,37 @@ pub struct App {
     pub system_info: Option<SystemInfo>,
     pub error_message: Option<String>,
     pub should_quit: bool,
+    /// 快捷键帮助浮层是否显示
+    pub show_help: bool,
+    /// 操作进行中按了 Ctrl+C，等待再次确认退出
+    pub quit_confirm: bool,
+    /// AI 分析视图显示 markdown 原文（false 时渲染标题、粗体、代码块等）
+    pub markdown_raw: bool,
     pub installed_count: Option<usize>,
+    /// 已安装包构成缓存
+    pub package_counts: Option<PackageCounts>,
+    /// 可用更新缓存（启动时后台检查，进入更新模式时复用），None 表示尚无结果
+    pub available_updates: Option<Vec<String>>,
+    /// available_updates 的获取时间，超过 update::UPDATES_CACHE_TTL 后进入更新模式时重新检查
+    pub available_updates_at: Option<Instant>,
+    /// 后台检查更新进行中
+    pub checking_updates: bool,
+    /// 后台检查更新失败原因
+    pub update_check_error: Option<String>,
+    /// 孤立包数量缓存
+    pub orphan_count: Option<usize>,
+    /// 包缓存大小缓存（字节）
+    pub cache_size: Option<u64>,
+    /// 占用空间最大的已安装包缓存（降序）
+    pub largest_packages: Option<Vec<InstalledPackage>>,
+    /// pacman.log 中最近的包操作缓存（最新的在前）
+    pub recent_changes: Option<Vec<PacmanLogEntry>>,
+    /// 操作进行中刷新 sudo 凭证，操作结束时置 None 停止
+    pub sudo_keepalive: Option<crate::package_manager::SudoKeepalive>,
+    /// 启动时读取的 /etc/pacman.conf（仓库、IgnorePkg、CacheDir）
+    pub pacman_conf: PacmanConf,
+    /// 配置文件对其他用户可读时的权限位，显示在仪表盘上
+    pub insecure_config_mode: Option<u32>,
     // 子状态
     pub update: UpdateModeState,
     pub query: QueryModeState,
@@ -573,10 +1570,17 @@ pub struct App {
     pub remove: RemoveModeState,
     pub settings: SettingsModeState,
     pub shell: ShellModeState,
+    pub pacnew: PacnewModeState,
+    /// 跨模式保留的事务计划
+    pub plan: PlanModeState,
 }
 
 impl App {
     pub fn new(config: Config) -> Self {
+        let mut query = QueryModeState::new();
+        query.history = crate::history::load(crate::history::QUERY_HISTORY_FILE);
+        let mut shell = ShellModeState::new();
+        shell.history = crate::history::load(crate::history::SHELL_HISTORY_FILE);
         Self {
             mode: AppMode::Dashboard,
             config,
@@ -584,13 +1588,30 @@ impl App {
             system_info: None,
             error_message: None,
             should_quit: false,
+            show_help: false,
+            quit_confirm: false,
+            markdown_raw: false,
             installed_count: None,
+            package_counts: None,
+            available_updates: None,
+            available_updates_at: None,
+            checking_updates: false,
+            update_check_error: None,
+            orphan_count: None,
+            cache_size: None,
+            largest_packages: None,
+            recent_changes: None,
+            insecure_config_mode: Config::insecure_permissions(),
+            sudo_keepalive: None,
+            pacman_conf: crate::package_manager::parse_pacman_conf(),
             update: UpdateModeState::new(),
-            query: QueryModeState::new(),
+            query,
             install: InstallModeState::new(),
             remove: RemoveModeState::new(),
             settings: SettingsModeState::new(),
-            shell: ShellModeState::new(),
+            shell,
+            pacnew: PacnewModeState::new(),
+            plan: PlanModeState::new(),
         }
     }
 
@@ -600,13 +1621,57 @@ impl App {
         self.error_message = None;
     }
 
+    /// 当前配色（随设置页切换实时生效）
+    pub fn theme(&self) -> super::theme::Theme {
+        super::theme::Theme::from_config(&self.config)
+    }
+
+    /// 当前是否有 pacman / 命令子进程在运行（此时退出需确认）
+    pub fn is_busy(&self) -> bool {
+        match self.mode {
+            AppMode::Update => self.update.phase == UpdatePhase::Updating,
+            AppMode::Install => self.install.phase == InstallPhase::Installing,
+            AppMode::Remove => self.remove.phase == RemovePhase::Removing,
+            AppMode::Shell => self.shell.phase == ShellPhase::Running,
+            AppMode::Plan => self.plan.phase == PlanPhase::Running,
+            AppMode::Dashboard | AppMode::Query | AppMode::Settings | AppMode::Pacnew => false,
+        }
+    }
+
+    /// 操作结束时发送桌面通知（配置开启时）；error 为 Some 表示失败
+    pub fn notify_finished(&self, operation: &str, count: usize, error: Option<&str>) {
+        if self.config.notifications {
+            crate::notify::operation_finished(operation, count, error);
+        }
+    }
+
+    /// 当前是否处于文本输入状态（此时 ? 等字符作为普通输入）
+    pub fn is_text_input(&self) -> bool {
+        match self.mode {
+            AppMode::Dashboard | AppMode::Pacnew | AppMode::Plan => false,
+            AppMode::Update => self.update.chat_input.is_some() || self.update.search.is_editing(),
+            AppMode::Install => self.install.phase == InstallPhase::Searching,
+            AppMode::Remove => self.remove.phase == RemovePhase::Browsing,
+            AppMode::Query => self.query.view == QueryView::List,
+            AppMode::Settings => self.settings.editing || self.settings.filter_editing,
+            AppMode::Shell => {
+                self.shell.phase == ShellPhase::Input || self.shell.search.is_editing()
+            }
+        }
+    }
+
     /// 重置查询相关状态
     pub fn reset_query_state(&mut self) {
+        // 保留搜索历史
+        let history = std::mem::take(&mut self.query.history);
+        self.query.search_cancel.cancel();
         self.query = QueryModeState::new();
+        self.query.history = history;
     }
 
     /// 重置安装相关状态
     pub fn reset_install_state(&mut self) {
+        self.install.search_cancel.cancel();
         self.install = InstallModeState::new();
         self.error_message = None;
     }
@@ -645,36 +1710,83 @@ impl App {
                 key: "ai.remove".to_string(),
                 value: self.config.ai.remove,
             },
+            SettingsItem::Toggle {
+                label: "缓存相同内容的分析结果".to_string(),
+                key: "ai.cache".to_string(),
+                value: self.config.ai.cache,
+            },
+            SettingsItem::Toggle {
+                label: "发送前隐藏用户名和家目录".to_string(),
+                key: "redact_prompts".to_string(),
+                value: self.config.redact_prompts,
+            },
             SettingsItem::Section("AI 配置".to_string()),
+            SettingsItem::Select {
+                label: "服务商".to_string(),
+                key: "provider".to_string(),
+                options: Provider::ALL.iter().map(|p| p.name().to_string()).collect(),
+                index: Provider::ALL
+                    .iter()
+                    .position(|p| *p == self.config.provider)
+                    .unwrap_or(0),
+            },
+            SettingsItem::Select {
+                label: "输出语言".to_string(),
+                key: "analysis_language".to_string(),
+                options: ANALYSIS_LANGUAGES.iter().map(|(_, name)| name.to_string()).collect(),
+                index: ANALYSIS_LANGUAGES
+                    .iter()
+                    .position(|(code, _)| *code == self.config.analysis_language)
+                    .unwrap_or(0),
+            },
             SettingsItem::TextEdit {
                 label: "模型".to_string(),
                 key: "model".to_string(),
                 value: self.config.model.clone(),
                 masked: false,
+                multiline: false,
             },
             SettingsItem::TextEdit {
                 label: "温度".to_string(),
                 key: "temperature".to_string(),
                 value: format!("{}", self.config.temperature),
                 masked: false,
+                multiline: false,
+            },
+            SettingsItem::TextEdit {
+                label: "Token 单价/1K".to_string(),
+                key: "token_price".to_string(),
+                value: self.config.token_price.map(|p| p.to_string()).unwrap_or_default(),
+                masked: false,
+                multiline: false,
             },
             SettingsItem::TextEdit {
                 label: "API 地址".to_string(),
                 key: "api_url".to_string(),
                 value: self.config.api_url.clone().unwrap_or_default(),
                 masked: false,
+                multiline: false,
             },
             SettingsItem::TextEdit {
                 label: "API Key".to_string(),
                 key: "api_key".to_string(),
                 value: self.config.api_key.clone().unwrap_or_default(),
                 masked: true,
+                multiline: false,
+            },
+            SettingsItem::TextEdit {
+                label: "系统提示词".to_string(),
+                key: "system_prompt".to_string(),
+                value: self.config.system_prompt.clone(),
+                masked: false,
+                multiline: true,
             },
             SettingsItem::TextEdit {
                 label: "代理".to_string(),
                 key: "proxy".to_string(),
                 value: self.config.proxy.clone().unwrap_or_default(),
                 masked: false,
+                multiline: false,
             },
             SettingsItem::Section("报告".to_string()),
             SettingsItem::TextEdit {
@@ -682,12 +1794,135 @@ impl App {
                 key: "report_dir".to_string(),
                 value: self.config.report_dir.display().to_string(),
                 masked: false,
+                multiline: false,
+            },
+            SettingsItem::Select {
+                label: "保存方式".to_string(),
+                key: "report_mode".to_string(),
+                options: ReportMode::ALL.iter().map(|m| m.name().to_string()).collect(),
+                index: ReportMode::ALL
+                    .iter()
+                    .position(|m| *m == self.config.report_mode)
+                    .unwrap_or(0),
+            },
+            SettingsItem::TextEdit {
+                label: "文件名模板".to_string(),
+                key: "report_filename".to_string(),
+                value: self.config.report_filename.clone(),
+                masked: false,
+                multiline: false,
+            },
+            SettingsItem::TextEdit {
+                label: "最多保留报告数".to_string(),
+                key: "max_reports".to_string(),
+                value: self.config.max_reports.to_string(),
+                masked: false,
+                multiline: false,
+            },
+            SettingsItem::Select {
+                label: "报告格式".to_string(),
+                key: "report_format".to_string(),
+                options: ReportFormat::ALL.iter().map(|f| f.name().to_string()).collect(),
+                index: ReportFormat::ALL
+                    .iter()
+                    .position(|f| *f == self.config.report_format)
+                    .unwrap_or(0),
+            },
+            SettingsItem::Section("界面".to_string()),
+            SettingsItem::Select {
+                label: "界面语言".to_string(),
+                key: "language".to_string(),
+                options: UI_LANGUAGES.iter().map(|(_, name)| name.to_string()).collect(),
+                index: UI_LANGUAGES
+                    .iter()
+                    .position(|(code, _)| *code == self.config.language)
+                    .unwrap_or(0),
+            },
+            SettingsItem::Select {
+                label: "配色".to_string(),
+                key: "theme".to_string(),
+                options: ThemePreset::ALL.iter().map(|t| t.name().to_string()).collect(),
+                index: ThemePreset::ALL
+                    .iter()
+                    .position(|t| *t == self.config.theme)
+                    .unwrap_or(0),
+            },
+            SettingsItem::Toggle {
+                label: "操作完成时发送桌面通知".to_string(),
+                key: "notifications".to_string(),
+                value: self.config.notifications,
+            },
+            SettingsItem::Section("更新".to_string()),
+            SettingsItem::TextEdit {
+                label: "更新前执行的命令".to_string(),
+                key: "pre_update_command".to_string(),
+                value: self.config.pre_update_command.clone().unwrap_or_default(),
+                masked: false,
+                multiline: false,
+            },
+            SettingsItem::TextEdit {
+                label: "提权命令 (sudo / doas)".to_string(),
+                key: "privilege_command".to_string(),
+                value: self.config.privilege_command.clone(),
+                masked: false,
+                multiline: false,
+            },
+            SettingsItem::TextEdit {
+                label: "sudo 凭证刷新间隔 (秒)".to_string(),
+                key: "sudo_keepalive_secs".to_string(),
+                value: self.config.sudo_keepalive_secs.to_string(),
+                masked: false,
+                multiline: false,
+            },
+            SettingsItem::Section("安装".to_string()),
+            SettingsItem::TextEdit {
+                label: "最少剩余空间 (MB)".to_string(),
+                key: "min_free_space_mb".to_string(),
+                value: self.config.min_free_space_mb.to_string(),
+                masked: false,
+                multiline: false,
+            },
+            SettingsItem::Section("导出".to_string()),
+            SettingsItem::TextEdit {
+                label: "包列表路径".to_string(),
+                key: "export_path".to_string(),
+                value: self.config.export_path.clone(),
+                masked: false,
+                multiline: false,
             },
         ];
         self.settings.selected = 0;
         self.settings.editing = false;
         self.settings.message = None;
         self.settings.scroll = 0;
+        self.apply_settings_filter();
+        self.show_config_errors();
+    }
+
+    /// 按名称筛选设置项，当前选中项仍匹配时保持选中，否则选中第一个匹配项
+    pub fn apply_settings_filter(&mut self) {
+        let needle = self.settings.filter.trim().to_lowercase();
+        self.settings.filtered = self.settings.items.iter()
+            .filter(|item| !matches!(item, SettingsItem::Section(_)))
+            .enumerate()
+            .filter(|(_, item)| needle.is_empty() || item.label().to_lowercase().contains(&needle))
+            .map(|(i, _)| i)
+            .collect();
+        if !self.settings.filtered.contains(&self.settings.selected) {
+            if let Some(&first) = self.settings.filtered.first() {
+                self.settings.selected = first;
+            }
+        }
+    }
+
+    /// 在筛选结果中移动选中项
+    pub fn move_settings_selection(&mut self, down: bool) {
+        let filtered = &self.settings.filtered;
+        let Some(pos) = filtered.iter().position(|&i| i == self.settings.selected) else {
+            return;
+        };
+        let next = if down { (pos + 1).min(filtered.len() - 1) } else { pos.saturating_sub(1) };
+        self.settings.selected = filtered[next];
     }
 
     /// 切换 Toggle 项的值并同步到 config
@@ -708,12 +1943,69 @@ impl App {
                     "ai.update" => self.config.ai.update = new_val,
                     "ai.install" => self.config.ai.install = new_val,
                     "ai.remove" => self.config.ai.remove = new_val,
+                    "ai.cache" => self.config.ai.cache = new_val,
+                    "redact_prompts" => self.config.redact_prompts = new_val,
+                    "notifications" => self.config.notifications = new_val,
                     _ => {}
                 }
             }
         }
     }
 
+    /// 循环切换 Select 项并同步到 config
+    pub fn cycle_settings_select(&mut self, forward: bool) {
+        let focusable: Vec<usize> = self.settings.items.iter().enumerate()
+            .filter(|(_, item)| !matches!(item, SettingsItem::Section(_)))
+            .map(|(i, _)| i)
+            .collect();
+
+        let Some(&real_idx) = focusable.get(self.settings.selected) else {
+            return;
+        };
+        let (key, new_index) = match &mut self.settings.items[real_idx] {
+            SettingsItem::Select { key, options, index, .. } => {
+                let len = options.len().max(1);
+                *index = if forward { (*index + 1) % len } else { (*index + len - 1) % len };
+                (key.clone(), *index)
+            }
+            _ => return,
+        };
+
+        if key == "analysis_language" {
+            self.config.analysis_language = ANALYSIS_LANGUAGES[new_index].0.to_string();
+        } else if key == "language" {
+            self.config.language = UI_LANGUAGES[new_index].0.to_string();
+            super::i18n::set_language(&self.config.language);
+        } else if key == "report_format" {
+            self.config.report_format = ReportFormat::ALL[new_index];
+        } else if key == "theme" {
+            self.config.theme = ThemePreset::ALL[new_index];
+        } else if key == "report_mode" {
+            self.config.report_mode = ReportMode::ALL[new_index];
+        } else if key == "provider" {
+            let provider = Provider::ALL[new_index];
+            self.config.provider = provider;
+            // 自定义服务商保留当前地址和模型，其余使用预设
+            if provider != Provider::Custom {
+                self.config.api_url = Some(provider.base_url().to_string());
+                self.config.model = provider.default_model().to_string();
+                self.set_settings_text("api_url", provider.base_url());
+                self.set_settings_text("model", provider.default_model());
+            }
+        }
+    }
+
+    /// 更新指定 key 的 TextEdit 显示值
+    fn set_settings_text(&mut self, target: &str, new_value: &str) {
+        for item in &mut self.settings.items {
+            if let SettingsItem::TextEdit { key, value, .. } = item {
+                if key == target {
+                    *value = new_value.to_string();
+                }
+            }
+        }
+    }
+
     /// 开始编辑 TextEdit 项
     pub fn start_settings_edit(&mut self) {
         let focusable: Vec<usize> = self.settings.items.iter().enumerate()
@@ -737,17 +2029,45 @@ impl App {
             .map(|(i, _)| i)
             .collect();
 
+        self.settings.message = None;
         if let Some(&real_idx) = focusable.get(self.settings.selected) {
             let buf = self.settings.edit_buffer.clone();
+            // 温度无效时保持编辑状态，提示后由用户修改
+            let temperature = match &self.settings.items[real_idx] {
+                SettingsItem::TextEdit { key, .. } if key == "temperature" => {
+                    match parse_temperature(&buf) {
+                        Ok(t) => Some(t),
+                        Err(msg) => {
+                            self.settings.message = Some(msg);
+                            return;
+                        }
+                    }
+                }
+                _ => None,
+            };
             if let SettingsItem::TextEdit { key, value, .. } = &mut self.settings.items[real_idx] {
                 *value = buf.clone();
                 match key.as_str() {
                     "model" => self.config.model = buf,
                     "temperature" => {
-                        if let Ok(t) = buf.parse::<f32>() {
+                        if let Some(t) = temperature {
                             self.config.temperature = t;
                         }
                     }
+                    "token_price" => {
+                        if buf.trim().is_empty() {
+                            self.config.token_price = None;
+                        } else {
+                            match buf.trim().parse::<f64>() {
+                                Ok(p) => self.config.token_price = Some(p),
+                                Err(_) => {
+                                    *value = self.config.token_price.map(|p| p.to_string()).unwrap_or_default();
+                                    self.settings.message =
+                                        Some(format!("✗ Token 单价必须是数字: {}", buf));
+                                }
+                            }
+                        }
+                    }
                     "api_url" => {
                         self.config.api_url = if buf.is_empty() { None } else { Some(buf) };
                     }
@@ -757,21 +2077,97 @@ impl App {
                     "proxy" => {
                         self.config.proxy = if buf.is_empty() { None } else { Some(buf) };
                     }
+                    "pre_update_command" => {
+                        self.config.pre_update_command =
+                            if buf.trim().is_empty() { None } else { Some(buf) };
+                    }
                     "report_dir" => {
                         self.config.report_dir = std::path::PathBuf::from(buf);
                     }
+                    "report_filename" => {
+                        self.config.report_filename = if buf.trim().is_empty() {
+                            *value = crate::config::default_report_filename();
+                            crate::config::default_report_filename()
+                        } else {
+                            buf
+                        };
+                    }
+                    "max_reports" => match buf.trim().parse::<usize>() {
+                        Ok(n) => self.config.max_reports = n,
+                        Err(_) => {
+                            *value = self.config.max_reports.to_string();
+                            self.settings.message =
+                                Some(format!("✗ 报告数量必须是非负整数 (0 = 不限制): {}", buf));
+                        }
+                    },
+                    "privilege_command" => {
+                        let cmd = buf.trim();
+                        self.config.privilege_command =
+                            if cmd.is_empty() { "sudo".to_string() } else { cmd.to_string() };
+                        *value = self.config.privilege_command.clone();
+                        crate::package_manager::privilege::set_command(&self.config.privilege_command);
+                    }
+                    "sudo_keepalive_secs" => match buf.trim().parse::<u64>() {
+                        Ok(n) => self.config.sudo_keepalive_secs = n,
+                        Err(_) => {
+                            *value = self.config.sudo_keepalive_secs.to_string();
+                            self.settings.message =
+                                Some(format!("✗ 刷新间隔必须是非负整数 (秒，0 = 不刷新): {}", buf));
+                        }
+                    },
+                    "min_free_space_mb" => match buf.trim().parse::<u64>() {
+                        Ok(n) => self.config.min_free_space_mb = n,
+                        Err(_) => {
+                            *value = self.config.min_free_space_mb.to_string();
+                            self.settings.message =
+                                Some(format!("✗ 剩余空间必须是非负整数 (MB): {}", buf));
+                        }
+                    },
+                    "export_path" => {
+                        self.config.export_path = buf;
+                    }
+                    "system_prompt" => {
+                        self.config.system_prompt = buf;
+                    }
                     _ => {}
                 }
             }
         }
         self.settings.editing = false;
+        if self.settings.message.is_none() {
+            self.show_config_errors();
+        }
+    }
+
+    /// 校验当前配置，有问题时显示在设置页 footer
+    fn show_config_errors(&mut self) {
+        if let Err(errors) = self.config.validate() {
+            self.settings.message = Some(format!("✗ {}", errors.join("; ")));
+        } else if let Some(mode) = Config::insecure_permissions() {
+            self.settings.message = Some(format!(
+                "✗ 配置文件权限为 {:o}，其他用户可读，按 Ctrl+P 改为 600",
+                mode
+            ));
+        }
+    }
+
+    /// 将配置文件权限改为 600
+    pub fn secure_config_permissions(&mut self) {
+        self.settings.message = Some(match Config::secure_permissions() {
+            Ok(()) => "✓ 配置文件权限已改为 600".to_string(),
+            Err(e) => format!("✗ 修改权限失败: {}", e),
+        });
+        self.insecure_config_mode = Config::insecure_permissions();
     }
 
     /// 保存配置到磁盘
     pub fn save_settings(&mut self) {
         match self.config.save() {
             Ok(()) => {
-                self.settings.message = Some("✓ 已保存到 ~/.config/lian/config.toml".to_string());
+                self.settings.message = Some(match &self.config.active_profile {
+                    Some(name) => format!("✓ 已保存到配置档案 [profiles.{}]", name),
+                    None => format!("✓ 已保存到 {}", crate::config::config_path().display()),
+                });
             }
             Err(e) => {
                 self.settings.message = Some(format!("✗ 保存失败: {}", e));
@@ -779,6 +2175,80 @@ impl App {
         }
     }
 
+    /// 将 API Key 移入系统密钥环，并从配置文件中清除
+    pub fn move_api_key_to_keyring(&mut self) {
+        match self.config.store_api_key_in_keyring() {
+            Ok(()) => {
+                self.set_settings_text("api_key", "");
+                self.settings.message =
+                    Some("✓ API Key 已存入系统密钥环，并已从配置文件中移除".to_string());
+            }
+            Err(e) => {
+                self.settings.message = Some(format!("✗ 存入密钥环失败: {}", e));
+            }
+        }
+    }
+
+    /// 恢复默认设置（保留 API Key），需 Ctrl+S 保存后才写入文件
+    pub fn reset_settings(&mut self) {
+        self.config.reset_preserving_key();
+        crate::package_manager::privilege::set_command(&self.config.privilege_command);
+        super::i18n::set_language(&self.config.language);
+        self.build_settings_items();
+        self.settings.message = Some("✓ 已恢复默认（API Key 已保留），Ctrl+S 保存".to_string());
+    }
+
+    /// 从磁盘重新加载配置，丢弃未保存的修改
+    pub fn reload_settings(&mut self) {
+        match Config::load_or_default(self.config.active_profile.as_deref()) {
+            Ok(config) => {
+                let selected = self.settings.selected;
+                self.config = config;
+                crate::package_manager::privilege::set_command(&self.config.privilege_command);
+                super::i18n::set_language(&self.config.language);
+                self.build_settings_items();
+                self.settings.selected = selected.min(self.settings_focusable_count().saturating_sub(1));
+                self.apply_settings_filter();
+                self.settings.message =
+                    Some("✓ 已重新加载配置，未保存的修改已丢弃".to_string());
+            }
+            Err(e) => {
+                self.settings.message = Some(format!("✗ 重新加载失败: {}", e));
+            }
+        }
+    }
+
+    /// 导出包列表到配置的路径（foreign = true 时仅导出外部包）
+    pub fn export_package_list(&mut self, foreign: bool) {
+        let pm = match &self.package_manager {
+            Some(pm) => pm,
+            None => {
+                self.settings.message = Some("✗ 包管理器尚未检测完成".to_string());
+                return;
+            }
+        };
+        let (path, result) = if foreign {
+            let path = self.config.foreign_export_path();
+            let result = pm.export_foreign(&path);
+            (path, result)
+        } else {
+            let path = self.config.explicit_export_path();
+            let result = pm.export_explicit(&path);
+            (path, result)
+        };
+        self.settings.message = Some(match result {
+            Ok(()) => format!("✓ 已导出: {}", path.display()),
+            Err(e) => format!("✗ 导出失败: {}", e),
+        });
+    }
+
+    /// 当前选中的设置项
+    pub fn selected_settings_item(&self) -> Option<&SettingsItem> {
+        self.settings.items.iter()
+            .filter(|item| !matches!(item, SettingsItem::Section(_)))
+            .nth(self.settings.selected)
+    }
+
     /// 获取可聚焦项数量
     pub fn settings_focusable_count(&self) -> usize {
         self.settings.items.iter()
@@ -1,16 +1,79 @@
-//! MTF flag 主题色定义，全局统一使用
+//! 界面配色：预设 + config 中的单项颜色覆盖，渲染时通过 App::theme() 获取
 
+use crate::config::{parse_hex_color, Config, ThemePreset};
 use ratatui::style::Color;
 
-/// 粉色 (MTF flag)
-pub const PINK: Color = Color::Rgb(245, 169, 184);
-/// 蓝色 (MTF flag)
-pub const BLUE: Color = Color::Rgb(91, 206, 250);
-/// 选中行背景色
-pub const SEL_BG: Color = Color::Rgb(45, 35, 55);
-/// 亮白色
-pub const BRIGHT_WHITE: Color = Color::Rgb(255, 255, 255);
-/// 暗灰色（次要信息）
-pub const DIM: Color = Color::Rgb(130, 130, 140);
-/// 描述文字灰色（选中行内）
-pub const DESC_DIM: Color = Color::Rgb(180, 180, 190);
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// 主色（MTF 预设为粉色）
+    pub primary: Color,
+    /// 辅色（MTF 预设为蓝色）
+    pub secondary: Color,
+    /// 选中行背景色
+    pub sel_bg: Color,
+    /// 正文
+    pub text: Color,
+    /// 暗灰色（次要信息）
+    pub dim: Color,
+    /// 描述文字灰色（选中行内）
+    pub desc_dim: Color,
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Mtf => Self {
+                primary: Color::Rgb(245, 169, 184),
+                secondary: Color::Rgb(91, 206, 250),
+                sel_bg: Color::Rgb(45, 35, 55),
+                text: Color::Rgb(255, 255, 255),
+                dim: Color::Rgb(130, 130, 140),
+                desc_dim: Color::Rgb(180, 180, 190),
+            },
+            ThemePreset::Nord => Self {
+                primary: Color::Rgb(136, 192, 208),
+                secondary: Color::Rgb(163, 190, 140),
+                sel_bg: Color::Rgb(59, 66, 82),
+                text: Color::Rgb(236, 239, 244),
+                dim: Color::Rgb(123, 136, 161),
+                desc_dim: Color::Rgb(216, 222, 233),
+            },
+            ThemePreset::Gruvbox => Self {
+                primary: Color::Rgb(254, 128, 25),
+                secondary: Color::Rgb(131, 165, 152),
+                sel_bg: Color::Rgb(60, 56, 54),
+                text: Color::Rgb(251, 241, 199),
+                dim: Color::Rgb(146, 131, 116),
+                desc_dim: Color::Rgb(213, 196, 161),
+            },
+            ThemePreset::Terminal => Self {
+                primary: Color::Magenta,
+                secondary: Color::Cyan,
+                sel_bg: Color::DarkGray,
+                text: Color::Reset,
+                dim: Color::DarkGray,
+                desc_dim: Color::Gray,
+            },
+        }
+    }
+
+    /// 按配置的预设构建，再应用 theme_colors 中格式正确的覆盖项
+    pub fn from_config(config: &Config) -> Self {
+        let mut theme = Self::preset(config.theme);
+        let colors = &config.theme_colors;
+        let slots = [
+            (&colors.primary, &mut theme.primary),
+            (&colors.secondary, &mut theme.secondary),
+            (&colors.selection, &mut theme.sel_bg),
+            (&colors.text, &mut theme.text),
+            (&colors.dim, &mut theme.dim),
+            (&colors.description, &mut theme.desc_dim),
+        ];
+        for (value, slot) in slots {
+            if let Some((r, g, b)) = value.as_deref().and_then(parse_hex_color) {
+                *slot = Color::Rgb(r, g, b);
+            }
+        }
+        theme
+    }
+}
@@ -1,6 +1,7 @@
+use super::i18n::{t, tf};
 use super::layout;
-use super::state::{App, AppEvent, UpdatePhase, ViewMode};
-use crate::deepseek::AiClient;
+use super::state::{follow_label, App, AppEvent, AppMode, UpdatePhase, ViewMode};
+use crate::deepseek::{AiClient, Message};
 use crate::prompt;
 use crate::report::ReportSaver;
 use crossterm::event::{KeyCode, KeyEvent};
@@ -11,23 +12,84 @@ use ratatui::{
 };
 use tokio::sync::mpsc;
 
+/// 预览中第一个包所在的行号（前面是 "找到 N 个可用更新" 与空行）
+const PREVIEW_FIRST_LINE: usize = 2;
+
 /// 处理更新模式的按键事件，返回 true 表示已消费该按键
 pub fn handle_update_key(
     key: KeyEvent,
     app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    api_key: &str,
     term_height: u16,
 ) -> bool {
+    if app.update.chat_input.is_some() {
+        return handle_chat_input_key(key, app, tx, api_key);
+    }
+    let content = app.update.get_content();
+    if app.update.search.handle_key(key, &content, &mut app.update.scroll) {
+        return true;
+    }
+
+    let previewing = app.update.phase == UpdatePhase::PreviewingUpdates && !app.update.preview.is_empty();
     match key.code {
+        KeyCode::Char(' ') if previewing => {
+            let i = app.update.preview_selected;
+            if !app.update.marked.remove(&i) {
+                app.update.marked.insert(i);
+            }
+            refresh_preview_line(app, i);
+            true
+        }
+        KeyCode::Char('k')
+            if previewing && crate::package_manager::has_keyring_update(&app.update.preview) =>
+        {
+            app.update.keyring_first = !app.update.keyring_first;
+            true
+        }
+        KeyCode::Up if previewing => {
+            move_preview_cursor(app, app.update.preview_selected.saturating_sub(1), term_height);
+            true
+        }
+        KeyCode::Down if previewing => {
+            move_preview_cursor(app, app.update.preview_selected + 1, term_height);
+            true
+        }
+        KeyCode::Char('f') if app.update.phase == UpdatePhase::Updating => {
+            app.update.toggle_follow();
+            true
+        }
+        KeyCode::Char('i')
+            if app.update.phase == UpdatePhase::AnalysisComplete && !app.update.chat_pending =>
+        {
+            if app.update.view_mode == ViewMode::UpdateLog {
+                app.update.toggle_view();
+            }
+            app.update.chat_input = Some(String::new());
+            true
+        }
         KeyCode::Tab => {
-            if app.update.phase == UpdatePhase::AnalysisComplete {
-                app.update.view_mode = match app.update.view_mode {
-                    ViewMode::UpdateLog => ViewMode::AIAnalysis,
-                    ViewMode::AIAnalysis => ViewMode::UpdateLog,
-                };
-                app.update.reset_scroll();
+            if matches!(app.update.phase, UpdatePhase::Analyzing | UpdatePhase::AnalysisComplete) {
+                app.update.toggle_view();
+                app.update.search.clear();
             }
             true
         }
+        KeyCode::Char('p')
+            if !app.update.pacnew_files.is_empty()
+                && matches!(
+                    app.update.phase,
+                    UpdatePhase::UpdateComplete | UpdatePhase::Analyzing | UpdatePhase::AnalysisComplete
+                ) =>
+        {
+            let files = app.update.pacnew_files.clone();
+            super::pacnew::open(app, files, AppMode::Update);
+            true
+        }
+        KeyCode::Char('r') if app.update.view_mode == ViewMode::AIAnalysis => {
+            app.markdown_raw = !app.markdown_raw;
+            true
+        }
         KeyCode::Up => {
             app.update.scroll_up();
             true
@@ -48,20 +110,268 @@ pub fn handle_update_key(
             app.update.scroll_page_down(10, content.len(), visible);
             true
         }
-        KeyCode::Char('y') => {
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.update.reset_scroll();
+            true
+        }
+        KeyCode::Char('G') | KeyCode::End => {
             let content = app.update.get_content();
-            let text = content.join("\n");
-            if layout::copy_to_clipboard(&text) {
-                app.update.progress = "✓ 已复制到剪贴板".to_string();
-            } else {
-                app.update.progress = "复制失败 (请确认已安装 wl-copy/xclip/xsel)".to_string();
-            }
+            let visible = layout::visible_content_height(term_height);
+            app.update.scroll = content.len().saturating_sub(visible);
+            true
+        }
+        KeyCode::Char('y') => {
+            let text = layout::plain_text(&app.update.get_content());
+            app.update.progress = match layout::copy_to_clipboard(&text) {
+                Ok(()) => "✓ 已复制到剪贴板".to_string(),
+                Err(e) => format!("复制失败: {}", e),
+            };
             true
         }
         _ => false,
     }
 }
 
+/// 追问输入状态按键处理
+fn handle_chat_input_key(
+    key: KeyEvent,
+    app: &mut App,
+    tx: &mpsc::Sender<AppEvent>,
+    api_key: &str,
+) -> bool {
+    let input = match app.update.chat_input.as_mut() {
+        Some(input) => input,
+        None => return false,
+    };
+    match key.code {
+        KeyCode::Esc => {
+            app.update.chat_input = None;
+        }
+        KeyCode::Enter => {
+            send_chat_question(app, tx, api_key);
+        }
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        KeyCode::Char(c) => {
+            input.push(c);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// 发送追问：以报告作为上文，连同历史问答一起发给 AI
+fn send_chat_question(app: &mut App, tx: &mpsc::Sender<AppEvent>, api_key: &str) {
+    let question = app.update.chat_input.take().unwrap_or_default();
+    let question = question.trim();
+    if question.is_empty() {
+        return;
+    }
+    let analysis = app.update.analysis.clone().unwrap_or_default();
+    app.update.chat.push(Message::user(question));
+
+    let mut messages = vec![
+        Message::user("请分析本次系统更新的日志，生成更新报告。"),
+        Message::assistant(analysis),
+    ];
+    messages.extend(app.update.chat.iter().cloned());
+
+    let client = AiClient::from_config(api_key, &app.config);
+    let model = app.config.get_model().to_string();
+    let temperature = app.config.temperature;
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        let event = match client.chat(messages, &model, temperature).await {
+            Ok((reply, _)) => AppEvent::ChatReply(reply),
+            Err(e) => AppEvent::ChatFailed(e.to_string()),
+        };
+        let _ = tx_clone.send(event).await;
+    });
+
+    app.update.chat_pending = true;
+    app.update.progress.clear();
+    // 跳到底部，由主循环 clamp
+    app.update.scroll = usize::MAX;
+}
+
+/// 处理追问回复
+pub fn handle_chat_reply(app: &mut App, reply: String) {
+    if !app.update.chat_pending {
+        return;
+    }
+    app.update.chat_pending = false;
+    app.update.chat.push(Message::assistant(reply));
+    app.update.scroll = usize::MAX;
+}
+
+/// 追问失败：撤回未得到回答的问题，保持对话历史一问一答，错误只显示在状态栏
+pub fn handle_chat_failed(app: &mut App, error: String) {
+    if !app.update.chat_pending {
+        return;
+    }
+    app.update.chat_pending = false;
+    if app.update.chat.last().is_some_and(|m| m.role == "user") {
+        app.update.chat.pop();
+    }
+    app.update.progress = format!("✗ 追问失败: {}", error);
+}
+
+/// 后台获取 Arch 官方新闻，网络失败时静默跳过
+pub fn spawn_news_task(app: &App, tx: &mpsc::Sender<AppEvent>) {
+    let proxy = app.config.proxy.clone();
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        match crate::news::fetch_arch_news(proxy.as_deref()).await {
+            Ok(news) => {
+                let _ = tx_clone.send(AppEvent::NewsLoaded(news)).await;
+            }
+            Err(e) => {
+                log::warn!("获取 Arch 新闻失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 将检查更新的结果写入更新模式的预览
+pub fn apply_update_preview(app: &mut App, updates: anyhow::Result<Vec<String>>) {
+    app.update.lines.clear();
+    app.update.marked.clear();
+    app.update.preview_selected = 0;
+    app.update.keyring_first = false;
+    let failed = match updates {
+        Ok(updates) => {
+            app.update.preview = updates;
+            None
+        }
+        Err(e) => {
+            app.update.preview.clear();
+            Some(e)
+        }
+    };
+    if let Some(e) = failed {
+        app.update.lines.push(format!("✗ 检查更新失败: {}", e));
+        app.update.lines.push("可检查网络后重新进入，或直接按 Enter 尝试更新。".to_string());
+    } else if app.update.preview.is_empty() {
+        app.update.lines.push("系统已是最新，没有可用更新。".to_string());
+    } else {
+        app.update.lines.push(format!("找到 {} 个可用更新：", app.update.preview.len()));
+        app.update.lines.push(String::new());
+        for i in 0..app.update.preview.len() {
+            let line = preview_line(app, i);
+            app.update.lines.push(line);
+        }
+        if let Some((kernel, old, new)) =
+            crate::package_manager::find_kernel_update(&app.update.preview)
+        {
+            app.update.lines.push(String::new());
+            app.update.lines.push(format!(
+                "⚠ 内核 {} 将从 {} 更新到 {}，完成后需要重启",
+                kernel, old, new
+            ));
+        }
+        let ignored = app.update.preview.iter()
+            .filter_map(|line| line.split_whitespace().next())
+            .filter(|name| app.pacman_conf.is_ignored(name))
+            .count();
+        if ignored > 0 {
+            app.update.lines.push(String::new());
+            app.update.lines.push(format!(
+                "灰色的 {} 个包在 pacman.conf 的 IgnorePkg 中，完整更新时会被跳过",
+                ignored
+            ));
+        }
+        app.update.keyring_first = crate::package_manager::has_keyring_update(&app.update.preview);
+        if app.update.keyring_first {
+            app.update.lines.push(String::new());
+            app.update.lines.push(format!(
+                "🔑 更新列表包含 {}，完整更新前会先单独更新密钥环，避免签名校验失败（k 切换）",
+                crate::package_manager::KEYRING_PACKAGE
+            ));
+        }
+    }
+    if let Some(hook) = &app.config.pre_update_command {
+        if !app.update.preview.is_empty() {
+            app.update.lines.push(String::new());
+            app.update.lines.push(format!("更新前将执行: {}（失败时中止更新）", hook));
+        }
+    }
+    app.update.push_news_lines();
+    app.update.phase = UpdatePhase::PreviewingUpdates;
+    app.update.reset_scroll();
+}
+
+/// 预览列表中第 i 个包的显示行：光标、选中标记、安全敏感包标记，IgnorePkg 中的包显示为灰色
+fn preview_line(app: &App, i: usize) -> String {
+    let pkg = &app.update.preview[i];
+    let cursor = if i == app.update.preview_selected { "▸" } else { " " };
+    let mark = if app.update.marked.contains(&i) { "[✓]" } else { "[ ]" };
+    let name = pkg.split_whitespace().next().unwrap_or("");
+    let lock = if crate::package_manager::is_security_sensitive(name) { "🔒" } else { "  " };
+    if app.pacman_conf.is_ignored(name) {
+        format!("DIM:{} {} {} {} (IgnorePkg)", cursor, mark, lock, pkg)
+    } else {
+        format!("{} {} {} {}", cursor, mark, lock, pkg)
+    }
+}
+
+/// 重绘预览中第 i 个包所在的行（包行紧跟在标题与空行之后）
+fn refresh_preview_line(app: &mut App, i: usize) {
+    let line = preview_line(app, i);
+    if let Some(slot) = app.update.lines.get_mut(PREVIEW_FIRST_LINE + i) {
+        *slot = line;
+    }
+}
+
+/// 移动预览光标，并让光标行保持在可见范围内
+fn move_preview_cursor(app: &mut App, target: usize, term_height: u16) {
+    let old = app.update.preview_selected;
+    app.update.preview_selected = target.min(app.update.preview.len().saturating_sub(1));
+    refresh_preview_line(app, old);
+    refresh_preview_line(app, app.update.preview_selected);
+    let line = PREVIEW_FIRST_LINE + app.update.preview_selected;
+    let visible = layout::visible_content_height(term_height).max(1);
+    if line < app.update.scroll {
+        app.update.scroll = line;
+    } else if line >= app.update.scroll + visible {
+        app.update.scroll = line + 1 - visible;
+    }
+}
+
+/// 可用更新缓存的有效期
+pub const UPDATES_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// 未过期的可用更新缓存
+pub fn cached_updates(app: &App) -> Option<Vec<String>> {
+    let fresh = app.available_updates_at.is_some_and(|at| at.elapsed() < UPDATES_CACHE_TTL);
+    app.available_updates.clone().filter(|_| fresh)
+}
+
+/// 安装 / 卸载改变了本地包后丢弃可用更新缓存并重新检查
+pub fn invalidate_update_cache(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    app.available_updates = None;
+    app.available_updates_at = None;
+    spawn_update_check(app, tx);
+}
+
+/// 后台检查可用更新，结果同时用于仪表盘计数和更新模式预览；已有检查进行中时不重复启动
+pub fn spawn_update_check(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let Some(pm) = app.package_manager.clone() else {
+        return;
+    };
+    if app.checking_updates {
+        return;
+    }
+    app.checking_updates = true;
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        let updates = tokio::task::spawn_blocking(move || pm.check_updates())
+            .await
+            .unwrap_or_else(|e| Err(e.into()));
+        let _ = tx_clone.send(AppEvent::UpdatePreviewReady(updates)).await;
+    });
+}
+
 /// 启动更新异步任务
 pub fn spawn_update_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
     let pm = match app.package_manager.clone() {
@@ -69,16 +379,50 @@ pub fn spawn_update_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
         None => return,
     };
     let tx_clone = tx.clone();
+    let hook = app.config.pre_update_command.clone();
+    let targets = app.update.partial_targets();
+    // 密钥环优先只用于完整更新，部分更新按所选包执行
+    let keyring_first = app.update.keyring_first && targets.is_empty();
+    let names: Vec<String> = app
+        .update
+        .target_preview()
+        .iter()
+        .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+        .collect();
+    app.update.download_total = 0;
+    app.update.downloaded.clear();
+    app.update.download_started = None;
     app.update.phase = UpdatePhase::Updating;
+    app.update.output = None;
+    app.update.package_diff.clear();
     app.update.lines.clear();
-    app.update.lines.push("正在执行更新...".to_string());
+    if targets.is_empty() {
+        app.update.lines.push("正在执行更新...".to_string());
+    } else {
+        app.update.lines.push(format!("正在执行部分更新: {}", targets.join(" ")));
+        app.update
+            .lines
+            .push("WARN:部分更新会刷新数据库 (-Sy) 但不升级其余包，可能导致依赖库版本不一致".to_string());
+    }
 
     // 在 UI 线程提前重置取消标志，防止工作线程内部的重置覆盖用户封窗口期的取消请求
     crate::package_manager::reset_cancel();
 
-    std::thread::spawn(move || {
-        let packages_before = pm.get_explicit_packages().ok();
+    // 汇总预览中仓库包的下载大小，供整体进度条使用（AUR 包不计入）
+    let pm_sizes = pm.clone();
+    let tx_sizes = tx.clone();
+    tokio::spawn(async move {
+        let sizes = tokio::task::spawn_blocking(move || pm_sizes.package_sizes(&names))
+            .await
+            .unwrap_or_default();
+        let total: u64 = sizes
+            .values()
+            .map(|s| crate::package_manager::parse_size(&s.download))
+            .sum();
+        let _ = tx_sizes.send(AppEvent::UpdateDownloadSize(total)).await;
+    });
 
+    std::thread::spawn(move || {
         let (output_tx, mut output_rx) = tokio::sync::mpsc::unbounded_channel();
 
         let tx_for_lines = tx_clone.clone();
@@ -88,16 +432,37 @@ pub fn spawn_update_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
             }
         });
 
-        let result = pm.update_streaming(output_tx);
+        // 更新前钩子（快照等），输出与更新日志合并显示
+        if let Some(hook) = hook {
+            if let Err(e) = crate::package_manager::run_pre_update_hook(&hook, output_tx.clone()) {
+                let _ = tx_clone.blocking_send(AppEvent::Error(e.to_string()));
+                return;
+            }
+        }
+
+        let packages_before = pm.get_explicit_packages().ok();
+
+        let result = if keyring_first {
+            update_keyring_first(&pm, output_tx)
+        } else {
+            pm.update_streaming(&targets, output_tx)
+        };
 
         match result {
             Ok(output) => {
                 let packages_after = pm.get_explicit_packages().ok();
+                let success = output.success;
                 let _ = tx_clone.blocking_send(AppEvent::UpdateComplete {
                     output,
                     packages_before,
                     packages_after,
                 });
+                if success {
+                    let required = crate::sysinfo::reboot_required();
+                    let _ = tx_clone.blocking_send(AppEvent::RebootCheck(required));
+                    let pacnew = crate::package_manager::find_pacnew_files();
+                    let _ = tx_clone.blocking_send(AppEvent::PacnewFound(pacnew));
+                }
             }
             Err(e) => {
                 let _ =
@@ -107,6 +472,58 @@ pub fn spawn_update_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
     });
 }
 
+/// 更新出错或执行失败后可按 r 重试（需已检测到包管理器）
+pub fn can_retry(app: &App) -> bool {
+    let failed = match app.update.phase {
+        UpdatePhase::Error => true,
+        UpdatePhase::UpdateComplete => app.update.output.as_ref().is_some_and(|o| !o.success),
+        _ => false,
+    };
+    failed && app.package_manager.is_some()
+}
+
+/// 重新执行更新，之前的日志保留在新一次尝试的上方
+pub fn retry_update_task(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let mut previous = std::mem::take(&mut app.update.lines);
+    // 错误信息只显示在页脚，重试前写入日志一并保留
+    if let Some(msg) = app.error_message.take() {
+        previous.push(format!("DANGER:✗ {}", msg));
+    }
+    app.update.last_line_is_progress = false;
+    app.update.search.clear();
+    spawn_update_task(app, tx);
+    previous.push(String::new());
+    previous.push("DIFF_CHG:──────── 重试更新 ────────".to_string());
+    previous.append(&mut app.update.lines);
+    app.update.lines = previous;
+    app.update.scroll = app.update.lines.len().saturating_sub(1);
+}
+
+/// 先 -Sy archlinux-keyring 再 -Syu，两步的输出合并为一次更新的结果；密钥环更新失败时不再继续
+fn update_keyring_first(
+    pm: &crate::package_manager::PackageManager,
+    output_tx: mpsc::UnboundedSender<String>,
+) -> anyhow::Result<crate::package_manager::UpdateOutput> {
+    let keyring = pm.keyring_streaming(output_tx.clone())?;
+    if !keyring.success {
+        return Ok(keyring);
+    }
+    let update = pm.update_streaming(&[], output_tx)?;
+    Ok(crate::package_manager::UpdateOutput {
+        stdout: format!("{}\n{}", keyring.stdout, update.stdout),
+        stderr: format!("{}\n{}", keyring.stderr, update.stderr),
+        success: update.success,
+    })
+}
+
+/// 处理重启检测结果：结合更新列表中的内核/关键包判断是否需要重启
+pub fn handle_reboot_check(app: &mut App, required: bool) {
+    let targets = app.update.target_preview();
+    app.update.kernel_update = crate::package_manager::find_kernel_update(&targets);
+    app.update.reboot_required = required || app.update.kernel_update.is_some();
+    app.update.critical_updates = crate::package_manager::find_critical_updates(&targets);
+}
+
 /// 处理更新完成事件，启动 AI 分析
 pub fn handle_update_complete(
     app: &mut App,
@@ -129,21 +546,33 @@ pub fn handle_update_complete(
                 pkg_before,
                 pkg_after,
                 sys_info.as_ref(),
+                &app.config.analysis_language,
             );
 
-            let client = AiClient::new(
-                api_key.to_string(),
-                app.config.get_api_url().to_string(),
-                app.config.proxy.as_deref(),
-            );
-            let model = app.config.model.clone();
+            let client = AiClient::from_config(api_key, &app.config);
+            let model = app.config.get_model().to_string();
             let temperature = app.config.temperature;
             let tx_clone = tx.clone();
 
+            // 切到分析视图，流式片段到达即显示
+            app.update.analysis = Some(String::new());
+            app.update.show_analysis();
+
+            let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+            let tx_for_chunks = tx.clone();
             tokio::spawn(async move {
-                match client.analyze_update(&prompt_text, &model, temperature).await {
-                    Ok(analysis) => {
-                        let _ = tx_clone.send(AppEvent::AnalysisComplete(analysis)).await;
+                while let Some(chunk) = chunk_rx.recv().await {
+                    let _ = tx_for_chunks.send(AppEvent::AnalysisChunk(chunk)).await;
+                }
+            });
+
+            let handle = tokio::spawn(async move {
+                match client
+                    .analyze_update_stream(&prompt_text, &model, temperature, chunk_tx)
+                    .await
+                {
+                    Ok((analysis, usage)) => {
+                        let _ = tx_clone.send(AppEvent::AnalysisComplete { analysis, usage }).await;
                     }
                     Err(e) => {
                         let _ = tx_clone
@@ -152,6 +581,7 @@ pub fn handle_update_complete(
                     }
                 }
             });
+            app.update.analysis_task = Some(handle.abort_handle());
         } else if output.success {
             // AI 分析已关闭，在 stdout 中追加提示
         }
@@ -166,25 +596,52 @@ pub fn handle_update_complete(
     }
 }
 
+/// 追加流式分析片段
+pub fn handle_analysis_chunk(app: &mut App, chunk: String) {
+    // 取消后仍可能有残留片段到达，忽略
+    if app.update.phase != UpdatePhase::Analyzing {
+        return;
+    }
+    app.update.analysis.get_or_insert_with(String::new).push_str(&chunk);
+}
+
+/// 取消进行中的流式分析，保留已收到的部分内容（不保存报告）
+pub fn cancel_analysis(app: &mut App) {
+    if let Some(handle) = app.update.analysis_task.take() {
+        handle.abort();
+    }
+    let analysis = app.update.analysis.get_or_insert_with(String::new);
+    analysis.push_str("\n\n[AI 分析已取消]");
+    app.update.phase = UpdatePhase::AnalysisComplete;
+}
+
 /// 处理分析完成事件，保存报告
 pub fn handle_analysis_complete(
     app: &mut App,
     analysis: String,
+    usage: Option<crate::deepseek::TokenUsage>,
     tx: &mpsc::Sender<AppEvent>,
 ) {
+    // 已被用户取消
+    if app.update.phase != UpdatePhase::Analyzing {
+        return;
+    }
+    app.update.analysis_task = None;
     app.update.analysis = Some(analysis.clone());
+    app.update.token_usage = usage;
     app.update.phase = UpdatePhase::AnalysisComplete;
-    app.update.view_mode = ViewMode::AIAnalysis;
-    app.update.reset_scroll();
+    app.update.show_analysis();
 
-    let report_dir = app.config.report_dir.clone();
+    let saver = ReportSaver::from_config(&app.config);
+    let log = app.update.output.as_ref()
+        .map(|o| o.combined_output())
+        .unwrap_or_default();
     let distro_name = app.system_info.as_ref()
         .map(|info| info.distro.clone())
         .unwrap_or_else(|| "Linux".to_string());
     let tx_clone = tx.clone();
     tokio::spawn(async move {
-        let saver = ReportSaver::new(report_dir);
-        match saver.save(&analysis, &distro_name, "Syu") {
+        match saver.save(&analysis, &log, &distro_name, "Syu") {
             Ok(path) => {
                 let _ = tx_clone
                     .send(AppEvent::ReportSaved(path.display().to_string()))
@@ -208,25 +665,31 @@ pub fn render_update(f: &mut Frame, app: &App) {
 
 fn render_update_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let title = match app.update.phase {
-        UpdatePhase::PackageManagerCheck => "🔍 检测包管理器...",
-        UpdatePhase::PreviewingUpdates => "📝 可用更新列表",
-        UpdatePhase::Updating => "⚙️  正在更新系统...",
-        UpdatePhase::UpdateComplete => "✅ 更新完成",
-        UpdatePhase::Analyzing => "🤖 AI 分析中...",
-        UpdatePhase::AnalysisComplete => "✨ 分析完成",
-        UpdatePhase::Error => "❌ 错误",
+        UpdatePhase::PackageManagerCheck => t("update.title.check"),
+        UpdatePhase::PreviewingUpdates => t("update.title.preview"),
+        UpdatePhase::Updating => t("update.title.updating"),
+        UpdatePhase::UpdateComplete => t("update.title.complete"),
+        UpdatePhase::Analyzing => t("common.analyzing"),
+        UpdatePhase::AnalysisComplete => t("common.analysis_done"),
+        UpdatePhase::Error => t("common.error"),
+    };
+    let reboot = if app.update.reboot_required {
+        t("update.reboot")
+    } else {
+        ""
     };
 
     let pm_info = if let Some(pm) = &app.package_manager {
-        format!(" | 包管理器: {}", pm.name())
+        tf("common.pm_info", &[&pm.name()])
     } else {
         String::new()
     };
 
-    let header = ratatui::widgets::Paragraph::new(format!("{}{}", title, pm_info))
+    let color = if app.update.reboot_required { Color::Red } else { Color::Cyan };
+    let header = ratatui::widgets::Paragraph::new(format!("{}{}{}", title, pm_info, reboot))
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(color)
                 .add_modifier(Modifier::BOLD),
         )
         .block(ratatui::widgets::Block::default().borders(ratatui::widgets::Borders::ALL))
@@ -237,65 +700,143 @@ fn render_update_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 
 fn render_update_content(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let title = match app.update.view_mode {
-        ViewMode::UpdateLog => "更新日志 [Tab 切换到 AI 分析]",
-        ViewMode::AIAnalysis => "AI 分析报告 [Tab 切换到更新日志]",
+        ViewMode::UpdateLog => t("update.view.log"),
+        ViewMode::AIAnalysis => t("update.view.analysis"),
     };
 
     let content = app.update.get_content();
-    layout::render_scrollable_content(f, title, &content, app.update.scroll, area);
+    layout::render_searchable_content(
+        f,
+        title,
+        &content,
+        app.update.scroll,
+        area,
+        Some(&app.update.search),
+        app.update.view_mode == ViewMode::AIAnalysis && !app.markdown_raw,
+    );
 }
 
 fn render_update_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let owned_text: String;
     let footer_text = match app.update.phase {
-        UpdatePhase::PackageManagerCheck => "正在检测包管理器...",
+        UpdatePhase::PackageManagerCheck => t("update.footer.checking"),
         UpdatePhase::PreviewingUpdates => {
-            if app.update.preview.is_empty() {
-                "Esc 返回 | q 退出"
+            let base = if app.update.preview.is_empty() {
+                t("update.footer.no_updates")
             } else {
-                "按 Enter 开始更新 | Esc 返回 | ↑↓ 滚动"
+                t("update.footer.preview")
+            };
+            let keyring = if crate::package_manager::has_keyring_update(&app.update.preview) {
+                if app.update.keyring_first { t("update.footer.keyring_on") } else { t("update.footer.keyring_off") }
+            } else {
+                ""
+            };
+            if app.update.is_partial() {
+                owned_text = tf(
+                    "update.footer.partial",
+                    &[&app.update.marked.len(), &app.update.preview.len()],
+                );
+                &owned_text
+            } else if app.update.has_recent_news() {
+                owned_text = tf("update.footer.news", &[&format!("{}{}", base, keyring)]);
+                &owned_text
+            } else {
+                owned_text = format!("{}{}", base, keyring);
+                &owned_text
             }
         }
         UpdatePhase::Updating => {
             let pt = app.update.progress_info.footer_text();
-            if pt.is_empty() {
-                "更新进行中..."
-            } else {
-                owned_text = pt;
-                &owned_text
-            }
+            let status = match app.update.download_footer_text() {
+                Some(total) if pt.is_empty() => tf("update.footer.download", &[&total]),
+                Some(total) => format!("{} | {}", tf("update.footer.download", &[&total]), pt),
+                None if pt.is_empty() => t("update.footer.running").to_string(),
+                None => pt,
+            };
+            owned_text = format!("{} | {}", status, follow_label(app.update.follow));
+            &owned_text
         }
         UpdatePhase::UpdateComplete => {
             if !app.update.progress.is_empty() && (app.update.progress.starts_with('✓') || app.update.progress.starts_with("复制")) {
-                owned_text = format!("{} | y 复制 | ↑↓ 滚动 | Esc 返回主页", app.update.progress);
+                owned_text = format!("{} | {}", app.update.progress, t("update.footer.done_keys"));
                 &owned_text
+            } else if app.update.has_signature_error() {
+                t("update.footer.signature")
+            } else if can_retry(app) {
+                t("update.footer.failed")
             } else {
-                "更新完成 | y 复制 | ↑↓ 滚动 | Esc 返回主页"
+                t("update.footer.done")
             }
         }
-        UpdatePhase::Analyzing => "AI 正在分析更新内容...",
+        UpdatePhase::Analyzing => t("update.footer.analyzing"),
         UpdatePhase::AnalysisComplete => {
-            if !app.update.progress.is_empty() && (app.update.progress.starts_with('✓') || app.update.progress.starts_with("复制")) {
-                owned_text = format!("{} | Tab 切换视图 | y 复制 | ↑↓ 滚动 | Esc 返回主页 | q 退出", app.update.progress);
+            if let Some(input) = &app.update.chat_input {
+                owned_text = tf("update.footer.chat_input", &[input]);
+                &owned_text
+            } else if app.update.chat_pending {
+                t("update.footer.chat_pending")
+            } else if app.update.progress.starts_with(['✓', '✗']) || app.update.progress.starts_with("复制") {
+                owned_text = format!("{} | {}", app.update.progress, t("update.footer.analysis_keys"));
                 &owned_text
             } else if let Some(path) = &app.update.report_path {
                 owned_text = format!(
-                    "报告已保存: {} | Tab 切换视图 | y 复制 | ↑↓ 滚动 | Esc 返回主页 | q 退出",
-                    path
+                    "{} | {}",
+                    tf("common.report_saved", &[path]),
+                    t("update.footer.analysis_keys")
                 );
                 &owned_text
             } else {
-                "Tab 切换视图 | y 复制 | ↑↓ 滚动 | Esc 返回主页 | q 退出"
+                t("update.footer.analysis")
             }
         }
         UpdatePhase::Error => {
-            if let Some(msg) = &app.error_message {
-                msg
-            } else {
-                "发生错误 | Esc 返回主页 | q 退出"
-            }
+            let retry = if can_retry(app) { t("update.footer.retry") } else { "" };
+            owned_text = match &app.error_message {
+                Some(msg) => tf("update.footer.error_back", &[msg, &retry]),
+                None => tf("update.footer.error", &[&retry]),
+            };
+            &owned_text
         }
     };
 
-    layout::render_footer(f, footer_text, area);
+    let with_usage: String;
+    let footer_text = match (&app.update.phase, &app.update.token_usage) {
+        (UpdatePhase::AnalysisComplete, Some(usage)) if app.update.chat_input.is_none() => {
+            with_usage = format!("{} | {}", usage.footer_text(app.config.token_price), footer_text);
+            &with_usage
+        }
+        _ => footer_text,
+    };
+
+    // 搜索输入中只显示输入框；已有查询时作为前缀显示匹配进度
+    let with_search: String;
+    let footer_text = match app.update.search.footer_text() {
+        Some(search) if app.update.search.is_editing() => {
+            with_search = search;
+            &with_search
+        }
+        Some(search) => {
+            with_search = format!("{} | {}", search, footer_text);
+            &with_search
+        }
+        None => footer_text,
+    };
+
+    if app.update.phase == UpdatePhase::PreviewingUpdates
+        && app.update.is_partial()
+        && !app.update.search.is_editing()
+    {
+        layout::render_danger_footer(f, footer_text, area);
+        return;
+    }
+
+    // 更新中优先显示整体下载进度，其次是当前包的进度
+    let percent = match app.update.phase {
+        UpdatePhase::Updating if !app.update.search.is_editing() => app
+            .update
+            .download_percent()
+            .or(app.update.progress_info.percent),
+        _ => None,
+    };
+    layout::render_progress_footer(f, footer_text, percent, area);
 }